@@ -0,0 +1,165 @@
+//! A.6.7 Case statements
+
+use crate::ast::*;
+
+/// case_statement ::= [ unique_priority ] case_keyword ( case_expression )
+/// case_item { case_item } endcase
+/// | [ unique_priority ] case ( case_expression ) inside
+/// case_inside_item { case_inside_item } endcase
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct CaseStatement {
+    pub unique_priority: Option<UniquePriority>,
+    pub keyword: CaseKeyword,
+    pub expr: Expression,
+    /// `case (...) inside`
+    pub inside: bool,
+    pub items: Vec<CaseItem>,
+    pub span: NodeSpan,
+}
+
+impl Parse for CaseStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = CaseStatement {
+            unique_priority: UniquePriority::parse_optional(parser),
+            keyword: CaseKeyword::parse(parser)?,
+            expr: parse_paren_expression(parser)?,
+            ..CaseStatement::default()
+        };
+        if res.keyword == CaseKeyword::Case && parser.probe(&[Token::Inside]) {
+            parser.advance();
+            res.inside = true;
+        }
+        loop {
+            res.items.push(CaseItem::parse(parser, res.inside)?);
+            if !parser.avail() || parser.probe(&[Token::EndCase]) {
+                break;
+            }
+        }
+        if !parser.probe_err(&[Token::EndCase]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// case_keyword ::= case | casez | casex
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum CaseKeyword {
+    #[default]
+    Case,
+    Casez,
+    Casex,
+}
+
+impl Parse for CaseKeyword {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if !parser.probe_err(&[Token::Case, Token::Casez, Token::Casex]) {
+            return None;
+        }
+        let res = match parser.peek()?.token {
+            Token::Casez => CaseKeyword::Casez,
+            Token::Casex => CaseKeyword::Casex,
+            _ => CaseKeyword::Case,
+        };
+        parser.advance();
+        Some(res)
+    }
+}
+
+/// case_item ::= case_item_expression { , case_item_expression } : statement_or_null
+/// | default [ : ] statement_or_null
+/// case_inside_item ::= open_range_list : statement_or_null
+/// | default [ : ] statement_or_null
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct CaseItem {
+    /// Empty for `default`
+    pub labels: Vec<ValueRange>,
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+impl CaseItem {
+    /// Ranges `[a:b]` are only allowed in `case inside`
+    fn parse(parser: &mut Parser<'_>, inside: bool) -> Option<Self> {
+        let first = parser.start();
+        let mut res = CaseItem::default();
+        if parser.probe(&[Token::Default]) {
+            parser.advance();
+            if parser.probe(&[Token::Colon]) {
+                parser.advance();
+            }
+        } else {
+            loop {
+                res.labels.push(if inside {
+                    ValueRange::parse(parser)?
+                } else {
+                    let first = parser.start();
+                    let from = Expression::parse(parser)?;
+                    ValueRange {
+                        from,
+                        to: None,
+                        span: parser.span_from(first),
+                    }
+                });
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+            if !parser.probe_err(&[Token::Colon]) {
+                return None;
+            }
+            parser.advance();
+        }
+        res.statement = Statement::parse(parser)?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+spanned!(CaseStatement, CaseItem);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case() {
+        let mut parser =
+            Parser::from("priority casez (s) 2'b1z, 2'b01: y = a; default y = 0; endcase");
+        let statement = CaseStatement::parse(&mut parser).unwrap();
+        assert_eq!(statement.unique_priority, Some(UniquePriority::Priority));
+        assert_eq!(statement.keyword, CaseKeyword::Casez);
+        assert!(!statement.inside);
+        assert_eq!(statement.items.len(), 2);
+        assert_eq!(statement.items[0].labels.len(), 2);
+        assert!(statement.items[1].labels.is_empty());
+        assert_eq!(statement.span, NodeSpan { first: 0, last: 18 });
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("case (s) inside [0:3], 7: ; default: ; endcase");
+        let statement = CaseStatement::parse(&mut parser).unwrap();
+        assert!(statement.inside);
+        assert!(statement.items[0].labels[0].to.is_some());
+        assert!(statement.items[0].labels[1].to.is_none());
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("case (s) inside 1: ; default begin a = 0; end endcase");
+        let statement = CaseStatement::parse(&mut parser).unwrap();
+        assert!(statement.items[1].labels.is_empty());
+        assert!(matches!(
+            statement.items[1].statement.item,
+            StatementItem::SeqBlock(_)
+        ));
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("case (s) 0: ;");
+        assert_eq!(CaseStatement::parse(&mut parser), None);
+        assert_eq!(parser.get_diag().len(), 1);
+    }
+}
@@ -0,0 +1,96 @@
+//! A.6.6 Conditional statements
+
+use crate::ast::*;
+
+/// conditional_statement ::= [ unique_priority ] if ( cond_predicate ) statement_or_null
+/// [ else statement_or_null ]
+///
+/// `else if` is an `else` whose statement is a conditional statement.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ConditionalStatement {
+    pub unique_priority: Option<UniquePriority>,
+    pub condition: Expression,
+    pub if_true: Statement,
+    pub if_false: Option<Statement>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ConditionalStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let unique_priority = UniquePriority::parse_optional(parser);
+        if !parser.probe_err(&[Token::If]) {
+            return None;
+        }
+        parser.advance();
+        let condition = parse_paren_expression(parser)?;
+        let if_true = Statement::parse(parser)?;
+        let if_false = if parser.probe(&[Token::Else]) {
+            parser.advance();
+            Some(Statement::parse(parser)?)
+        } else {
+            None
+        };
+        Some(ConditionalStatement {
+            unique_priority,
+            condition,
+            if_true,
+            if_false,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// unique_priority ::= unique | unique0 | priority
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum UniquePriority {
+    Unique,
+    Unique0,
+    Priority,
+}
+
+impl UniquePriority {
+    /// [ unique_priority ]
+    pub(crate) fn parse_optional(parser: &mut Parser<'_>) -> Option<Self> {
+        let res = match parser.peek()?.token {
+            Token::Unique => UniquePriority::Unique,
+            Token::Unique0 => UniquePriority::Unique0,
+            Token::Priority => UniquePriority::Priority,
+            _ => return None,
+        };
+        parser.advance();
+        Some(res)
+    }
+}
+
+spanned!(ConditionalStatement);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_else() {
+        let mut parser = Parser::from("unique if (a) x = 1; else if (b) x = 2; else ;");
+        let statement = ConditionalStatement::parse(&mut parser).unwrap();
+        assert_eq!(statement.unique_priority, Some(UniquePriority::Unique));
+        assert!(matches!(statement.if_true.item, StatementItem::Blocking(_)));
+        match &statement.if_false.as_ref().unwrap().item {
+            StatementItem::Conditional(nested) => {
+                assert_eq!(nested.unique_priority, None);
+                assert_eq!(nested.if_false.as_ref().unwrap().item, StatementItem::Null);
+            }
+            item => panic!("{:?}", item),
+        }
+        assert_eq!(statement.span, NodeSpan { first: 0, last: 19 });
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("if a x = 1;");
+        assert_eq!(ConditionalStatement::parse(&mut parser), None);
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::UnexpectedTokens(vec![Token::LParen], "a".to_owned())
+        );
+    }
+}
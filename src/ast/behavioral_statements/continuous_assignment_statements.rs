@@ -0,0 +1,102 @@
+//! A.6.1 Continuous assignment and net alias statements
+
+use crate::ast::*;
+
+/// continuous_assign ::= assign [ drive_strength ] [ delay3 ] list_of_net_assignments ;
+/// | assign [ delay_control ] list_of_variable_assignments ;
+/// list_of_net_assignments ::= net_assignment { , net_assignment }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ContinuousAssign {
+    pub strength: Option<DriveStrength>,
+    pub delay: Option<Delay3>,
+    pub assignments: Vec<NetAssignment>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ContinuousAssign {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Assign]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = ContinuousAssign::default();
+        if parser.probe(&[Token::LParen]) && parser.probe_next(DriveStrength::FIRST) {
+            res.strength = Some(DriveStrength::parse(parser)?);
+        }
+        if parser.probe(&[Token::Sharp]) {
+            res.delay = Some(Delay3::parse(parser)?);
+        }
+        loop {
+            res.assignments.push(NetAssignment::parse(parser)?);
+            if !parser.probe(&[Token::Comma]) {
+                break;
+            }
+            parser.advance();
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// net_assignment ::= net_lvalue = expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct NetAssignment {
+    pub lvalue: Expression,
+    pub rvalue: Expression,
+    pub span: NodeSpan,
+}
+
+impl Parse for NetAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let lvalue = Expression::parse_postfix(parser)?;
+        if !parser.probe_err(&[Token::Equal]) {
+            return None;
+        }
+        parser.advance();
+        let rvalue = Expression::parse(parser)?;
+        Some(NetAssignment {
+            lvalue,
+            rvalue,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+spanned!(ContinuousAssign, NetAssignment);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuous_assign() {
+        let mut parser = Parser::from("assign (weak1, strong0) #(1, 2, 3) {o, c} = a + b, d = 0;");
+        let assign = ContinuousAssign::parse(&mut parser).unwrap();
+        let strength = assign.strength.as_ref().unwrap();
+        assert_eq!(strength.strength0, Strength::Strong);
+        assert_eq!(strength.strength1, Strength::Weak);
+        assert_eq!(assign.delay.as_ref().unwrap().values.len(), 3);
+        assert_eq!(assign.assignments.len(), 2);
+        assert!(matches!(
+            assign.assignments[0].lvalue,
+            Expression::Concatenation(_)
+        ));
+        assert_eq!(assign.span, NodeSpan { first: 0, last: 27 });
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("assign #5 a = b;");
+        let assign = ContinuousAssign::parse(&mut parser).unwrap();
+        assert_eq!(assign.strength, None);
+        assert_eq!(assign.delay.as_ref().unwrap().values.len(), 1);
+
+        let mut parser = Parser::from("assign (weak0, pull0) a = b;");
+        assert_eq!(ContinuousAssign::parse(&mut parser), None);
+        assert_eq!(parser.get_diag().len(), 1);
+    }
+}
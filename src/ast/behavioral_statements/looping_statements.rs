@@ -0,0 +1,354 @@
+//! A.6.8 Looping statements
+
+use crate::ast::*;
+
+/// loop_statement ::= forever statement_or_null
+/// | repeat ( expression ) statement_or_null
+/// | while ( expression ) statement_or_null
+/// | do statement_or_null while ( expression ) ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct LoopStatement {
+    pub kind: LoopKind,
+    /// `None` for `forever`
+    pub condition: Option<Expression>,
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum LoopKind {
+    #[default]
+    Forever,
+    Repeat,
+    While,
+    DoWhile,
+}
+
+impl LoopStatement {
+    pub(crate) const FIRST: &'static [Token] =
+        &[Token::Forever, Token::Repeat, Token::While, Token::Do];
+}
+
+impl Parse for LoopStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(Self::FIRST) {
+            return None;
+        }
+        let mut res = LoopStatement {
+            kind: match parser.peek()?.token {
+                Token::Repeat => LoopKind::Repeat,
+                Token::While => LoopKind::While,
+                Token::Do => LoopKind::DoWhile,
+                _ => LoopKind::Forever,
+            },
+            ..LoopStatement::default()
+        };
+        parser.advance();
+        match res.kind {
+            LoopKind::Forever => res.statement = Statement::parse(parser)?,
+            LoopKind::Repeat | LoopKind::While => {
+                res.condition = Some(parse_paren_expression(parser)?);
+                res.statement = Statement::parse(parser)?;
+            }
+            LoopKind::DoWhile => {
+                res.statement = Statement::parse(parser)?;
+                if !parser.probe_err(&[Token::While]) {
+                    return None;
+                }
+                parser.advance();
+                res.condition = Some(parse_paren_expression(parser)?);
+                if !parser.probe_err(&[Token::Semicolon]) {
+                    return None;
+                }
+                parser.advance();
+            }
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// for ( [ for_initialization ] ; [ expression ] ; [ for_step ] ) statement_or_null
+/// for_initialization ::= list_of_variable_assignments
+/// | for_variable_declaration { , for_variable_declaration }
+/// for_step ::= for_step_assignment { , for_step_assignment }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ForStatement {
+    pub initializations: Vec<ForInitialization>,
+    pub condition: Option<Expression>,
+    pub steps: Vec<ForStep>,
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+impl Parse for ForStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::For]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = ForStatement::default();
+        if !parser.probe(&[Token::Semicolon]) {
+            loop {
+                res.initializations.push(ForInitialization::parse(parser)?);
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe(&[Token::Semicolon]) {
+            res.condition = Some(Expression::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe(&[Token::RParen]) {
+            loop {
+                res.steps.push(ForStep::parse(parser)?);
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+        }
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        res.statement = Statement::parse(parser)?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// for_variable_declaration ::= [ var ] data_type variable_identifier = expression
+/// variable_assignment ::= variable_lvalue = expression
+///
+/// `data_type` is only present for declarations.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ForInitialization {
+    pub data_type: Option<DataType>,
+    pub lvalue: Expression,
+    pub rvalue: Expression,
+    pub span: NodeSpan,
+}
+
+impl Parse for ForInitialization {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = ForInitialization::default();
+        if parser.probe(&[Token::Var]) {
+            parser.advance();
+            res.data_type = Some(DataType::parse(parser)?);
+        } else if DataType::is_data_type(parser) {
+            res.data_type = Some(DataType::parse(parser)?);
+        }
+        res.lvalue = if res.data_type.is_some() {
+            Expression::Name(Identifier::parse(parser)?)
+        } else {
+            Expression::parse_postfix(parser)?
+        };
+        if !parser.probe_err(&[Token::Equal]) {
+            return None;
+        }
+        parser.advance();
+        res.rvalue = Expression::parse(parser)?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// for_step_assignment ::= operator_assignment | inc_or_dec_expression | function_subroutine_call
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum ForStep {
+    Assignment(OperatorAssignment),
+    IncOrDec(IncOrDecExpression),
+    Call(Expression),
+}
+
+impl Parse for ForStep {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if parser.probe(&[Token::OpIncrement, Token::OpDecrement]) {
+            return Some(ForStep::IncOrDec(IncOrDecExpression::parse(parser)?));
+        }
+        let first = parser.start();
+        let lvalue = Expression::parse_postfix(parser)?;
+        if parser.probe(&[Token::OpIncrement, Token::OpDecrement]) {
+            Some(ForStep::IncOrDec(IncOrDecExpression::parse_suffix(
+                parser, first, lvalue,
+            )?))
+        } else if matches!(lvalue, Expression::Call(_) | Expression::SystemCall(_))
+            && parser.probe(&[Token::Comma, Token::RParen])
+        {
+            Some(ForStep::Call(lvalue))
+        } else {
+            Some(ForStep::Assignment(OperatorAssignment::parse_operator(
+                parser, first, lvalue,
+            )?))
+        }
+    }
+}
+
+impl Spanned for ForStep {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            ForStep::Assignment(step) => step.node_span(),
+            ForStep::IncOrDec(step) => step.node_span(),
+            ForStep::Call(step) => step.node_span(),
+        }
+    }
+}
+
+/// foreach ( ps_or_hierarchical_array_identifier [ loop_variables ] ) statement
+/// loop_variables ::= [ index_variable_identifier ] { , [ index_variable_identifier ] }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ForeachStatement {
+    pub array: Expression,
+    /// `None` for a skipped dimension
+    pub variables: Vec<Option<Identifier>>,
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+impl Parse for ForeachStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Foreach]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let array_first = parser.start();
+        let mut res = ForeachStatement {
+            array: Expression::Name(Identifier::parse(parser)?),
+            ..ForeachStatement::default()
+        };
+        while parser.probe(&[Token::Dot]) {
+            parser.advance();
+            let member = Identifier::parse(parser)?;
+            res.array = Expression::Member(Box::new(MemberSelect {
+                value: res.array,
+                member,
+                span: parser.span_from(array_first),
+            }));
+        }
+        if !parser.probe_err(&[Token::LBracket]) {
+            return None;
+        }
+        parser.advance();
+        loop {
+            if parser.probe(&[Token::Identifier]) {
+                res.variables.push(Some(Identifier::parse(parser)?));
+            } else {
+                res.variables.push(None);
+            }
+            if !parser.probe(&[Token::Comma]) {
+                break;
+            }
+            parser.advance();
+        }
+        if !parser.probe_err(&[Token::RBracket]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        res.statement = Statement::parse(parser)?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+spanned!(
+    LoopStatement,
+    ForStatement,
+    ForInitialization,
+    ForeachStatement
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loops() {
+        let mut parser = Parser::from(
+            "forever #1 clk = ~clk; repeat (4) @(posedge clk); do i++; while (i < 8);",
+        );
+        let statement = LoopStatement::parse(&mut parser).unwrap();
+        assert_eq!(statement.kind, LoopKind::Forever);
+        assert_eq!(statement.condition, None);
+        let statement = LoopStatement::parse(&mut parser).unwrap();
+        assert_eq!(statement.kind, LoopKind::Repeat);
+        assert!(matches!(
+            statement.statement.item,
+            StatementItem::TimingControl(_)
+        ));
+        let statement = LoopStatement::parse(&mut parser).unwrap();
+        assert_eq!(statement.kind, LoopKind::DoWhile);
+        assert!(matches!(
+            statement.statement.item,
+            StatementItem::IncOrDec(_)
+        ));
+        assert!(matches!(statement.condition, Some(Expression::Binary(_))));
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+    }
+
+    #[test]
+    fn for_loop() {
+        let mut parser = Parser::from("for (bit [3:0] i = 0, j = 1; i < 8; i++, j += 2, --k) ;");
+        let statement = ForStatement::parse(&mut parser).unwrap();
+        assert_eq!(statement.initializations.len(), 2);
+        assert!(statement.initializations[0].data_type.is_some());
+        assert!(statement.initializations[1].data_type.is_none());
+        assert!(statement.condition.is_some());
+        assert!(matches!(&statement.steps[0], ForStep::IncOrDec(step) if !step.prefix));
+        assert!(
+            matches!(&statement.steps[1], ForStep::Assignment(step) if step.op == AssignmentOperator::AddAssign)
+        );
+        assert!(matches!(&statement.steps[2], ForStep::IncOrDec(step) if step.prefix));
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("for (;;) ;");
+        let statement = ForStatement::parse(&mut parser).unwrap();
+        assert!(statement.initializations.is_empty());
+        assert!(statement.condition.is_none());
+        assert!(statement.steps.is_empty());
+    }
+
+    #[test]
+    fn foreach() {
+        let mut parser = Parser::from("foreach (s.mem[i, , k]) mem[i][k] = 0;");
+        let statement = ForeachStatement::parse(&mut parser).unwrap();
+        assert!(matches!(statement.array, Expression::Member(_)));
+        assert_eq!(
+            statement.variables,
+            vec![
+                Some(Identifier { token: 6 }),
+                None,
+                Some(Identifier { token: 9 })
+            ]
+        );
+        assert_eq!(statement.span, NodeSpan { first: 0, last: 21 });
+        assert!(parser.get_diag().is_empty());
+    }
+}
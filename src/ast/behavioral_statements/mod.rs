@@ -0,0 +1,21 @@
+//! A.6 Behavioral statements
+
+mod case_statements;
+mod conditional_statements;
+mod continuous_assignment_statements;
+mod looping_statements;
+mod parallel_and_sequential_blocks;
+mod patterns;
+mod procedural_blocks;
+mod statements;
+mod timing_control_statements;
+
+pub use case_statements::*;
+pub use conditional_statements::*;
+pub use continuous_assignment_statements::*;
+pub use looping_statements::*;
+pub use parallel_and_sequential_blocks::*;
+pub use patterns::*;
+pub use procedural_blocks::*;
+pub use statements::*;
+pub use timing_control_statements::*;
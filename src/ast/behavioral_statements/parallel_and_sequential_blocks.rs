@@ -0,0 +1,175 @@
+//! A.6.3 Parallel and sequential blocks
+
+use crate::ast::*;
+
+/// seq_block ::= begin [ : block_identifier ] { statement_or_null } end [ : block_identifier ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SeqBlock {
+    pub label: Option<Identifier>,
+    pub statements: Vec<Statement>,
+    pub end_label: Option<Identifier>,
+    pub span: NodeSpan,
+}
+
+impl Parse for SeqBlock {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Begin]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = SeqBlock {
+            label: parse_block_label(parser)?,
+            ..SeqBlock::default()
+        };
+        while parser.avail() && !parser.probe(&[Token::End]) {
+            res.statements.push(Statement::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::End]) {
+            return None;
+        }
+        parser.advance();
+        res.end_label = parse_end_label(parser, res.label.as_ref())?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// par_block ::= fork [ : block_identifier ] { statement_or_null } join_keyword [ : block_identifier ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ParBlock {
+    pub label: Option<Identifier>,
+    pub statements: Vec<Statement>,
+    pub join: JoinKeyword,
+    pub end_label: Option<Identifier>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ParBlock {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Fork]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = ParBlock {
+            label: parse_block_label(parser)?,
+            ..ParBlock::default()
+        };
+        let joins = [Token::Join, Token::JoinAny, Token::JoinNone];
+        while parser.avail() && !parser.probe(&joins) {
+            res.statements.push(Statement::parse(parser)?);
+        }
+        if !parser.probe_err(&joins) {
+            return None;
+        }
+        res.join = match parser.peek()?.token {
+            Token::JoinAny => JoinKeyword::JoinAny,
+            Token::JoinNone => JoinKeyword::JoinNone,
+            _ => JoinKeyword::Join,
+        };
+        parser.advance();
+        res.end_label = parse_end_label(parser, res.label.as_ref())?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// join_keyword ::= join | join_any | join_none
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum JoinKeyword {
+    #[default]
+    Join,
+    JoinAny,
+    JoinNone,
+}
+
+/// [ : block_identifier ]
+pub(crate) fn parse_block_label(parser: &mut Parser<'_>) -> Option<Option<Identifier>> {
+    if parser.probe(&[Token::Colon]) {
+        parser.advance();
+        return Some(Some(Identifier::parse(parser)?));
+    }
+    Some(None)
+}
+
+/// [ : block_identifier ] after the end of a block, which must be the
+/// name given at its start
+pub(crate) fn parse_end_label(
+    parser: &mut Parser<'_>,
+    name: Option<&Identifier>,
+) -> Option<Option<Identifier>> {
+    if !parser.probe(&[Token::Colon]) {
+        return Some(None);
+    }
+    parser.advance();
+    let from = parser.location_from();
+    let to = parser.location_to();
+    let label = Identifier::parse(parser)?;
+    let text = parser.get_token(label.token).text.to_string();
+    let name = name.map(|name| parser.get_token(name.token).text.to_string());
+    if name.as_ref() != Some(&text) {
+        parser.err(from, to, Message::EndLabelMismatch(text, name));
+    }
+    Some(Some(label))
+}
+
+spanned!(SeqBlock, ParBlock);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seq_block() {
+        let mut parser = Parser::from("begin a = 1; ; begin end end");
+        let block = SeqBlock::parse(&mut parser).unwrap();
+        assert_eq!(block.statements.len(), 3);
+        assert_eq!(block.statements[1].item, StatementItem::Null);
+        assert_eq!(block.span, NodeSpan { first: 0, last: 8 });
+
+        let mut parser = Parser::from("begin : outer a = 1; end : outer");
+        let block = SeqBlock::parse(&mut parser).unwrap();
+        assert_eq!(block.label, Some(Identifier { token: 2 }));
+        assert_eq!(block.end_label, Some(Identifier { token: 9 }));
+        assert!(!parser.avail());
+
+        for (text, msg) in [
+            (
+                "begin : l a = 1; end : other",
+                Message::EndLabelMismatch("other".to_owned(), Some("l".to_owned())),
+            ),
+            (
+                "begin end : l",
+                Message::EndLabelMismatch("l".to_owned(), None),
+            ),
+        ] {
+            let mut parser = Parser::from(text);
+            assert!(SeqBlock::parse(&mut parser).is_some());
+            let msgs: Vec<Message> = parser.get_diag().iter().map(|d| d.msg.clone()).collect();
+            assert_eq!(msgs, vec![msg]);
+        }
+
+        let mut parser = Parser::from("begin a = 1;");
+        assert_eq!(SeqBlock::parse(&mut parser), None);
+        assert_eq!(parser.get_diag().len(), 1);
+    }
+
+    #[test]
+    fn par_block() {
+        let mut parser = Parser::from("fork : f #1 a = 1; b = 2; join_none : f");
+        let block = ParBlock::parse(&mut parser).unwrap();
+        assert_eq!(block.statements.len(), 2);
+        assert_eq!(block.join, JoinKeyword::JoinNone);
+        assert!(block.label.is_some() && block.end_label.is_some());
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("fork : f join : g");
+        assert!(ParBlock::parse(&mut parser).is_some());
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::EndLabelMismatch("g".to_owned(), Some("f".to_owned()))
+        );
+    }
+}
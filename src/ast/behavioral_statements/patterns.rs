@@ -0,0 +1,105 @@
+//! A.6.7.1 Patterns
+
+use crate::ast::*;
+
+/// assignment_pattern_expression ::= [ assignment_pattern_expression_type ] assignment_pattern
+/// assignment_pattern ::= '{ expression { , expression } }
+/// | '{ structure_pattern_key : expression { , structure_pattern_key : expression } }
+/// | '{ array_pattern_key : expression { , array_pattern_key : expression } }
+/// | '{ constant_expression { expression { , expression } } }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct AssignmentPattern {
+    pub pattern_type: Option<Expression>,
+    /// Count of `'{ n { ... } }`
+    pub replication: Option<Expression>,
+    pub items: Vec<PatternItem>,
+    pub span: NodeSpan,
+}
+
+impl Parse for AssignmentPattern {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::ApostropheLBraces]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = AssignmentPattern::default();
+        if !parser.probe(&[Token::RBraces]) {
+            let item = PatternItem::parse(parser)?;
+            if item.key.is_none() && parser.probe(&[Token::LBraces]) {
+                let concatenation = Concatenation::parse(parser)?;
+                res.replication = Some(item.value);
+                res.items = concatenation
+                    .exprs
+                    .into_iter()
+                    .map(|value| PatternItem {
+                        key: None,
+                        span: value.span(),
+                        value,
+                    })
+                    .collect();
+            } else {
+                res.items.push(item);
+                while parser.probe(&[Token::Comma]) {
+                    parser.advance();
+                    res.items.push(PatternItem::parse(parser)?);
+                }
+            }
+        }
+        if !parser.probe_err(&[Token::RBraces]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// structure_pattern_key : expression | array_pattern_key : expression | expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct PatternItem {
+    pub key: Option<PatternKey>,
+    pub value: Expression,
+    pub span: NodeSpan,
+}
+
+/// structure_pattern_key ::= member_identifier | assignment_pattern_key
+/// array_pattern_key ::= constant_expression | assignment_pattern_key
+/// assignment_pattern_key ::= simple_type | default
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum PatternKey {
+    Default,
+    Expression(Expression),
+}
+
+impl Parse for PatternItem {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let key = if parser.probe(&[Token::Default]) {
+            parser.advance();
+            if !parser.probe_err(&[Token::Colon]) {
+                return None;
+            }
+            Some(PatternKey::Default)
+        } else {
+            let expr = Expression::parse(parser)?;
+            if !parser.probe(&[Token::Colon]) {
+                return Some(PatternItem {
+                    key: None,
+                    value: expr,
+                    span: parser.span_from(first),
+                });
+            }
+            Some(PatternKey::Expression(expr))
+        };
+        parser.advance();
+        let value = Expression::parse(parser)?;
+        Some(PatternItem {
+            key,
+            value,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+spanned!(AssignmentPattern, PatternItem);
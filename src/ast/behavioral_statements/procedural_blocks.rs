@@ -0,0 +1,243 @@
+//! A.6.2 Procedural blocks and assignments
+
+use crate::ast::*;
+
+/// initial_construct ::= initial statement_or_null
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct InitialConstruct {
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+impl Parse for InitialConstruct {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Initial]) {
+            return None;
+        }
+        parser.advance();
+        let statement = Statement::parse(parser)?;
+        Some(InitialConstruct {
+            statement,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// always_construct ::= always_keyword statement
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct AlwaysConstruct {
+    pub keyword: AlwaysKeyword,
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+impl Parse for AlwaysConstruct {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let keyword = AlwaysKeyword::parse(parser)?;
+        let statement = Statement::parse(parser)?;
+        Some(AlwaysConstruct {
+            keyword,
+            statement,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// always_keyword ::= always | always_comb | always_latch | always_ff
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum AlwaysKeyword {
+    #[default]
+    Always,
+    AlwaysComb,
+    AlwaysLatch,
+    AlwaysFf,
+}
+
+impl Parse for AlwaysKeyword {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if parser.probe_err(&[
+            Token::Always,
+            Token::AlwaysComb,
+            Token::AlwaysLatch,
+            Token::AlwaysFf,
+        ]) {
+            let res = match parser.peek()?.token {
+                Token::AlwaysComb => AlwaysKeyword::AlwaysComb,
+                Token::AlwaysLatch => AlwaysKeyword::AlwaysLatch,
+                Token::AlwaysFf => AlwaysKeyword::AlwaysFf,
+                _ => AlwaysKeyword::Always,
+            };
+            parser.advance();
+            return Some(res);
+        }
+        None
+    }
+}
+
+/// final_construct ::= final function_statement
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct FinalConstruct {
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+impl Parse for FinalConstruct {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Final]) {
+            return None;
+        }
+        parser.advance();
+        let statement = Statement::parse(parser)?;
+        Some(FinalConstruct {
+            statement,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// blocking_assignment ::= variable_lvalue = [ delay_or_event_control ] expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct BlockingAssignment {
+    pub lvalue: Expression,
+    /// Intra-assignment timing control
+    pub control: Option<DelayOrEventControl>,
+    pub rvalue: Expression,
+    pub span: NodeSpan,
+}
+
+/// nonblocking_assignment ::= variable_lvalue <= [ delay_or_event_control ] expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct NonblockingAssignment {
+    pub lvalue: Expression,
+    /// Intra-assignment timing control
+    pub control: Option<DelayOrEventControl>,
+    pub rvalue: Expression,
+    pub span: NodeSpan,
+}
+
+/// operator_assignment ::= variable_lvalue assignment_operator expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct OperatorAssignment {
+    pub lvalue: Expression,
+    pub op: AssignmentOperator,
+    pub rvalue: Expression,
+    pub span: NodeSpan,
+}
+
+impl Parse for OperatorAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let lvalue = Expression::parse_postfix(parser)?;
+        Self::parse_operator(parser, first, lvalue)
+    }
+}
+
+impl OperatorAssignment {
+    /// The operator and right-hand side after `lvalue`, which starts at
+    /// `first`
+    pub(crate) fn parse_operator(
+        parser: &mut Parser<'_>,
+        first: usize,
+        lvalue: Expression,
+    ) -> Option<Self> {
+        let op = match parser
+            .peek()
+            .and_then(|token| AssignmentOperator::from_token(token.token))
+        {
+            Some(op) => op,
+            None => {
+                parser.probe_err(&[Token::Equal]);
+                return None;
+            }
+        };
+        parser.advance();
+        let rvalue = Expression::parse(parser)?;
+        Some(OperatorAssignment {
+            lvalue,
+            op,
+            rvalue,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// assignment_operator ::= = | += | -= | *= | /= | %= | &= | |= | ^= | <<= | >>= | <<<= | >>>=
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum AssignmentOperator {
+    #[default]
+    Assign,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    ModAssign,
+    AndAssign,
+    OrAssign,
+    XorAssign,
+    LeftShiftAssign,
+    RightShiftAssign,
+    ArithLeftShiftAssign,
+    ArithRightShiftAssign,
+}
+
+impl AssignmentOperator {
+    pub fn from_token(token: Token) -> Option<Self> {
+        Some(match token {
+            Token::Equal => AssignmentOperator::Assign,
+            Token::OpAddAssign => AssignmentOperator::AddAssign,
+            Token::OpSubAssign => AssignmentOperator::SubAssign,
+            Token::OpMulAssign => AssignmentOperator::MulAssign,
+            Token::OpDivAssign => AssignmentOperator::DivAssign,
+            Token::OpModAssign => AssignmentOperator::ModAssign,
+            Token::OpAndAssign => AssignmentOperator::AndAssign,
+            Token::OpOrAssign => AssignmentOperator::OrAssign,
+            Token::OpXorAssign => AssignmentOperator::XorAssign,
+            Token::OpLeftShiftAssign => AssignmentOperator::LeftShiftAssign,
+            Token::OpRightShiftAssign => AssignmentOperator::RightShiftAssign,
+            Token::OpArithLeftShiftAssign => AssignmentOperator::ArithLeftShiftAssign,
+            Token::OpArithRightShiftAssign => AssignmentOperator::ArithRightShiftAssign,
+            _ => return None,
+        })
+    }
+}
+
+spanned!(
+    InitialConstruct,
+    AlwaysConstruct,
+    FinalConstruct,
+    BlockingAssignment,
+    NonblockingAssignment,
+    OperatorAssignment
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn procedural_blocks() {
+        let mut parser = Parser::from("always_ff @(posedge clk) q <= d; initial a = 0; final ;");
+        let always = AlwaysConstruct::parse(&mut parser).unwrap();
+        assert_eq!(always.keyword, AlwaysKeyword::AlwaysFf);
+        assert_eq!(always.span, NodeSpan { first: 0, last: 9 });
+        match &always.statement.item {
+            StatementItem::TimingControl(control) => {
+                assert!(matches!(control.control, TimingControl::Event(_)));
+                assert!(matches!(
+                    control.statement.item,
+                    StatementItem::Nonblocking(_)
+                ));
+            }
+            item => panic!("{:?}", item),
+        }
+        let initial = InitialConstruct::parse(&mut parser).unwrap();
+        assert!(matches!(initial.statement.item, StatementItem::Blocking(_)));
+        let final_construct = FinalConstruct::parse(&mut parser).unwrap();
+        assert_eq!(final_construct.statement.item, StatementItem::Null);
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+//! A.6.4 Statements
+
+use crate::ast::*;
+
+/// statement_or_null ::= statement | { attribute_instance } ;
+/// statement ::= [ block_identifier : ] { attribute_instance } statement_item
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Statement {
+    pub label: Option<Identifier>,
+    pub attributes: Attributes,
+    pub item: StatementItem,
+    pub span: NodeSpan,
+}
+
+/// statement_item ::= blocking_assignment ;
+/// | nonblocking_assignment ;
+/// | operator_assignment ;
+/// | inc_or_dec_expression ;
+/// | subroutine_call_statement
+/// | disable_statement
+/// | event_trigger
+/// | jump_statement
+/// | par_block
+/// | procedural_timing_control_statement
+/// | seq_block
+/// | wait_statement
+/// | conditional_statement
+/// | case_statement
+/// | loop_statement
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum StatementItem {
+    /// `;`
+    #[default]
+    Null,
+    Blocking(Box<BlockingAssignment>),
+    Nonblocking(Box<NonblockingAssignment>),
+    Operator(Box<OperatorAssignment>),
+    IncOrDec(Box<IncOrDecExpression>),
+    /// subroutine_call_statement ::= subroutine_call ;
+    SubroutineCall(Expression),
+    Disable(Box<DisableStatement>),
+    EventTrigger(Box<EventTrigger>),
+    Jump(Box<JumpStatement>),
+    ParBlock(Box<ParBlock>),
+    TimingControl(Box<ProceduralTimingControlStatement>),
+    SeqBlock(Box<SeqBlock>),
+    Wait(Box<WaitStatement>),
+    Conditional(Box<ConditionalStatement>),
+    Case(Box<CaseStatement>),
+    Loop(Box<LoopStatement>),
+    For(Box<ForStatement>),
+    Foreach(Box<ForeachStatement>),
+}
+
+impl Parse for Statement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = Statement::default();
+        if parser.probe(&[Token::Identifier]) && parser.probe_next(&[Token::Colon]) {
+            res.label = Some(Identifier::parse(parser)?);
+            parser.advance();
+        }
+        if parser.probe(&[Token::LParen]) && parser.probe_next(&[Token::OpMultiply]) {
+            res.attributes = Attributes::parse(parser)?;
+        }
+        res.item = match parser.peek().map(|token| token.token) {
+            Some(Token::Semicolon) => {
+                parser.advance();
+                StatementItem::Null
+            }
+            Some(Token::Begin) => StatementItem::SeqBlock(Box::new(SeqBlock::parse(parser)?)),
+            Some(Token::Fork) => StatementItem::ParBlock(Box::new(ParBlock::parse(parser)?)),
+            Some(Token::Sharp | Token::At) => StatementItem::TimingControl(Box::new(
+                ProceduralTimingControlStatement::parse(parser)?,
+            )),
+            Some(Token::If) => {
+                StatementItem::Conditional(Box::new(ConditionalStatement::parse(parser)?))
+            }
+            Some(Token::Unique | Token::Unique0 | Token::Priority)
+                if parser.probe_next(&[Token::If]) =>
+            {
+                StatementItem::Conditional(Box::new(ConditionalStatement::parse(parser)?))
+            }
+            Some(
+                Token::Unique
+                | Token::Unique0
+                | Token::Priority
+                | Token::Case
+                | Token::Casez
+                | Token::Casex,
+            ) => StatementItem::Case(Box::new(CaseStatement::parse(parser)?)),
+            Some(Token::Forever | Token::Repeat | Token::While | Token::Do) => {
+                StatementItem::Loop(Box::new(LoopStatement::parse(parser)?))
+            }
+            Some(Token::For) => StatementItem::For(Box::new(ForStatement::parse(parser)?)),
+            Some(Token::Foreach) => {
+                StatementItem::Foreach(Box::new(ForeachStatement::parse(parser)?))
+            }
+            Some(Token::Return | Token::Break | Token::Continue) => {
+                StatementItem::Jump(Box::new(JumpStatement::parse(parser)?))
+            }
+            Some(Token::Wait) => StatementItem::Wait(Box::new(WaitStatement::parse(parser)?)),
+            Some(Token::Disable) => {
+                StatementItem::Disable(Box::new(DisableStatement::parse(parser)?))
+            }
+            Some(Token::OpImplication | Token::OpNonBlockingTrigger) => {
+                StatementItem::EventTrigger(Box::new(EventTrigger::parse(parser)?))
+            }
+            _ => {
+                let item = Self::parse_simple(parser)?;
+                if !parser.probe_err(&[Token::Semicolon]) {
+                    return None;
+                }
+                parser.advance();
+                item
+            }
+        };
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl Statement {
+    /// Assignments, increments and subroutine calls, without the `;`
+    ///
+    /// The lvalue is a primary, so `<=` is not parsed as a comparison.
+    fn parse_simple(parser: &mut Parser<'_>) -> Option<StatementItem> {
+        let first = parser.start();
+        if parser.probe(&[Token::OpIncrement, Token::OpDecrement]) {
+            return Some(StatementItem::IncOrDec(Box::new(
+                IncOrDecExpression::parse(parser)?,
+            )));
+        }
+        let lvalue = Expression::parse_postfix(parser)?;
+        if parser.probe(&[Token::Semicolon])
+            && matches!(
+                lvalue,
+                Expression::Name(_)
+                    | Expression::Member(_)
+                    | Expression::Call(_)
+                    | Expression::SystemCall(_)
+            )
+        {
+            return Some(StatementItem::SubroutineCall(lvalue));
+        }
+        if parser.probe(&[Token::OpIncrement, Token::OpDecrement]) {
+            return Some(StatementItem::IncOrDec(Box::new(
+                IncOrDecExpression::parse_suffix(parser, first, lvalue)?,
+            )));
+        }
+        if !parser.probe(&[Token::Equal, Token::OpLessEqual]) {
+            if parser
+                .peek()
+                .is_some_and(|token| AssignmentOperator::from_token(token.token).is_some())
+            {
+                return Some(StatementItem::Operator(Box::new(
+                    OperatorAssignment::parse_operator(parser, first, lvalue)?,
+                )));
+            }
+            parser.probe_err(&[Token::Equal, Token::OpLessEqual]);
+            return None;
+        }
+        let blocking = parser.probe(&[Token::Equal]);
+        parser.advance();
+        let control = if parser.probe(&[Token::Sharp, Token::At, Token::Repeat]) {
+            Some(DelayOrEventControl::parse(parser)?)
+        } else {
+            None
+        };
+        let rvalue = Expression::parse(parser)?;
+        let span = parser.span_from(first);
+        Some(if blocking {
+            StatementItem::Blocking(Box::new(BlockingAssignment {
+                lvalue,
+                control,
+                rvalue,
+                span,
+            }))
+        } else {
+            StatementItem::Nonblocking(Box::new(NonblockingAssignment {
+                lvalue,
+                control,
+                rvalue,
+                span,
+            }))
+        })
+    }
+}
+
+spanned!(Statement);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assignments() {
+        let mut parser = Parser::from("q[1] <= a <= b; {c, d} = e + 1; (* full *) ;");
+        let statement = Statement::parse(&mut parser).unwrap();
+        match &statement.item {
+            StatementItem::Nonblocking(assign) => {
+                assert!(matches!(assign.lvalue, Expression::Select(_)));
+                assert!(matches!(assign.rvalue, Expression::Binary(_)));
+                assert_eq!(assign.span, NodeSpan { first: 0, last: 7 });
+            }
+            item => panic!("{:?}", item),
+        }
+        assert_eq!(statement.span, NodeSpan { first: 0, last: 8 });
+        let statement = Statement::parse(&mut parser).unwrap();
+        match &statement.item {
+            StatementItem::Blocking(assign) => {
+                assert!(matches!(assign.lvalue, Expression::Concatenation(_)))
+            }
+            item => panic!("{:?}", item),
+        }
+        let statement = Statement::parse(&mut parser).unwrap();
+        assert_eq!(statement.attributes.attrs.len(), 1);
+        assert_eq!(statement.item, StatementItem::Null);
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("a + 1;");
+        assert_eq!(Statement::parse(&mut parser), None);
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::UnexpectedTokens(vec![Token::Equal, Token::OpLessEqual], "+".to_owned())
+        );
+    }
+
+    #[test]
+    fn statement_items() {
+        let mut parser = Parser::from(
+            "step: q = #2 d; q <= repeat (2) @(posedge clk) d; x += 1; i--; $display(i); t.run; \
+             -> ev; ->> #1 ev; return x + 1; break; wait (ready) ; wait fork; disable fork; disable blk;",
+        );
+        let statement = Statement::parse(&mut parser).unwrap();
+        assert_eq!(statement.label, Some(Identifier { token: 0 }));
+        match &statement.item {
+            StatementItem::Blocking(assign) => {
+                assert!(matches!(
+                    assign.control.as_ref().unwrap().control,
+                    TimingControl::Delay(_)
+                ))
+            }
+            item => panic!("{:?}", item),
+        }
+        match &Statement::parse(&mut parser).unwrap().item {
+            StatementItem::Nonblocking(assign) => {
+                assert!(assign.control.as_ref().unwrap().repeat.is_some())
+            }
+            item => panic!("{:?}", item),
+        }
+        let items: Vec<StatementItem> = (0..12)
+            .map(|_| Statement::parse(&mut parser).unwrap().item)
+            .collect();
+        assert!(
+            matches!(&items[0], StatementItem::Operator(assign) if assign.op == AssignmentOperator::AddAssign)
+        );
+        assert!(matches!(&items[1], StatementItem::IncOrDec(expr) if !expr.prefix));
+        assert!(matches!(
+            &items[2],
+            StatementItem::SubroutineCall(Expression::SystemCall(_))
+        ));
+        assert!(matches!(
+            &items[3],
+            StatementItem::SubroutineCall(Expression::Member(_))
+        ));
+        assert!(matches!(&items[4], StatementItem::EventTrigger(trigger) if !trigger.nonblocking));
+        assert!(
+            matches!(&items[5], StatementItem::EventTrigger(trigger) if trigger.control.is_some())
+        );
+        assert!(matches!(&items[6], StatementItem::Jump(jump) if jump.value.is_some()));
+        assert!(matches!(&items[7], StatementItem::Jump(jump) if jump.kind == JumpKind::Break));
+        assert!(matches!(&items[8], StatementItem::Wait(wait) if wait.condition.is_some()));
+        assert!(matches!(&items[9], StatementItem::Wait(wait) if wait.condition.is_none()));
+        assert!(matches!(&items[10], StatementItem::Disable(disable) if disable.target.is_none()));
+        assert!(matches!(&items[11], StatementItem::Disable(disable) if disable.target.is_some()));
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+    }
+}
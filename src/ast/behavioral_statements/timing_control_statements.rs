@@ -0,0 +1,439 @@
+//! A.6.5 Timing control statements
+
+use crate::ast::*;
+
+/// procedural_timing_control_statement ::= procedural_timing_control statement_or_null
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ProceduralTimingControlStatement {
+    pub control: TimingControl,
+    pub statement: Statement,
+    pub span: NodeSpan,
+}
+
+impl Parse for ProceduralTimingControlStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let control = TimingControl::parse(parser)?;
+        let statement = Statement::parse(parser)?;
+        Some(ProceduralTimingControlStatement {
+            control,
+            statement,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// procedural_timing_control ::= delay_control | event_control
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum TimingControl {
+    Delay(DelayControl),
+    Event(EventControl),
+}
+
+impl Default for TimingControl {
+    fn default() -> Self {
+        Self::Delay(DelayControl::default())
+    }
+}
+
+impl Parse for TimingControl {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if !parser.probe_err(&[Token::Sharp, Token::At]) {
+            return None;
+        }
+        if parser.probe(&[Token::Sharp]) {
+            Some(TimingControl::Delay(DelayControl::parse(parser)?))
+        } else {
+            Some(TimingControl::Event(EventControl::parse(parser)?))
+        }
+    }
+}
+
+impl Spanned for TimingControl {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            TimingControl::Delay(delay) => delay.node_span(),
+            TimingControl::Event(event) => event.node_span(),
+        }
+    }
+}
+
+/// delay_control ::= # delay_value | # ( mintypmax_expression )
+/// delay_value ::= unsigned_number | real_number | ps_identifier | time_literal | 1step
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DelayControl {
+    pub value: Expression,
+    pub span: NodeSpan,
+}
+
+impl Parse for DelayControl {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Sharp]) {
+            return None;
+        }
+        parser.advance();
+        // a primary, `#1 -> e` is a delayed event trigger
+        let value = Expression::parse_postfix(parser)?;
+        Some(DelayControl {
+            value,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// delay_or_event_control ::= delay_control | event_control | repeat ( expression ) event_control
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DelayOrEventControl {
+    pub repeat: Option<Expression>,
+    pub control: TimingControl,
+    pub span: NodeSpan,
+}
+
+impl Parse for DelayOrEventControl {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = DelayOrEventControl::default();
+        if parser.probe(&[Token::Repeat]) {
+            parser.advance();
+            res.repeat = Some(parse_paren_expression(parser)?);
+            res.control = TimingControl::Event(EventControl::parse(parser)?);
+        } else {
+            res.control = TimingControl::parse(parser)?;
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// event_control ::= @ hierarchical_event_identifier
+/// | @ ( event_expression )
+/// | @ *
+/// | @ (*)
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct EventControl {
+    /// Events separated by `or` or `,`, empty for the implicit `@*`
+    pub events: Vec<EventExpression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for EventControl {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::At]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = EventControl::default();
+        if parser.probe(&[Token::OpMultiply]) {
+            parser.advance();
+        } else if parser.probe(&[Token::LParen]) {
+            parser.advance();
+            if parser.probe(&[Token::OpMultiply]) {
+                parser.advance();
+            } else {
+                loop {
+                    res.events.push(EventExpression::parse(parser)?);
+                    if parser.probe(&[Token::Or, Token::Comma]) {
+                        parser.advance();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            if !parser.probe_err(&[Token::RParen]) {
+                return None;
+            }
+            parser.advance();
+        } else {
+            let start = parser.start();
+            let expr = Expression::parse_postfix(parser)?;
+            res.events.push(EventExpression {
+                edge: None,
+                expr,
+                iff: None,
+                span: parser.span_from(start),
+            });
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// event_expression ::= [ edge_identifier ] expression [ iff expression ]
+/// | event_expression or event_expression
+/// | event_expression , event_expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct EventExpression {
+    pub edge: Option<EdgeIdentifier>,
+    pub expr: Expression,
+    pub iff: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for EventExpression {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = EventExpression::default();
+        if parser.probe(&[Token::PosEdge, Token::NegEdge, Token::Edge]) {
+            res.edge = match parser.peek()?.token {
+                Token::PosEdge => Some(EdgeIdentifier::PosEdge),
+                Token::NegEdge => Some(EdgeIdentifier::NegEdge),
+                _ => Some(EdgeIdentifier::Edge),
+            };
+            parser.advance();
+        }
+        res.expr = Expression::parse(parser)?;
+        if parser.probe(&[Token::Iff]) {
+            parser.advance();
+            res.iff = Some(Expression::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// edge_identifier ::= posedge | negedge | edge
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum EdgeIdentifier {
+    PosEdge,
+    NegEdge,
+    Edge,
+}
+
+/// jump_statement ::= return [ expression ] ; | break ; | continue ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct JumpStatement {
+    pub kind: JumpKind,
+    /// Value of a `return`
+    pub value: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum JumpKind {
+    #[default]
+    Return,
+    Break,
+    Continue,
+}
+
+impl Parse for JumpStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Return, Token::Break, Token::Continue]) {
+            return None;
+        }
+        let mut res = JumpStatement {
+            kind: match parser.peek()?.token {
+                Token::Break => JumpKind::Break,
+                Token::Continue => JumpKind::Continue,
+                _ => JumpKind::Return,
+            },
+            ..JumpStatement::default()
+        };
+        parser.advance();
+        if res.kind == JumpKind::Return && !parser.probe(&[Token::Semicolon]) {
+            res.value = Some(Expression::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// wait_statement ::= wait ( expression ) statement_or_null | wait fork ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct WaitStatement {
+    /// `None` for `wait fork`
+    pub condition: Option<Expression>,
+    pub statement: Option<Statement>,
+    pub span: NodeSpan,
+}
+
+impl Parse for WaitStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Wait]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = WaitStatement::default();
+        if parser.probe(&[Token::Fork]) {
+            parser.advance();
+            if !parser.probe_err(&[Token::Semicolon]) {
+                return None;
+            }
+            parser.advance();
+        } else {
+            res.condition = Some(parse_paren_expression(parser)?);
+            res.statement = Some(Statement::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// event_trigger ::= -> hierarchical_event_identifier ;
+/// | ->> [ delay_or_event_control ] hierarchical_event_identifier ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct EventTrigger {
+    /// `->>`
+    pub nonblocking: bool,
+    pub control: Option<DelayOrEventControl>,
+    pub event: Expression,
+    pub span: NodeSpan,
+}
+
+impl Parse for EventTrigger {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::OpImplication, Token::OpNonBlockingTrigger]) {
+            return None;
+        }
+        let mut res = EventTrigger {
+            nonblocking: parser.probe(&[Token::OpNonBlockingTrigger]),
+            ..EventTrigger::default()
+        };
+        parser.advance();
+        if res.nonblocking && parser.probe(&[Token::Sharp, Token::At, Token::Repeat]) {
+            res.control = Some(DelayOrEventControl::parse(parser)?);
+        }
+        res.event = Expression::parse_postfix(parser)?;
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// disable_statement ::= disable hierarchical_task_identifier ;
+/// | disable hierarchical_block_identifier ;
+/// | disable fork ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DisableStatement {
+    /// `None` for `disable fork`
+    pub target: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for DisableStatement {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Disable]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = DisableStatement::default();
+        if parser.probe(&[Token::Fork]) {
+            parser.advance();
+        } else {
+            res.target = Some(Expression::parse_postfix(parser)?);
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// ( expression )
+pub(crate) fn parse_paren_expression(parser: &mut Parser<'_>) -> Option<Expression> {
+    if !parser.probe_err(&[Token::LParen]) {
+        return None;
+    }
+    parser.advance();
+    let expr = Expression::parse(parser)?;
+    if !parser.probe_err(&[Token::RParen]) {
+        return None;
+    }
+    parser.advance();
+    Some(expr)
+}
+
+spanned!(
+    ProceduralTimingControlStatement,
+    DelayControl,
+    DelayOrEventControl,
+    EventControl,
+    EventExpression,
+    JumpStatement,
+    WaitStatement,
+    EventTrigger,
+    DisableStatement
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_control(text: &str) -> EventControl {
+        let mut parser = Parser::from(text);
+        let res = EventControl::parse(&mut parser).unwrap();
+        assert!(!parser.avail(), "{}", text);
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        res
+    }
+
+    #[test]
+    fn event_controls() {
+        let control = event_control("@(posedge clk or negedge rst_n iff en, edge a)");
+        let edges: Vec<Option<EdgeIdentifier>> =
+            control.events.iter().map(|e| e.edge.clone()).collect();
+        assert_eq!(
+            edges,
+            vec![
+                Some(EdgeIdentifier::PosEdge),
+                Some(EdgeIdentifier::NegEdge),
+                Some(EdgeIdentifier::Edge)
+            ]
+        );
+        assert!(control.events[1].iff.is_some());
+        assert_eq!(control.events[1].span, NodeSpan { first: 5, last: 8 });
+        assert!(event_control("@*").events.is_empty());
+        assert!(event_control("@ (*)").events.is_empty());
+        assert_eq!(event_control("@(a)").events.len(), 1);
+        assert!(matches!(
+            event_control("@ev").events[0].expr,
+            Expression::Name(_)
+        ));
+    }
+
+    #[test]
+    fn delay_controls() {
+        let mut parser = Parser::from("#5 a = 1; #10ns; #(D + 1) b <= 0; #1step;");
+        for _ in 0..4 {
+            let statement = Statement::parse(&mut parser).unwrap();
+            assert!(matches!(
+                statement.item,
+                StatementItem::TimingControl(ref control)
+                    if matches!(control.control, TimingControl::Delay(_))
+            ));
+        }
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+
+        let mut parser = Parser::from("#(1:2:3) a = 1;");
+        let statement = Statement::parse(&mut parser).unwrap();
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        match statement.item {
+            StatementItem::TimingControl(control) => match control.control {
+                TimingControl::Delay(delay) => match delay.value {
+                    Expression::Paren(paren) => {
+                        assert!(matches!(paren.expr, Expression::MinTypMax(_)))
+                    }
+                    value => panic!("{:?}", value),
+                },
+                control => panic!("{:?}", control),
+            },
+            item => panic!("{:?}", item),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! A.2.8 Block item declarations
+
+use crate::ast::*;
+
+/// block_item_declaration ::= { attribute_instance } data_declaration
+/// | { attribute_instance } local_parameter_declaration ;
+/// | { attribute_instance } parameter_declaration ;
+///
+/// Type declarations are one of the forms of `data_declaration`.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum BlockItemDeclaration {
+    Data(Attributes, DataDeclaration),
+    Type(Attributes, TypeDeclaration),
+    Parameter(Attributes, ParameterDeclaration),
+}
+
+impl BlockItemDeclaration {
+    /// Whether a block item declaration starts at the current token
+    pub(crate) fn is_block_item(parser: &mut Parser<'_>) -> bool {
+        DataDeclaration::is_data_declaration(parser)
+            || parser.probe(&[Token::TypeDef, Token::Parameter, Token::Localparam])
+    }
+}
+
+impl Parse for BlockItemDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let attributes = if parser.probe(&[Token::LParen]) {
+            Attributes::parse(parser).unwrap_or_default()
+        } else {
+            Attributes::default()
+        };
+        if parser.probe(&[Token::Parameter, Token::Localparam]) {
+            let declaration = ParameterDeclaration::parse(parser)?;
+            if !parser.probe_err(&[Token::Semicolon]) {
+                return None;
+            }
+            parser.advance();
+            return Some(BlockItemDeclaration::Parameter(attributes, declaration));
+        }
+        if parser.probe(&[Token::TypeDef]) {
+            return Some(BlockItemDeclaration::Type(
+                attributes,
+                TypeDeclaration::parse(parser)?,
+            ));
+        }
+        Some(BlockItemDeclaration::Data(
+            attributes,
+            DataDeclaration::parse(parser)?,
+        ))
+    }
+}
+
+impl Spanned for BlockItemDeclaration {
+    fn node_span(&self) -> Option<NodeSpan> {
+        let (attributes, span) = match self {
+            BlockItemDeclaration::Data(attributes, decl) => (attributes, decl.span),
+            BlockItemDeclaration::Type(attributes, decl) => (attributes, decl.span),
+            BlockItemDeclaration::Parameter(attributes, decl) => (attributes, decl.span),
+        };
+        Some(match attributes.span {
+            Some(attributes) => attributes.join(span),
+            None => span,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_items() {
+        let mut parser = Parser::from("const var automatic x = 1, y [4];");
+        let item = BlockItemDeclaration::parse(&mut parser).unwrap();
+        let decl = match &item {
+            BlockItemDeclaration::Data(_, decl) => decl,
+            item => panic!("{:?}", item),
+        };
+        assert!(decl.constant && decl.var);
+        assert_eq!(decl.lifetime, Some(Lifetime::Automatic));
+        assert!(matches!(
+            decl.data_type,
+            DataTypeOrImplicit::ImplicitData(_)
+        ));
+        assert_eq!(decl.assignments.len(), 2);
+        assert_eq!(decl.assignments[1].dimensions.len(), 1);
+        assert_eq!(item.node_span(), Some(NodeSpan { first: 0, last: 11 }));
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("(* keep *) localparam logic [3:0] P = 2;");
+        let item = BlockItemDeclaration::parse(&mut parser).unwrap();
+        assert!(matches!(item, BlockItemDeclaration::Parameter(..)));
+        assert_eq!(item.node_span(), Some(NodeSpan { first: 0, last: 14 }));
+        assert!(!parser.avail());
+
+        let mut parser = Parser::from("logic a b;");
+        assert_eq!(BlockItemDeclaration::parse(&mut parser), None);
+        assert_eq!(parser.get_diag().len(), 1);
+    }
+}
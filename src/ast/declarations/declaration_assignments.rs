@@ -0,0 +1,172 @@
+//! A.2.4 Declaration assignments
+
+use crate::ast::*;
+
+/// net_decl_assignment ::= net_identifier { unpacked_dimension } [ = expression ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct NetDeclAssignment {
+    pub identifier: Identifier,
+    pub dimensions: Vec<UnpackedDimension>,
+    pub init: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for NetDeclAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = NetDeclAssignment {
+            identifier: Identifier::parse(parser)?,
+            ..NetDeclAssignment::default()
+        };
+        while parser.probe(&[Token::LBracket]) {
+            res.dimensions.push(UnpackedDimension::parse(parser)?);
+        }
+        if parser.probe(&[Token::Equal]) {
+            parser.advance();
+            res.init = Some(Expression::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl NetDeclAssignment {
+    /// list_of_net_decl_assignments ::= net_decl_assignment { , net_decl_assignment }
+    pub(crate) fn parse_list(parser: &mut Parser<'_>) -> Option<Vec<Self>> {
+        let mut res = vec![NetDeclAssignment::parse(parser)?];
+        while parser.probe(&[Token::Comma]) {
+            parser.advance();
+            res.push(NetDeclAssignment::parse(parser)?);
+        }
+        Some(res)
+    }
+}
+
+/// variable_decl_assignment ::= variable_identifier { variable_dimension } [ = expression ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct VariableDeclAssignment {
+    pub identifier: Identifier,
+    pub dimensions: Vec<UnpackedDimension>,
+    pub init: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for VariableDeclAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = VariableDeclAssignment {
+            identifier: Identifier::parse(parser)?,
+            ..VariableDeclAssignment::default()
+        };
+        while parser.probe(&[Token::LBracket]) {
+            res.dimensions.push(UnpackedDimension::parse(parser)?);
+        }
+        if parser.probe(&[Token::Equal]) {
+            parser.advance();
+            res.init = Some(Expression::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl VariableDeclAssignment {
+    /// list_of_variable_decl_assignments ::= variable_decl_assignment { , variable_decl_assignment }
+    pub(crate) fn parse_list(parser: &mut Parser<'_>) -> Option<Vec<Self>> {
+        let mut res = vec![VariableDeclAssignment::parse(parser)?];
+        while parser.probe(&[Token::Comma]) {
+            parser.advance();
+            res.push(VariableDeclAssignment::parse(parser)?);
+        }
+        Some(res)
+    }
+}
+
+/// param_assignment ::= parameter_identifier { unpacked_dimension } [ = constant_param_expression ]
+/// type_assignment ::= type_identifier [ = data_type ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ParamAssignment {
+    pub identifier: Identifier,
+    pub dimensions: Vec<UnpackedDimension>,
+    /// Default value, only optional in parameter port lists
+    pub value: Option<ParamExpression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ParamAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = ParamAssignment {
+            identifier: Identifier::parse(parser)?,
+            ..ParamAssignment::default()
+        };
+        while parser.probe(&[Token::LBracket]) {
+            res.dimensions.push(UnpackedDimension::parse(parser)?);
+        }
+        if parser.probe(&[Token::Equal]) {
+            parser.advance();
+            res.value = Some(ParamExpression::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// defparam_assignment ::= hierarchical_parameter_identifier = constant_mintypmax_expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DefparamAssignment {
+    pub parameter: Expression,
+    pub value: Expression,
+    pub span: NodeSpan,
+}
+
+impl Parse for DefparamAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let parameter = Expression::parse_postfix(parser)?;
+        if !parser.probe_err(&[Token::Equal]) {
+            return None;
+        }
+        parser.advance();
+        let value = Expression::parse_mintypmax(parser)?;
+        Some(DefparamAssignment {
+            parameter,
+            value,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+spanned!(
+    NetDeclAssignment,
+    VariableDeclAssignment,
+    ParamAssignment,
+    DefparamAssignment
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn net_decl_assignments() {
+        let mut parser = Parser::from("a, b[3:0][2] = '{default: 0}, c = 1'b1");
+        let list = NetDeclAssignment::parse_list(&mut parser).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].init, None);
+        assert_eq!(list[1].dimensions.len(), 2);
+        assert!(matches!(
+            list[1].init,
+            Some(Expression::AssignmentPattern(_))
+        ));
+        assert_eq!(
+            list[2].span,
+            NodeSpan {
+                first: 18,
+                last: 20
+            }
+        );
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+    }
+}
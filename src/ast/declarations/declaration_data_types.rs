@@ -3,22 +3,154 @@ use crate::ast::*;
 
 /// # A.2.2.1 Net and variable types
 /// ## data_type ::= integer_vector_type [ signing ] { packed_dimension }
+/// | integer_atom_type [ signing ]
+/// | non_integer_type
+/// | struct_union [ packed [ signing ] ] { struct_union_member { struct_union_member } } { packed_dimension }
+/// | enum [ enum_base_type ] { enum_name_declaration { , enum_name_declaration } } { packed_dimension }
+/// | string
+/// | chandle
+/// | [ class_scope | package_scope ] type_identifier { packed_dimension }
+/// | class_type
+/// | event
+/// | type_reference
 /// ## signing ::= signed | unsigned
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct DataType {
-    pub integer_type: IntegerVectorType,
+    pub kind: DataTypeKind,
+    /// Signing of integer types, packed structures keep theirs
     pub sign: Option<Signing>,
     pub dimensions: Vec<PackedDimension>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum DataTypeKind {
+    IntegerVector(IntegerVectorType),
+    IntegerAtom(IntegerAtomType),
+    NonInteger(NonIntegerType),
+    StructUnion(StructUnionType),
+    Enum(EnumType),
+    String,
+    CHandle,
+    Event,
+    /// Type and class names, possibly scoped
+    Named(TypeName),
+    Reference(Box<TypeReference>),
+}
+
+impl Default for DataTypeKind {
+    fn default() -> Self {
+        Self::IntegerVector(IntegerVectorType::default())
+    }
+}
+
+impl DataType {
+    /// Keywords a data type starts with, type names start with an identifier
+    pub(crate) const FIRST: &'static [Token] = &[
+        Token::Bit,
+        Token::Logic,
+        Token::Reg,
+        Token::Byte,
+        Token::ShortInt,
+        Token::Int,
+        Token::LongInt,
+        Token::Integer,
+        Token::Time,
+        Token::ShortReal,
+        Token::Real,
+        Token::Realtime,
+        Token::Struct,
+        Token::Union,
+        Token::Enum,
+        Token::String,
+        Token::CHandle,
+        Token::Event,
+        Token::Type,
+    ];
+
+    /// Whether a data type starts at the current token
+    ///
+    /// A type name is only recognized when a declared identifier follows it,
+    /// otherwise it is the start of an expression or an implicit type.
+    pub(crate) fn is_data_type(parser: &mut Parser<'_>) -> bool {
+        if parser.probe(Self::FIRST) {
+            return true;
+        }
+        let mut tokens = parser.lookahead().peekable();
+        loop {
+            if tokens.next() != Some(Token::Identifier) {
+                return false;
+            }
+            if tokens.next_if_eq(&Token::Sharp).is_some() {
+                if tokens.peek() != Some(&Token::LParen) {
+                    return false;
+                }
+                skip_group(&mut tokens);
+            }
+            if tokens.next_if_eq(&Token::DoubleColon).is_none() {
+                break;
+            }
+        }
+        while tokens.peek() == Some(&Token::LBracket) {
+            skip_group(&mut tokens);
+        }
+        tokens.next() == Some(Token::Identifier)
+    }
 }
 
 impl Parse for DataType {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = Self::default();
-        if let Some(integer_type) = IntegerVectorType::parse(parser) {
-            res.integer_type = integer_type;
-            if parser.probe(&[Token::Signed, Token::Unsigned]) {
-                res.sign = Signing::parse(parser);
+        let first = parser.start();
+        res.kind = match parser.peek()?.token {
+            Token::Bit | Token::Logic | Token::Reg => {
+                DataTypeKind::IntegerVector(IntegerVectorType::parse(parser)?)
+            }
+            Token::Byte
+            | Token::ShortInt
+            | Token::Int
+            | Token::LongInt
+            | Token::Integer
+            | Token::Time => DataTypeKind::IntegerAtom(IntegerAtomType::parse(parser)?),
+            Token::ShortReal | Token::Real | Token::Realtime => {
+                DataTypeKind::NonInteger(NonIntegerType::parse(parser)?)
+            }
+            Token::Struct | Token::Union => {
+                DataTypeKind::StructUnion(StructUnionType::parse(parser)?)
+            }
+            Token::Enum => DataTypeKind::Enum(EnumType::parse(parser)?),
+            Token::String => {
+                parser.advance();
+                DataTypeKind::String
             }
+            Token::CHandle => {
+                parser.advance();
+                DataTypeKind::CHandle
+            }
+            Token::Event => {
+                parser.advance();
+                DataTypeKind::Event
+            }
+            Token::Type => DataTypeKind::Reference(Box::new(TypeReference::parse(parser)?)),
+            Token::Identifier => DataTypeKind::Named(TypeName::parse(parser)?),
+            _ => return None,
+        };
+        if matches!(
+            res.kind,
+            DataTypeKind::IntegerVector(_) | DataTypeKind::IntegerAtom(_)
+        ) && parser.probe(&[Token::Signed, Token::Unsigned])
+        {
+            res.sign = Signing::parse(parser);
+        }
+        if !matches!(
+            res.kind,
+            DataTypeKind::IntegerAtom(_)
+                | DataTypeKind::NonInteger(_)
+                | DataTypeKind::String
+                | DataTypeKind::CHandle
+                | DataTypeKind::Event
+                | DataTypeKind::Reference(_)
+        ) {
             while parser.probe(&[Token::LBracket]) {
                 if let Some(dimension) = PackedDimension::parse(parser) {
                     res.dimensions.push(dimension);
@@ -26,28 +158,22 @@ impl Parse for DataType {
                     break;
                 }
             }
-            Some(res)
-        } else {
-            None
         }
+        res.span = parser.span_from(first);
+        Some(res)
     }
 }
 
 /// # A.2.2.1 Net and variable types
 /// ## integer_vector_type ::= bit | logic | reg
-#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub enum IntegerVectorType {
+    #[default]
     Bit,
     Logic,
     Reg,
 }
 
-impl Default for IntegerVectorType {
-    fn default() -> Self {
-        Self::Bit
-    }
-}
-
 impl Parse for IntegerVectorType {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         if let Some(token) = parser.peek() {
@@ -67,6 +193,382 @@ impl Parse for IntegerVectorType {
     }
 }
 
+/// # A.2.2.1 Net and variable types
+/// ## integer_atom_type ::= byte | shortint | int | longint | integer | time
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum IntegerAtomType {
+    Byte,
+    ShortInt,
+    Int,
+    LongInt,
+    Integer,
+    Time,
+}
+
+impl Parse for IntegerAtomType {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let res = match parser.peek()?.token {
+            Token::Byte => IntegerAtomType::Byte,
+            Token::ShortInt => IntegerAtomType::ShortInt,
+            Token::Int => IntegerAtomType::Int,
+            Token::LongInt => IntegerAtomType::LongInt,
+            Token::Integer => IntegerAtomType::Integer,
+            Token::Time => IntegerAtomType::Time,
+            _ => return None,
+        };
+        parser.advance();
+        Some(res)
+    }
+}
+
+/// # A.2.2.1 Net and variable types
+/// ## non_integer_type ::= shortreal | real | realtime
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum NonIntegerType {
+    ShortReal,
+    Real,
+    RealTime,
+}
+
+impl Parse for NonIntegerType {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let res = match parser.peek()?.token {
+            Token::ShortReal => NonIntegerType::ShortReal,
+            Token::Real => NonIntegerType::Real,
+            Token::Realtime => NonIntegerType::RealTime,
+            _ => return None,
+        };
+        parser.advance();
+        Some(res)
+    }
+}
+
+/// # A.2.2.1 Net and variable types
+/// ## struct_union ::= struct | union [ tagged ]
+///
+/// The members and the packing of a structure or union type.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct StructUnionType {
+    pub kind: StructUnionKind,
+    pub tagged: bool,
+    pub packed: bool,
+    pub sign: Option<Signing>,
+    pub members: Vec<StructUnionMember>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum StructUnionKind {
+    #[default]
+    Struct,
+    Union,
+}
+
+impl Parse for StructUnionType {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Struct, Token::Union]) {
+            return None;
+        }
+        let mut res = StructUnionType::default();
+        if parser.probe(&[Token::Union]) {
+            res.kind = StructUnionKind::Union;
+        }
+        parser.advance();
+        if res.kind == StructUnionKind::Union && parser.probe(&[Token::Tagged]) {
+            parser.advance();
+            res.tagged = true;
+        }
+        if parser.probe(&[Token::Packed]) {
+            parser.advance();
+            res.packed = true;
+            if parser.probe(&[Token::Signed, Token::Unsigned]) {
+                res.sign = Signing::parse(parser);
+            }
+        }
+        if !parser.probe_err(&[Token::LBraces]) {
+            return None;
+        }
+        parser.advance();
+        loop {
+            res.members.push(StructUnionMember::parse(parser)?);
+            if parser.probe(&[Token::RBraces]) || !parser.avail() {
+                break;
+            }
+        }
+        if !parser.probe_err(&[Token::RBraces]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.2.1 Net and variable types
+/// ## struct_union_member ::= { attribute_instance } [ random_qualifier ] data_type_or_void list_of_variable_decl_assignments ;
+/// ## random_qualifier ::= rand | randc
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct StructUnionMember {
+    pub attributes: Attributes,
+    pub random: Option<RandomQualifier>,
+    /// `None` for `void` members of tagged unions
+    pub data_type: Option<DataType>,
+    pub assignments: Vec<VariableDeclAssignment>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum RandomQualifier {
+    Rand,
+    RandC,
+}
+
+impl Parse for StructUnionMember {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = StructUnionMember::default();
+        if parser.probe(&[Token::LParen]) {
+            res.attributes = Attributes::parse(parser)?;
+        }
+        if parser.probe(&[Token::Rand, Token::RandC]) {
+            res.random = Some(if parser.probe(&[Token::Rand]) {
+                RandomQualifier::Rand
+            } else {
+                RandomQualifier::RandC
+            });
+            parser.advance();
+        }
+        if parser.probe(&[Token::Void]) {
+            parser.advance();
+        } else if DataType::is_data_type(parser) {
+            res.data_type = Some(DataType::parse(parser)?);
+        } else {
+            parser.probe_err(DataType::FIRST);
+            return None;
+        }
+        res.assignments = VariableDeclAssignment::parse_list(parser)?;
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.2.1 Net and variable types
+/// ## enum_base_type ::= integer_atom_type [ signing ]
+/// | integer_vector_type [ signing ] [ packed_dimension ]
+/// | type_identifier [ packed_dimension ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct EnumType {
+    /// `None` for the default `int` base type
+    pub base: Option<Box<DataType>>,
+    pub names: Vec<EnumNameDeclaration>,
+    pub span: NodeSpan,
+}
+
+impl Parse for EnumType {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Enum]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = EnumType::default();
+        if !parser.probe(&[Token::LBraces]) {
+            if !parser.probe_err(&[
+                Token::Bit,
+                Token::Logic,
+                Token::Reg,
+                Token::Byte,
+                Token::ShortInt,
+                Token::Int,
+                Token::LongInt,
+                Token::Integer,
+                Token::Time,
+                Token::Identifier,
+            ]) {
+                return None;
+            }
+            res.base = Some(Box::new(DataType::parse(parser)?));
+        }
+        if !parser.probe_err(&[Token::LBraces]) {
+            return None;
+        }
+        parser.advance();
+        loop {
+            res.names.push(EnumNameDeclaration::parse(parser)?);
+            if !parser.probe(&[Token::Comma]) {
+                break;
+            }
+            parser.advance();
+        }
+        if !parser.probe_err(&[Token::RBraces]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.2.1 Net and variable types
+/// ## enum_name_declaration ::= enum_identifier [ [ integral_number [ : integral_number ] ] ] [ = constant_expression ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct EnumNameDeclaration {
+    pub identifier: Identifier,
+    pub range: Option<EnumNameRange>,
+    pub value: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+/// Suffixes of the names generated by `name[N]` or `name[N:M]`
+///
+/// Without `last` the names are numbered from 0 to `first` - 1.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct EnumNameRange {
+    pub first: Expression,
+    pub last: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for EnumNameDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = EnumNameDeclaration {
+            identifier: Identifier::parse(parser)?,
+            ..EnumNameDeclaration::default()
+        };
+        if parser.probe(&[Token::LBracket]) {
+            let range_first = parser.start();
+            parser.advance();
+            let mut range = EnumNameRange {
+                first: Expression::parse(parser)?,
+                ..EnumNameRange::default()
+            };
+            if parser.probe(&[Token::Colon]) {
+                parser.advance();
+                range.last = Some(Expression::parse(parser)?);
+            }
+            if !parser.probe_err(&[Token::RBracket]) {
+                return None;
+            }
+            parser.advance();
+            range.span = parser.span_from(range_first);
+            res.range = Some(range);
+        }
+        if parser.probe(&[Token::Equal]) {
+            parser.advance();
+            res.value = Some(Expression::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.2.1 Net and variable types
+/// ## class_scope ::= class_type ::
+/// ## class_type ::= ps_class_identifier [ parameter_value_assignment ]
+/// { :: class_identifier [ parameter_value_assignment ] }
+/// ## package_scope ::= package_identifier ::
+///
+/// A type or class name with the package and classes it is declared in.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TypeName {
+    pub scope: Vec<TypeScope>,
+    pub identifier: Identifier,
+    /// Parameters of a class type
+    pub parameters: Option<ParameterValueAssignment>,
+    pub span: NodeSpan,
+}
+
+/// A package or class before `::`
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TypeScope {
+    pub identifier: Identifier,
+    pub parameters: Option<ParameterValueAssignment>,
+}
+
+impl TypeName {
+    /// Whether a package or class scope `::` follows the identifier at the
+    /// current token
+    pub(crate) fn is_scoped(parser: &Parser<'_>) -> bool {
+        let mut tokens = parser.lookahead().peekable();
+        if tokens.next() != Some(Token::Identifier) {
+            return false;
+        }
+        if tokens.next_if_eq(&Token::Sharp).is_some() {
+            if tokens.peek() != Some(&Token::LParen) {
+                return false;
+            }
+            skip_group(&mut tokens);
+        }
+        tokens.next() == Some(Token::DoubleColon)
+    }
+}
+
+impl Parse for TypeName {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = TypeName::default();
+        loop {
+            let identifier = Identifier::parse(parser)?;
+            let parameters = if parser.probe(&[Token::Sharp]) {
+                Some(ParameterValueAssignment::parse(parser)?)
+            } else {
+                None
+            };
+            if !parser.probe(&[Token::DoubleColon]) {
+                res.identifier = identifier;
+                res.parameters = parameters;
+                break;
+            }
+            parser.advance();
+            res.scope.push(TypeScope {
+                identifier,
+                parameters,
+            });
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.2.1 Net and variable types
+/// ## type_reference ::= type ( expression ) | type ( data_type_or_incomplete_class_scoped_type )
+///
+/// Type names are parsed as expressions.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum TypeReference {
+    Expression(Expression),
+    DataType(DataType),
+}
+
+impl Parse for TypeReference {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if !parser.probe_err(&[Token::Type]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let res = if parser.probe(DataType::FIRST) {
+            TypeReference::DataType(DataType::parse(parser)?)
+        } else {
+            TypeReference::Expression(Expression::parse(parser)?)
+        };
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        Some(res)
+    }
+}
+
 /// # A.2.2.1 Net and variable types
 /// ## signing ::= signed | unsigned
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
@@ -99,19 +601,29 @@ impl Parse for Signing {
 pub struct ImplicitDataType {
     pub sign: Option<Signing>,
     pub dimensions: Vec<PackedDimension>,
+    pub span: Option<NodeSpan>,
+}
+
+impl ImplicitDataType {
+    /// Tokens a non-empty implicit data type starts with
+    pub(crate) const FIRST: &'static [Token] = &[Token::Signed, Token::Unsigned, Token::LBracket];
 }
 
 impl Parse for ImplicitDataType {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = Self::default();
+        let first = parser.start();
         if parser.probe(&[Token::Signed, Token::Unsigned]) {
             res.sign = Signing::parse(parser);
         }
         while parser.probe(&[Token::LBracket]) {
             if let Some(dimension) = PackedDimension::parse(parser) {
                 res.dimensions.push(dimension);
+            } else {
+                break;
             }
         }
+        res.span = parser.optional_span_from(first);
         Some(res)
     }
 }
@@ -132,18 +644,15 @@ impl Default for DataTypeOrImplicit {
 
 impl Parse for DataTypeOrImplicit {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
-        if parser.probe(&[Token::Bit, Token::Logic, Token::Reg]) {
+        if DataType::is_data_type(parser) {
             if let Some(data) = DataType::parse(parser) {
                 return Some(DataTypeOrImplicit::Data(data));
             }
         }
-        if parser.probe(&[
-            Token::Signed,
-            Token::Unsigned,
-            Token::LBracket,
+        if parser.probe(ImplicitDataType::FIRST)
             // FOLLOW
-            Token::Identifier,
-        ]) {
+            || parser.probe(&[Token::Identifier])
+        {
             if let Some(data) = ImplicitDataType::parse(parser) {
                 return Some(DataTypeOrImplicit::ImplicitData(data));
             }
@@ -158,39 +667,24 @@ impl Parse for DataTypeOrImplicit {
 pub struct NetPortType {
     pub net_type: Option<NetType>,
     pub data_type_or_implicit: DataTypeOrImplicit,
+    pub span: Option<NodeSpan>,
 }
 
 impl Parse for NetPortType {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = NetPortType::default();
-        if parser.probe(&[
-            Token::Supply0,
-            Token::Supply1,
-            Token::Tri,
-            Token::TriAnd,
-            Token::TriOr,
-            Token::TriReg,
-            Token::Tri0,
-            Token::Tri1,
-            Token::Uwire,
-            Token::Wire,
-            Token::Wand,
-            Token::Wor,
-        ]) {
+        let first = parser.start();
+        if parser.probe(NetType::FIRST) {
             res.net_type = NetType::parse(parser);
         }
-        if parser.probe(&[
-            Token::Bit,
-            Token::Logic,
-            Token::Reg,
-            Token::Signed,
-            Token::Unsigned,
-            Token::LBracket,
+        if parser.probe(DataType::FIRST)
+            || parser.probe(ImplicitDataType::FIRST)
             // FOLLOW
-            Token::Identifier,
-        ]) {
+            || parser.probe(&[Token::Identifier])
+        {
             if let Some(data) = DataTypeOrImplicit::parse(parser) {
                 res.data_type_or_implicit = data;
+                res.span = parser.optional_span_from(first);
                 return Some(res);
             }
         }
@@ -198,6 +692,228 @@ impl Parse for NetPortType {
     }
 }
 
+/// # A.2.2.2 Strengths
+/// ## drive_strength ::= ( strength0 , strength1 ) | ( strength1 , strength0 )
+/// | ( strength0 , highz1 ) | ( strength1 , highz0 )
+/// | ( highz0 , strength1 ) | ( highz1 , strength0 )
+/// ## strength0 ::= supply0 | strong0 | pull0 | weak0
+/// ## strength1 ::= supply1 | strong1 | pull1 | weak1
+///
+/// The strengths are stored by value regardless of the written order.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DriveStrength {
+    pub strength0: Strength,
+    pub strength1: Strength,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum Strength {
+    Supply,
+    #[default]
+    Strong,
+    Pull,
+    Weak,
+    HighZ,
+}
+
+const STRENGTH0: &[Token] = &[
+    Token::Supply0,
+    Token::Strong0,
+    Token::Pull0,
+    Token::Weak0,
+    Token::HighZ0,
+];
+const STRENGTH1: &[Token] = &[
+    Token::Supply1,
+    Token::Strong1,
+    Token::Pull1,
+    Token::Weak1,
+    Token::HighZ1,
+];
+
+impl DriveStrength {
+    /// Tokens after the `(` of a drive strength
+    pub(crate) const FIRST: &'static [Token] = &[
+        Token::Supply0,
+        Token::Strong0,
+        Token::Pull0,
+        Token::Weak0,
+        Token::HighZ0,
+        Token::Supply1,
+        Token::Strong1,
+        Token::Pull1,
+        Token::Weak1,
+        Token::HighZ1,
+    ];
+
+    /// A strength of `tokens`, returns whether it is a 0 strength
+    fn parse_strength(parser: &mut Parser<'_>, tokens: &[Token]) -> Option<(Strength, bool)> {
+        if !parser.probe_err(tokens) {
+            return None;
+        }
+        let token = parser.peek()?.token;
+        let strength = match token {
+            Token::Supply0 | Token::Supply1 => Strength::Supply,
+            Token::Strong0 | Token::Strong1 => Strength::Strong,
+            Token::Pull0 | Token::Pull1 => Strength::Pull,
+            Token::Weak0 | Token::Weak1 => Strength::Weak,
+            _ => Strength::HighZ,
+        };
+        parser.advance();
+        Some((strength, STRENGTH0.contains(&token)))
+    }
+}
+
+impl Parse for DriveStrength {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let (strength, zero) = Self::parse_strength(parser, Self::FIRST)?;
+        if !parser.probe_err(&[Token::Comma]) {
+            return None;
+        }
+        parser.advance();
+        // the other value, at most one of them is highz
+        let mut other: Vec<Token> = if zero { STRENGTH1 } else { STRENGTH0 }.to_vec();
+        if strength == Strength::HighZ {
+            other.pop();
+        }
+        let (other, _) = Self::parse_strength(parser, &other)?;
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        let (strength0, strength1) = if zero {
+            (strength, other)
+        } else {
+            (other, strength)
+        };
+        Some(DriveStrength {
+            strength0,
+            strength1,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// # A.2.2.2 Strengths
+/// ## charge_strength ::= ( small ) | ( medium ) | ( large )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ChargeStrength {
+    pub size: ChargeSize,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum ChargeSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl Parse for ChargeStrength {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::Small, Token::Medium, Token::Large]) {
+            return None;
+        }
+        let size = match parser.peek()?.token {
+            Token::Small => ChargeSize::Small,
+            Token::Large => ChargeSize::Large,
+            _ => ChargeSize::Medium,
+        };
+        parser.advance();
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        Some(ChargeStrength {
+            size,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// # A.2.2.3 Delays
+/// ## delay3 ::= # delay_value | # ( mintypmax_expression [ , mintypmax_expression [ , mintypmax_expression ] ] )
+///
+/// The values are the rise, fall and turn-off delays.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Delay3 {
+    pub values: Vec<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for Delay3 {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Sharp]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = Delay3::default();
+        if parser.probe(&[Token::LParen]) {
+            parser.advance();
+            res.values.push(Expression::parse_mintypmax(parser)?);
+            while res.values.len() < 3 && parser.probe(&[Token::Comma]) {
+                parser.advance();
+                res.values.push(Expression::parse_mintypmax(parser)?);
+            }
+            if !parser.probe_err(&[Token::RParen]) {
+                return None;
+            }
+            parser.advance();
+        } else {
+            res.values.push(Expression::parse_postfix(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl Spanned for ImplicitDataType {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.span
+    }
+}
+
+impl Spanned for DataTypeOrImplicit {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            DataTypeOrImplicit::Data(data) => data.node_span(),
+            DataTypeOrImplicit::ImplicitData(data) => data.node_span(),
+        }
+    }
+}
+
+impl Spanned for NetPortType {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.span
+    }
+}
+
+spanned!(
+    DataType,
+    StructUnionType,
+    StructUnionMember,
+    EnumType,
+    EnumNameDeclaration,
+    EnumNameRange,
+    TypeName,
+    DriveStrength,
+    ChargeStrength,
+    Delay3
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,18 +922,138 @@ mod tests {
     fn data_type() {
         let mut parser = Parser::from("logic signed [1:2][][]");
         let m = DataType::parse(&mut parser);
-        assert_eq!(m.as_ref().unwrap().integer_type, IntegerVectorType::Logic);
+        assert_eq!(m.as_ref().unwrap().span, NodeSpan { first: 0, last: 10 });
+        assert_eq!(
+            m.as_ref().unwrap().kind,
+            DataTypeKind::IntegerVector(IntegerVectorType::Logic)
+        );
         assert_eq!(m.as_ref().unwrap().sign, Some(Signing::Signed));
         assert_eq!(
-            m.as_ref().unwrap().dimensions[0]
-                .from
-                .as_ref()
-                .unwrap()
-                .token,
-            3
+            m.as_ref().unwrap().dimensions[0].from,
+            Some(Expression::Number(Number { token: 3 }))
         );
     }
 
+    #[test]
+    fn builtin_data_types() {
+        let mut parser = Parser::from("int unsigned");
+        let m = DataType::parse(&mut parser).unwrap();
+        assert_eq!(m.kind, DataTypeKind::IntegerAtom(IntegerAtomType::Int));
+        assert_eq!(m.sign, Some(Signing::Unsigned));
+
+        for (text, kind) in [
+            (
+                "realtime",
+                DataTypeKind::NonInteger(NonIntegerType::RealTime),
+            ),
+            ("string", DataTypeKind::String),
+            ("chandle", DataTypeKind::CHandle),
+            ("event", DataTypeKind::Event),
+        ] {
+            let mut parser = Parser::from(text);
+            assert_eq!(DataType::parse(&mut parser).unwrap().kind, kind);
+            assert!(!parser.avail());
+        }
+
+        let mut parser = Parser::from("type(a + b)");
+        let m = DataType::parse(&mut parser).unwrap();
+        assert!(matches!(
+            m.kind,
+            DataTypeKind::Reference(ref reference) if matches!(**reference, TypeReference::Expression(_))
+        ));
+        assert_eq!(m.span, NodeSpan { first: 0, last: 5 });
+    }
+
+    #[test]
+    fn struct_union_type() {
+        let mut parser =
+            Parser::from("struct packed signed { rand bit [3:0] a, b; (* keep *) logic c; } [1:0]");
+        let m = DataType::parse(&mut parser).unwrap();
+        let DataTypeKind::StructUnion(data) = &m.kind else {
+            panic!("{:?}", m.kind);
+        };
+        assert_eq!(data.kind, StructUnionKind::Struct);
+        assert!(data.packed);
+        assert_eq!(data.sign, Some(Signing::Signed));
+        assert_eq!(data.members.len(), 2);
+        assert_eq!(data.members[0].random, Some(RandomQualifier::Rand));
+        assert_eq!(data.members[0].assignments.len(), 2);
+        assert!(data.members[1].attributes.span.is_some());
+        assert_eq!(m.dimensions.len(), 1);
+        assert_eq!(data.node_span().unwrap().last + 5, m.span.last);
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("union tagged { void invalid; int value; }");
+        let m = DataType::parse(&mut parser).unwrap();
+        let DataTypeKind::StructUnion(data) = &m.kind else {
+            panic!("{:?}", m.kind);
+        };
+        assert_eq!(data.kind, StructUnionKind::Union);
+        assert!(data.tagged && !data.packed);
+        assert_eq!(data.members[0].data_type, None);
+        assert_eq!(data.span, NodeSpan { first: 0, last: 9 });
+        assert!(parser.get_diag().is_empty());
+    }
+
+    #[test]
+    fn enum_type() {
+        let mut parser = Parser::from("enum logic [1:0] { IDLE, S[2] = 1, T[3:4] } [1:0]");
+        let m = DataType::parse(&mut parser).unwrap();
+        let DataTypeKind::Enum(data) = &m.kind else {
+            panic!("{:?}", m.kind);
+        };
+        let base = data.base.as_ref().unwrap();
+        assert_eq!(
+            base.kind,
+            DataTypeKind::IntegerVector(IntegerVectorType::Logic)
+        );
+        assert_eq!(base.dimensions.len(), 1);
+        assert_eq!(data.names.len(), 3);
+        assert_eq!(data.names[0].range, None);
+        let range = data.names[1].range.as_ref().unwrap();
+        assert_eq!(range.last, None);
+        assert!(data.names[1].value.is_some());
+        assert!(data.names[2].range.as_ref().unwrap().last.is_some());
+        assert_eq!(m.dimensions.len(), 1);
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("enum { A }");
+        let m = DataType::parse(&mut parser).unwrap();
+        assert!(matches!(
+            m.kind,
+            DataTypeKind::Enum(EnumType {
+                base: None,
+                span: NodeSpan { first: 0, last: 3 },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn type_name() {
+        let mut parser = Parser::from("pkg::cls #(8)::item_t [3:0] x");
+        assert!(DataType::is_data_type(&mut parser));
+        let m = DataType::parse(&mut parser).unwrap();
+        let DataTypeKind::Named(name) = &m.kind else {
+            panic!("{:?}", m.kind);
+        };
+        assert_eq!(name.scope.len(), 2);
+        assert!(name.scope[0].parameters.is_none());
+        assert!(name.scope[1].parameters.is_some());
+        assert_eq!(name.identifier, Identifier { token: 8 });
+        assert_eq!(m.dimensions.len(), 1);
+        assert_eq!(m.span, NodeSpan { first: 0, last: 13 });
+
+        let mut parser = Parser::from("my_class #(.W(4)) obj");
+        assert!(DataType::is_data_type(&mut parser));
+        let mut parser = Parser::from("a [3] = 1");
+        assert!(!DataType::is_data_type(&mut parser));
+        let mut parser = Parser::from("pkg::f(x)");
+        assert!(!DataType::is_data_type(&mut parser));
+    }
+
     #[test]
     fn net_port_type() {
         let mut parser = Parser::from("logic");
@@ -226,7 +1062,8 @@ mod tests {
         assert_eq!(
             m.as_ref().unwrap().data_type_or_implicit,
             DataTypeOrImplicit::Data(DataType {
-                integer_type: IntegerVectorType::Logic,
+                kind: DataTypeKind::IntegerVector(IntegerVectorType::Logic),
+                span: NodeSpan::token(0),
                 ..DataType::default()
             })
         );
@@ -234,5 +1071,27 @@ mod tests {
         let mut parser = Parser::from("wire abc");
         let m = NetPortType::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().net_type, Some(NetType::Wire));
+        assert_eq!(m.as_ref().unwrap().span, Some(NodeSpan::token(0)));
+
+        let mut parser = Parser::from("abc");
+        let m = NetPortType::parse(&mut parser);
+        assert_eq!(m.as_ref().unwrap().span, None);
+    }
+
+    #[test]
+    fn delay3() {
+        let mut parser = Parser::from("#(1:2:3, 4, 5:6:7) #2");
+        let delay = Delay3::parse(&mut parser).unwrap();
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        assert_eq!(delay.values.len(), 3);
+        assert!(matches!(delay.values[0], Expression::MinTypMax(_)));
+        assert!(matches!(delay.values[1], Expression::Number(_)));
+        assert!(matches!(delay.values[2], Expression::MinTypMax(_)));
+        let delay = Delay3::parse(&mut parser).unwrap();
+        assert_eq!(delay.values.len(), 1);
+
+        let mut parser = Parser::from("#(1:2)");
+        assert_eq!(Delay3::parse(&mut parser), None);
+        assert_eq!(parser.get_diag().len(), 1);
     }
 }
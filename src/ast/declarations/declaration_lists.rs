@@ -6,10 +6,12 @@ use crate::ast::*;
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct ListOfPortIdentifiers {
     pub ports: Vec<(Identifier, Option<UnpackedDimension>)>,
+    pub span: NodeSpan,
 }
 
 impl Parse for ListOfPortIdentifiers {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
         if let Some(init_identifier) = Identifier::parse(parser) {
             let mut res = Self::default();
             let dimension = if parser.probe(&[Token::LBracket]) {
@@ -29,12 +31,15 @@ impl Parse for ListOfPortIdentifiers {
                     res.ports.push((identifier, dimension));
                 }
             }
+            res.span = parser.span_from(first);
             return Some(res);
         }
         None
     }
 }
 
+spanned!(ListOfPortIdentifiers);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,5 +49,6 @@ mod tests {
         let mut parser = Parser::from("abc[1:3],cd");
         let m = ListOfPortIdentifiers::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().ports.len(), 2);
+        assert_eq!(m.as_ref().unwrap().span, NodeSpan { first: 0, last: 7 });
     }
 }
@@ -1,78 +1,165 @@
 //! A.2.5 Declaration ranges
 
 use crate::ast::*;
+use crate::diagnostic::Diagnostic;
 
 /// packed_dimension ::= [ constant_range ] | unsized_dimension
 /// unsized_dimension ::= [ ]
 /// constant_range ::= constant_expression : constant_expression
-/// constant_expression ::= constant_primary
-/// constant_primary ::= primary_literal
-/// primary_literal ::= number
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct PackedDimension {
-    pub from: Option<Number>,
-    pub to: Option<Number>,
+    pub from: Option<Expression>,
+    pub to: Option<Expression>,
+    pub span: NodeSpan,
 }
 
 impl Parse for PackedDimension {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
         if parser.probe_err(&[Token::LBracket]) {
             parser.advance();
-            if parser.probe(&[Token::Number]) {
-                let mut res = Self::default();
-                res.from = Number::parse(parser);
-                if parser.probe_err(&[Token::Colon]) {
-                    parser.advance();
-                    res.to = Number::parse(parser);
-                    if parser.probe_err(&[Token::RBracket]) {
-                        parser.advance();
-                        return Some(res);
-                    }
-                }
-            } else if parser.probe_err(&[Token::RBracket]) {
+            if parser.probe(&[Token::RBracket]) {
+                parser.advance();
                 return Some(PackedDimension {
                     from: None,
                     to: None,
+                    span: parser.span_from(first),
                 });
             }
+            let from = Expression::parse(parser)?;
+            if parser.probe_err(&[Token::Colon]) {
+                parser.advance();
+                let to = Expression::parse(parser)?;
+                if parser.probe_err(&[Token::RBracket]) {
+                    parser.advance();
+                    return Some(PackedDimension {
+                        from: Some(from),
+                        to: Some(to),
+                        span: parser.span_from(first),
+                    });
+                }
+            }
         }
         None
     }
 }
 
-/// unpacked_dimension ::= [ constant_range ]
+/// unpacked_dimension ::= [ constant_range ] | [ constant_expression ]
 /// constant_range ::= constant_expression : constant_expression
-/// constant_expression ::= constant_primary
-/// constant_primary ::= primary_literal
-/// primary_literal ::= number
+/// queue_dimension ::= [ $ [ : constant_expression ] ]
+///
+/// `to` is `None` for a dimension given by its size, both are `None` for
+/// an unsized dimension. A queue has no `from` and its optional maximum
+/// index in `to`.
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct UnpackedDimension {
-    pub from: Option<Number>,
-    pub to: Option<Number>,
+    pub from: Option<Expression>,
+    pub to: Option<Expression>,
+    pub queue: bool,
+    pub span: NodeSpan,
 }
 
 impl Parse for UnpackedDimension {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
         if parser.probe_err(&[Token::LBracket]) {
             parser.advance();
-            if parser.probe(&[Token::Number]) {
-                let mut res = Self::default();
-                res.from = Number::parse(parser);
-                if parser.probe_err(&[Token::Colon]) {
+            let mut res = Self::default();
+            if parser.probe(&[Token::Dollar]) {
+                parser.advance();
+                res.queue = true;
+                if parser.probe(&[Token::Colon]) {
                     parser.advance();
-                    res.to = Number::parse(parser);
-                    if parser.probe_err(&[Token::RBracket]) {
-                        parser.advance();
-                        return Some(res);
-                    }
+                    res.to = Some(Expression::parse(parser)?);
+                }
+            } else if !parser.probe(&[Token::RBracket]) {
+                res.from = Some(Expression::parse(parser)?);
+                if parser.probe(&[Token::Colon]) {
+                    parser.advance();
+                    res.to = Some(Expression::parse(parser)?);
                 }
-            } else if parser.probe_err(&[Token::RBracket]) {
-                return Some(UnpackedDimension {
-                    from: None,
-                    to: None,
-                });
+            }
+            if parser.probe_err(&[Token::RBracket]) {
+                parser.advance();
+                res.span = parser.span_from(first);
+                return Some(res);
             }
         }
         None
     }
 }
+
+impl PackedDimension {
+    /// Evaluated bounds `(left, right)`, `None` for `[]` or bounds that are
+    /// not constant, see `Expression::constant_value`
+    pub fn range(
+        &self,
+        tokens: &[ParsedToken<'_>],
+        diag: &mut Vec<Diagnostic>,
+    ) -> Option<(i64, i64)> {
+        let left = self.from.as_ref()?.constant_value(tokens, diag)?;
+        let right = self.to.as_ref()?.constant_value(tokens, diag)?;
+        Some((left, right))
+    }
+
+    /// Number of elements of the range
+    pub fn width(&self, tokens: &[ParsedToken<'_>], diag: &mut Vec<Diagnostic>) -> Option<u64> {
+        let (left, right) = self.range(tokens, diag)?;
+        Some(left.abs_diff(right) + 1)
+    }
+}
+
+spanned!(PackedDimension, UnpackedDimension);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimensions() {
+        let mut parser = Parser::from("[7:0][]");
+        let m = PackedDimension::parse(&mut parser).unwrap();
+        assert_eq!(m.span, NodeSpan { first: 0, last: 4 });
+        let m = PackedDimension::parse(&mut parser).unwrap();
+        assert_eq!(m.from, None);
+        assert_eq!(m.span, NodeSpan { first: 5, last: 6 });
+        assert!(!parser.avail());
+
+        let mut parser = Parser::from("[WIDTH-1:0][8]");
+        let m = PackedDimension::parse(&mut parser).unwrap();
+        assert!(matches!(m.from, Some(Expression::Binary(_))));
+        let m = UnpackedDimension::parse(&mut parser).unwrap();
+        assert_eq!(m.from, Some(Expression::Number(Number { token: 8 })));
+        assert_eq!(m.to, None);
+
+        let mut parser = Parser::from("[$] [$:255] []");
+        let m = UnpackedDimension::parse(&mut parser).unwrap();
+        assert!(m.queue);
+        assert_eq!(m.to, None);
+        let m = UnpackedDimension::parse(&mut parser).unwrap();
+        assert!(m.queue);
+        assert_eq!(m.to, Some(Expression::Number(Number { token: 6 })));
+        let m = UnpackedDimension::parse(&mut parser).unwrap();
+        assert!(!m.queue);
+        assert_eq!(m.from, None);
+        assert!(parser.get_diag().is_empty());
+    }
+
+    #[test]
+    fn range() {
+        let mut parser = Parser::from("[6:0] [0:2**3-1] [$clog2(5)-1:'h0] [W-1:0] [4'hFF:0]");
+        let dimensions: Vec<PackedDimension> = (0..5)
+            .map(|_| PackedDimension::parse(&mut parser).unwrap())
+            .collect();
+        let (tokens, _) = parser.finish();
+        let mut diag = vec![];
+        assert_eq!(dimensions[0].range(&tokens, &mut diag), Some((6, 0)));
+        assert_eq!(dimensions[0].width(&tokens, &mut diag), Some(7));
+        assert_eq!(dimensions[1].range(&tokens, &mut diag), Some((0, 7)));
+        assert_eq!(dimensions[2].range(&tokens, &mut diag), Some((2, 0)));
+        assert_eq!(dimensions[3].range(&tokens, &mut diag), None);
+        assert!(diag.is_empty());
+        assert_eq!(dimensions[4].range(&tokens, &mut diag), Some((15, 0)));
+        assert_eq!(diag[0].msg, Message::NumberTruncated("4'hFF".to_owned(), 4));
+    }
+}
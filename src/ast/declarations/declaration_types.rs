@@ -9,10 +9,12 @@ macro_rules! port_declaration {
         pub struct $s {
             pub port_type: NetPortType,
             pub identifiers: ListOfPortIdentifiers,
+            pub span: NodeSpan,
         }
 
         impl Parse for $s {
             fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+                let first = parser.start();
                 if parser.probe(&[Token::$tok]) {
                     parser.advance();
                     if let Some(port_type) = NetPortType::parse(parser) {
@@ -20,6 +22,7 @@ macro_rules! port_declaration {
                             return Some($s {
                                 port_type,
                                 identifiers,
+                                span: parser.span_from(first),
                             });
                         }
                     }
@@ -27,6 +30,8 @@ macro_rules! port_declaration {
                 None
             }
         }
+
+        spanned!($s);
     };
 }
 
@@ -48,6 +53,519 @@ port_declaration! {
     OutputDeclaration, Output, output
 }
 
+/// # A.2.1.1 Module parameter declarations
+/// ## local_parameter_declaration ::= localparam data_type_or_implicit list_of_param_assignments
+/// | localparam type list_of_type_assignments
+/// ## parameter_declaration ::= parameter data_type_or_implicit list_of_param_assignments
+/// | parameter type list_of_type_assignments
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ParameterDeclaration {
+    /// `None` in a parameter port list declaration without keyword
+    pub keyword: Option<ParameterKeyword>,
+    pub parameter_type: ParameterType,
+    pub assignments: Vec<ParamAssignment>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum ParameterKeyword {
+    Parameter,
+    Localparam,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum ParameterType {
+    Data(DataTypeOrImplicit),
+    /// `type` parameters, their values are data types
+    Type,
+}
+
+impl Default for ParameterType {
+    fn default() -> Self {
+        Self::Data(DataTypeOrImplicit::ImplicitData(ImplicitDataType::default()))
+    }
+}
+
+impl Parse for ParameterDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if !parser.probe_err(&[Token::Parameter, Token::Localparam]) {
+            return None;
+        }
+        Self::parse_optional_keyword(parser)
+    }
+}
+
+impl ParameterDeclaration {
+    /// A declaration whose keyword may be omitted, as in parameter port lists
+    pub(crate) fn parse_optional_keyword(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = ParameterDeclaration::default();
+        if parser.probe(&[Token::Parameter, Token::Localparam]) {
+            res.keyword = Some(if parser.probe(&[Token::Parameter]) {
+                ParameterKeyword::Parameter
+            } else {
+                ParameterKeyword::Localparam
+            });
+            parser.advance();
+        }
+        if parser.probe(&[Token::Type]) {
+            parser.advance();
+            res.parameter_type = ParameterType::Type;
+        } else if DataType::is_data_type(parser) || parser.probe(ImplicitDataType::FIRST) {
+            res.parameter_type = ParameterType::Data(DataTypeOrImplicit::parse(parser)?);
+        }
+        // list_of_param_assignments ::= param_assignment { , param_assignment }
+        loop {
+            res.assignments.push(ParamAssignment::parse(parser)?);
+            // in port lists a data type may start the next declaration
+            let another = {
+                let mut next = parser.lookahead().skip(1);
+                next.next() == Some(Token::Identifier) && next.next() != Some(Token::Identifier)
+            };
+            if parser.probe(&[Token::Comma]) && another {
+                parser.advance();
+            } else {
+                break;
+            }
+        }
+        if res.parameter_type == ParameterType::Type {
+            for assignment in &res.assignments {
+                parser.declare_type(assignment.identifier.token);
+            }
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.1.3 Type declarations
+/// ## net_declaration ::= net_type [ drive_strength | charge_strength ] [ vectored | scalared ]
+/// data_type_or_implicit [ delay3 ] list_of_net_decl_assignments ;
+/// | net_type_identifier [ delay_control ] list_of_net_decl_assignments ;
+/// | interconnect implicit_data_type [ # delay_value ] net_identifier { unpacked_dimension }
+/// [ , net_identifier { unpacked_dimension } ] ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct NetDeclaration {
+    pub kind: NetKind,
+    pub strength: Option<NetStrength>,
+    pub vectored: Option<VectoredScalared>,
+    pub data_type: DataTypeOrImplicit,
+    pub delay: Option<Delay3>,
+    pub assignments: Vec<NetDeclAssignment>,
+    pub span: NodeSpan,
+}
+
+/// The net type of a net declaration
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum NetKind {
+    Net(NetType),
+    /// A type declared by `nettype`
+    User(Identifier),
+    Interconnect,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum NetStrength {
+    Drive(DriveStrength),
+    Charge(ChargeStrength),
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum VectoredScalared {
+    Vectored,
+    Scalared,
+}
+
+impl Parse for NetDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = NetDeclaration {
+            kind: NetKind::Interconnect,
+            strength: None,
+            vectored: None,
+            data_type: DataTypeOrImplicit::ImplicitData(ImplicitDataType::default()),
+            delay: None,
+            assignments: vec![],
+            span: NodeSpan::default(),
+        };
+        if parser.probe(&[Token::Interconnect]) {
+            parser.advance();
+            res.data_type = DataTypeOrImplicit::ImplicitData(ImplicitDataType::parse(parser)?);
+            if parser.probe(&[Token::Sharp]) {
+                res.delay = Some(Delay3::parse(parser)?);
+            }
+            loop {
+                let first = parser.start();
+                let mut assignment = NetDeclAssignment {
+                    identifier: Identifier::parse(parser)?,
+                    ..NetDeclAssignment::default()
+                };
+                while parser.probe(&[Token::LBracket]) {
+                    assignment
+                        .dimensions
+                        .push(UnpackedDimension::parse(parser)?);
+                }
+                assignment.span = parser.span_from(first);
+                res.assignments.push(assignment);
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+        } else if parser.probe(&[Token::Identifier]) {
+            res.kind = NetKind::User(Identifier::parse(parser)?);
+            if parser.probe(&[Token::Sharp]) {
+                res.delay = Some(Delay3::parse(parser)?);
+            }
+            res.assignments = NetDeclAssignment::parse_list(parser)?;
+        } else {
+            if !parser.probe_err(NetType::FIRST) {
+                return None;
+            }
+            res.kind = NetKind::Net(NetType::parse(parser)?);
+            if parser.probe(&[Token::LParen]) {
+                res.strength = Some(
+                    if parser.probe_next(&[Token::Small, Token::Medium, Token::Large]) {
+                        NetStrength::Charge(ChargeStrength::parse(parser)?)
+                    } else {
+                        NetStrength::Drive(DriveStrength::parse(parser)?)
+                    },
+                );
+            }
+            if parser.probe(&[Token::Vectored, Token::Scalared]) {
+                res.vectored = Some(if parser.probe(&[Token::Vectored]) {
+                    VectoredScalared::Vectored
+                } else {
+                    VectoredScalared::Scalared
+                });
+                parser.advance();
+            }
+            if DataType::is_data_type(parser) || parser.probe(ImplicitDataType::FIRST) {
+                res.data_type = DataTypeOrImplicit::parse(parser)?;
+            }
+            if parser.probe(&[Token::Sharp]) {
+                res.delay = Some(Delay3::parse(parser)?);
+            }
+            res.assignments = NetDeclAssignment::parse_list(parser)?;
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl NetDeclaration {
+    /// Whether the next tokens declare nets of a user-defined net type
+    ///
+    /// Both these and module instantiations start with two identifiers, only
+    /// instantiations continue with a `(`.
+    pub(crate) fn is_user_net(parser: &Parser<'_>) -> bool {
+        let mut tokens = parser.lookahead().peekable();
+        if tokens.next() != Some(Token::Identifier) {
+            return false;
+        }
+        if tokens.next_if_eq(&Token::Sharp).is_some() {
+            if tokens.peek() == Some(&Token::LParen) {
+                skip_group(&mut tokens);
+            } else {
+                tokens.next();
+            }
+        }
+        if tokens.next() != Some(Token::Identifier) {
+            return false;
+        }
+        while tokens.peek() == Some(&Token::LBracket) {
+            skip_group(&mut tokens);
+        }
+        matches!(
+            tokens.next(),
+            Some(Token::Semicolon | Token::Comma | Token::Equal)
+        )
+    }
+}
+
+/// Skip a parenthesized or bracketed group of tokens
+pub(crate) fn skip_group(tokens: &mut impl Iterator<Item = Token>) {
+    let mut depth = 0;
+    for token in tokens {
+        match token {
+            Token::LParen | Token::LBracket | Token::LBraces => depth += 1,
+            Token::RParen | Token::RBracket | Token::RBraces => depth -= 1,
+            _ => {}
+        }
+        if depth <= 0 {
+            break;
+        }
+    }
+}
+
+/// # A.2.1.3 Type declarations
+/// ## data_declaration ::= [ const ] [ var ] [ lifetime ] data_type_or_implicit list_of_variable_decl_assignments ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DataDeclaration {
+    pub constant: bool,
+    pub var: bool,
+    pub lifetime: Option<Lifetime>,
+    pub data_type: DataTypeOrImplicit,
+    pub assignments: Vec<VariableDeclAssignment>,
+    pub span: NodeSpan,
+}
+
+impl DataDeclaration {
+    /// Whether a data declaration starts at the current token
+    pub(crate) fn is_data_declaration(parser: &mut Parser<'_>) -> bool {
+        DataType::is_data_type(parser)
+            || parser.probe(&[Token::Const, Token::Var])
+            || parser.probe(Lifetime::FIRST)
+    }
+}
+
+impl Parse for DataDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = DataDeclaration::default();
+        if parser.probe(&[Token::Const]) {
+            parser.advance();
+            res.constant = true;
+        }
+        if parser.probe(&[Token::Var]) {
+            parser.advance();
+            res.var = true;
+        }
+        if parser.probe(Lifetime::FIRST) {
+            res.lifetime = Some(Lifetime::parse(parser)?);
+        }
+        if res.var {
+            res.data_type = DataTypeOrImplicit::parse(parser)?;
+        } else if DataType::is_data_type(parser) {
+            res.data_type = DataTypeOrImplicit::Data(DataType::parse(parser)?);
+        } else {
+            parser.probe_err(DataType::FIRST);
+            return None;
+        }
+        res.assignments = VariableDeclAssignment::parse_list(parser)?;
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.1.3 Type declarations
+/// ## lifetime ::= static | automatic
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Lifetime {
+    Static,
+    Automatic,
+}
+
+impl Lifetime {
+    pub(crate) const FIRST: &'static [Token] = &[Token::Static, Token::Automatic];
+}
+
+impl Parse for Lifetime {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if !parser.probe_err(Self::FIRST) {
+            return None;
+        }
+        let res = match parser.peek()?.token {
+            Token::Static => Lifetime::Static,
+            _ => Lifetime::Automatic,
+        };
+        parser.advance();
+        Some(res)
+    }
+}
+
+/// # A.2.1.3 Type declarations
+/// ## type_declaration ::= typedef data_type type_identifier { variable_dimension } ;
+/// | typedef [ enum | struct | union | class | interface class ] type_identifier ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TypeDeclaration {
+    /// `None` for forward declarations
+    pub data_type: Option<DataType>,
+    /// Kind of type given by a forward declaration
+    pub forward: Option<ForwardType>,
+    pub identifier: Identifier,
+    pub dimensions: Vec<UnpackedDimension>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum ForwardType {
+    Enum,
+    Struct,
+    Union,
+    Class,
+    InterfaceClass,
+}
+
+impl TypeDeclaration {
+    /// Whether a forward declaration follows `typedef`
+    fn is_forward(parser: &Parser<'_>) -> bool {
+        let mut tokens = parser.lookahead().peekable();
+        match tokens.next() {
+            Some(Token::Enum | Token::Struct | Token::Union | Token::Class) => {}
+            Some(Token::Interface) => return tokens.next() == Some(Token::Class),
+            Some(Token::Identifier) => return tokens.next() == Some(Token::Semicolon),
+            _ => return false,
+        }
+        tokens.next() == Some(Token::Identifier) && tokens.next() == Some(Token::Semicolon)
+    }
+}
+
+impl Parse for TypeDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::TypeDef]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = TypeDeclaration::default();
+        if Self::is_forward(parser) {
+            res.forward = match parser.peek()?.token {
+                Token::Enum => Some(ForwardType::Enum),
+                Token::Struct => Some(ForwardType::Struct),
+                Token::Union => Some(ForwardType::Union),
+                Token::Class => Some(ForwardType::Class),
+                Token::Interface => {
+                    parser.advance();
+                    Some(ForwardType::InterfaceClass)
+                }
+                _ => None,
+            };
+            if res.forward.is_some() {
+                parser.advance();
+            }
+        } else if DataType::is_data_type(parser) {
+            res.data_type = Some(DataType::parse(parser)?);
+        } else {
+            parser.probe_err(DataType::FIRST);
+            return None;
+        }
+        res.identifier = Identifier::parse(parser)?;
+        if res.data_type.is_some() {
+            while parser.probe(&[Token::LBracket]) {
+                res.dimensions.push(UnpackedDimension::parse(parser)?);
+            }
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.1.3 Type declarations
+/// ## genvar_declaration ::= genvar list_of_genvar_identifiers ;
+/// ## list_of_genvar_identifiers ::= genvar_identifier { , genvar_identifier }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct GenvarDeclaration {
+    pub identifiers: Vec<Identifier>,
+    pub span: NodeSpan,
+}
+
+impl Parse for GenvarDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::GenVar]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = GenvarDeclaration::default();
+        loop {
+            res.identifiers.push(Identifier::parse(parser)?);
+            if !parser.probe(&[Token::Comma]) {
+                break;
+            }
+            parser.advance();
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// # A.2.1.3 Type declarations
+/// ## nettype_declaration ::= nettype data_type nettype_identifier [ with tf_identifier ] ;
+/// | nettype net_type_identifier nettype_identifier ;
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct NetTypeDeclaration {
+    pub base: NetTypeBase,
+    pub identifier: Identifier,
+    /// Resolution function given by `with`
+    pub resolution: Option<Identifier>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum NetTypeBase {
+    Data(DataType),
+    /// Another name for an existing net type
+    NetType(Identifier),
+}
+
+impl Parse for NetTypeDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::NetType]) {
+            return None;
+        }
+        parser.advance();
+        let base = if parser.probe(&[Token::Identifier]) {
+            NetTypeBase::NetType(Identifier::parse(parser)?)
+        } else if parser.probe_err(DataType::FIRST) {
+            NetTypeBase::Data(DataType::parse(parser)?)
+        } else {
+            return None;
+        };
+        let identifier = Identifier::parse(parser)?;
+        let mut resolution = None;
+        if matches!(base, NetTypeBase::Data(_)) && parser.probe(&[Token::With]) {
+            parser.advance();
+            resolution = Some(Identifier::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        Some(NetTypeDeclaration {
+            base,
+            identifier,
+            resolution,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+spanned!(
+    ParameterDeclaration,
+    DataDeclaration,
+    TypeDeclaration,
+    GenvarDeclaration,
+    NetDeclaration,
+    NetTypeDeclaration
+);
+
+impl Spanned for NetStrength {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            NetStrength::Drive(strength) => strength.node_span(),
+            NetStrength::Charge(strength) => strength.node_span(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,5 +578,98 @@ mod tests {
             m.as_ref().unwrap().port_type.net_type.as_ref().unwrap(),
             &NetType::Wire
         );
+        assert_eq!(m.as_ref().unwrap().span, NodeSpan { first: 0, last: 7 });
+    }
+
+    #[test]
+    fn net_declaration() {
+        let mut parser = Parser::from(
+            "tri1 devclrn; wire (strong0, weak1) vectored signed [7:0] #(1, 2) a = 0, b; \
+             trireg (large) c; interconnect [3:0] d [2], e; mynet #3 f; nettype logic [1:0] mynet with resolve;",
+        );
+        let net = NetDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(net.kind, NetKind::Net(NetType::Tri1));
+        assert_eq!(net.assignments.len(), 1);
+        assert_eq!(net.span, NodeSpan { first: 0, last: 2 });
+
+        let net = NetDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(net.kind, NetKind::Net(NetType::Wire));
+        match &net.strength {
+            Some(NetStrength::Drive(strength)) => {
+                assert_eq!(strength.strength0, Strength::Strong);
+                assert_eq!(strength.strength1, Strength::Weak);
+            }
+            strength => panic!("{:?}", strength),
+        }
+        assert_eq!(net.vectored, Some(VectoredScalared::Vectored));
+        assert!(
+            matches!(net.data_type, DataTypeOrImplicit::ImplicitData(ref data) if data.sign.is_some())
+        );
+        assert_eq!(net.delay.as_ref().unwrap().values.len(), 2);
+        assert!(net.assignments[0].init.is_some());
+        assert!(net.assignments[1].init.is_none());
+
+        let net = NetDeclaration::parse(&mut parser).unwrap();
+        assert!(matches!(
+            net.strength,
+            Some(NetStrength::Charge(ChargeStrength {
+                size: ChargeSize::Large,
+                ..
+            }))
+        ));
+
+        let net = NetDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(net.kind, NetKind::Interconnect);
+        assert_eq!(net.assignments[0].dimensions.len(), 1);
+        assert_eq!(net.assignments.len(), 2);
+
+        assert!(NetDeclaration::is_user_net(&parser));
+        let net = NetDeclaration::parse(&mut parser).unwrap();
+        assert!(matches!(net.kind, NetKind::User(_)));
+        assert!(net.delay.is_some());
+
+        let nettype = NetTypeDeclaration::parse(&mut parser).unwrap();
+        assert!(matches!(nettype.base, NetTypeBase::Data(_)));
+        assert!(nettype.resolution.is_some());
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let parser = Parser::from("mod #(.W(8)) u [3:0] (a);");
+        assert!(!NetDeclaration::is_user_net(&parser));
+        let parser = Parser::from("mynet a [3:0], b;");
+        assert!(NetDeclaration::is_user_net(&parser));
+    }
+
+    #[test]
+    fn type_declaration() {
+        let mut parser = Parser::from(
+            "typedef enum bit { OFF, ON } switch_t; typedef logic [7:0] bytes_t [4]; \
+             typedef struct node; typedef interface class ic; typedef fwd;",
+        );
+        let typedef = TypeDeclaration::parse(&mut parser).unwrap();
+        assert!(matches!(
+            typedef.data_type,
+            Some(DataType {
+                kind: DataTypeKind::Enum(_),
+                ..
+            })
+        ));
+        assert_eq!(typedef.identifier, Identifier { token: 8 });
+        assert_eq!(typedef.span, NodeSpan { first: 0, last: 9 });
+
+        let typedef = TypeDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(typedef.dimensions.len(), 1);
+        assert_eq!(typedef.forward, None);
+
+        let typedef = TypeDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(typedef.forward, Some(ForwardType::Struct));
+        assert_eq!(typedef.data_type, None);
+        let typedef = TypeDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(typedef.forward, Some(ForwardType::InterfaceClass));
+        let typedef = TypeDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(typedef.forward, None);
+        assert_eq!(typedef.data_type, None);
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
     }
 }
@@ -0,0 +1,304 @@
+//! A.2.6 Function declarations
+
+use crate::ast::*;
+
+/// function_declaration ::= function [ lifetime ] function_body_declaration
+/// function_body_declaration ::= function_data_type_or_implicit function_identifier ;
+/// { tf_item_declaration } { function_statement_or_null } endfunction [ : function_identifier ]
+/// | function_data_type_or_implicit function_identifier ( [ tf_port_list ] ) ;
+/// { block_item_declaration } { function_statement_or_null } endfunction [ : function_identifier ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct FunctionDeclaration {
+    pub lifetime: Option<Lifetime>,
+    pub return_type: FunctionReturnType,
+    pub identifier: Identifier,
+    /// `None` if the ports are declared in the body
+    pub ports: Option<Vec<TfPortItem>>,
+    pub items: Vec<TfItemDeclaration>,
+    pub statements: Vec<Statement>,
+    pub end_label: Option<Identifier>,
+    pub span: NodeSpan,
+}
+
+impl Parse for FunctionDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Function]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = FunctionDeclaration::default();
+        if parser.probe(Lifetime::FIRST) {
+            res.lifetime = Some(Lifetime::parse(parser)?);
+        }
+        res.return_type = FunctionReturnType::parse(parser)?;
+        res.identifier = Identifier::parse(parser)?;
+        if parser.probe(&[Token::LParen]) {
+            res.ports = Some(TfPortItem::parse_list(parser)?);
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.items = TfItemDeclaration::parse_items(parser)?;
+        while parser.avail() && !parser.probe(&[Token::EndFunction]) {
+            res.statements.push(Statement::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::EndFunction]) {
+            return None;
+        }
+        parser.advance();
+        res.end_label = parse_end_label(parser, Some(&res.identifier))?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// function_data_type_or_implicit ::= data_type_or_void | implicit_data_type
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum FunctionReturnType {
+    Void,
+    Data(DataTypeOrImplicit),
+}
+
+impl Default for FunctionReturnType {
+    fn default() -> Self {
+        Self::Data(DataTypeOrImplicit::ImplicitData(ImplicitDataType::default()))
+    }
+}
+
+impl Parse for FunctionReturnType {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if parser.probe(&[Token::Void]) {
+            parser.advance();
+            Some(FunctionReturnType::Void)
+        } else if DataType::is_data_type(parser) || parser.probe(ImplicitDataType::FIRST) {
+            Some(FunctionReturnType::Data(DataTypeOrImplicit::parse(parser)?))
+        } else {
+            Some(FunctionReturnType::default())
+        }
+    }
+}
+
+/// dpi_import_export ::= import dpi_spec_string [ dpi_function_import_property ] [ c_identifier = ] dpi_function_proto ;
+/// | import dpi_spec_string [ dpi_task_import_property ] [ c_identifier = ] dpi_task_proto ;
+/// | export dpi_spec_string [ c_identifier = ] function function_identifier ;
+/// | export dpi_spec_string [ c_identifier = ] task task_identifier ;
+/// dpi_spec_string ::= "DPI-C" | "DPI"
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DpiImportExport {
+    pub direction: DpiDirection,
+    pub spec: StringLiteral,
+    pub property: Option<DpiProperty>,
+    /// Name of the C function if it differs from the SystemVerilog name
+    pub c_identifier: Option<Identifier>,
+    /// Exports only give the kind and the name
+    pub prototype: SubroutinePrototype,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum DpiDirection {
+    #[default]
+    Import,
+    Export,
+}
+
+/// dpi_function_import_property ::= context | pure
+/// dpi_task_import_property ::= context
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum DpiProperty {
+    Context,
+    Pure,
+}
+
+impl Parse for DpiImportExport {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Import, Token::Export]) {
+            return None;
+        }
+        let mut res = DpiImportExport {
+            direction: match parser.peek()?.token {
+                Token::Export => DpiDirection::Export,
+                _ => DpiDirection::Import,
+            },
+            ..DpiImportExport::default()
+        };
+        parser.advance();
+        res.spec = StringLiteral::parse(parser)?;
+        if res.direction == DpiDirection::Import && parser.probe(&[Token::Context, Token::Pure]) {
+            res.property = Some(match parser.peek()?.token {
+                Token::Pure => DpiProperty::Pure,
+                _ => DpiProperty::Context,
+            });
+            parser.advance();
+        }
+        if parser.probe(&[Token::Identifier]) {
+            res.c_identifier = Some(Identifier::parse(parser)?);
+            if !parser.probe_err(&[Token::Equal]) {
+                return None;
+            }
+            parser.advance();
+        }
+        res.prototype = match res.direction {
+            DpiDirection::Import => SubroutinePrototype::parse(parser)?,
+            DpiDirection::Export => SubroutinePrototype::parse_name(parser)?,
+        };
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// function_prototype ::= function data_type_or_void function_identifier [ ( [ tf_port_list ] ) ]
+/// task_prototype ::= task task_identifier [ ( [ tf_port_list ] ) ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SubroutinePrototype {
+    pub kind: SubroutineKind,
+    /// `None` for tasks
+    pub return_type: Option<FunctionReturnType>,
+    pub identifier: Identifier,
+    pub ports: Option<Vec<TfPortItem>>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum SubroutineKind {
+    #[default]
+    Function,
+    Task,
+}
+
+impl Parse for SubroutinePrototype {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Function, Token::Task]) {
+            return None;
+        }
+        let mut res = SubroutinePrototype::default();
+        if parser.probe(&[Token::Task]) {
+            res.kind = SubroutineKind::Task;
+        }
+        parser.advance();
+        if res.kind == SubroutineKind::Function {
+            res.return_type = Some(FunctionReturnType::parse(parser)?);
+        }
+        res.identifier = Identifier::parse(parser)?;
+        if parser.probe(&[Token::LParen]) {
+            res.ports = Some(TfPortItem::parse_list(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl SubroutinePrototype {
+    /// function function_identifier | task task_identifier
+    fn parse_name(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Function, Token::Task]) {
+            return None;
+        }
+        let kind = match parser.peek()?.token {
+            Token::Task => SubroutineKind::Task,
+            _ => SubroutineKind::Function,
+        };
+        parser.advance();
+        Some(SubroutinePrototype {
+            kind,
+            identifier: Identifier::parse(parser)?,
+            span: parser.span_from(first),
+            ..SubroutinePrototype::default()
+        })
+    }
+}
+
+spanned!(FunctionDeclaration, DpiImportExport, SubroutinePrototype);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn function_declaration() {
+        let mut parser = Parser::from(
+            "function automatic logic [7:0] add(input [7:0] a, b = 1);
+               logic [7:0] sum;
+               sum = a + b;
+               return sum;
+             endfunction : add",
+        );
+        let function = FunctionDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(function.lifetime, Some(Lifetime::Automatic));
+        assert!(matches!(
+            function.return_type,
+            FunctionReturnType::Data(DataTypeOrImplicit::Data(_))
+        ));
+        let ports = function.ports.as_ref().unwrap();
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports[1].direction, None);
+        assert!(ports[1].default.is_some());
+        assert_eq!(function.items.len(), 1);
+        assert_eq!(function.statements.len(), 2);
+        assert!(function.end_label.is_some());
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from(
+            "function void log;
+               input [3:0] level;
+               parameter P = 1;
+               $display(level);
+             endfunction",
+        );
+        let function = FunctionDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(function.return_type, FunctionReturnType::Void);
+        assert_eq!(function.ports, None);
+        assert_eq!(function.items.len(), 2);
+        assert_eq!(function.statements.len(), 1);
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("function [3:0] f(); endfunction");
+        let function = FunctionDeclaration::parse(&mut parser).unwrap();
+        assert!(matches!(
+            function.return_type,
+            FunctionReturnType::Data(DataTypeOrImplicit::ImplicitData(_))
+        ));
+        assert_eq!(function.ports, Some(vec![]));
+    }
+
+    #[test]
+    fn dpi_import_export() {
+        let mut parser = Parser::from(
+            r#"import "DPI-C" pure c_add = function bit [7:0] add(bit [7:0], bit [7:0]);"#,
+        );
+        let dpi = DpiImportExport::parse(&mut parser).unwrap();
+        assert_eq!(dpi.direction, DpiDirection::Import);
+        assert_eq!(dpi.property, Some(DpiProperty::Pure));
+        assert_eq!(dpi.c_identifier, Some(Identifier { token: 3 }));
+        assert_eq!(dpi.prototype.kind, SubroutineKind::Function);
+        let ports = dpi.prototype.ports.as_ref().unwrap();
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports[0].identifier, None);
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from(r#"import "DPI-C" context task wait_cycles(input bit n);"#);
+        let dpi = DpiImportExport::parse(&mut parser).unwrap();
+        assert_eq!(dpi.property, Some(DpiProperty::Context));
+        assert_eq!(dpi.prototype.kind, SubroutineKind::Task);
+        assert_eq!(dpi.prototype.return_type, None);
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from(r#"export "DPI-C" function sv_callback;"#);
+        let dpi = DpiImportExport::parse(&mut parser).unwrap();
+        assert_eq!(dpi.direction, DpiDirection::Export);
+        assert_eq!(dpi.prototype.identifier, Identifier { token: 3 });
+        assert_eq!(dpi.prototype.ports, None);
+        assert_eq!(dpi.span, NodeSpan { first: 0, last: 4 });
+    }
+}
@@ -1,11 +1,19 @@
 //! A.2 Declarations
 
+mod block_item_declarations;
+mod declaration_assignments;
 mod declaration_data_types;
 mod declaration_lists;
 mod declaration_ranges;
 mod declaration_types;
+mod function_declarations;
+mod task_declarations;
 
+pub use block_item_declarations::*;
+pub use declaration_assignments::*;
 pub use declaration_data_types::*;
 pub use declaration_lists::*;
 pub use declaration_ranges::*;
 pub use declaration_types::*;
+pub use function_declarations::*;
+pub use task_declarations::*;
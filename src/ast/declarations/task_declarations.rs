@@ -0,0 +1,314 @@
+//! A.2.7 Task declarations
+
+use crate::ast::*;
+
+/// task_declaration ::= task [ lifetime ] task_body_declaration
+/// task_body_declaration ::= task_identifier ;
+/// { tf_item_declaration } { statement_or_null } endtask [ : task_identifier ]
+/// | task_identifier ( [ tf_port_list ] ) ;
+/// { block_item_declaration } { statement_or_null } endtask [ : task_identifier ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TaskDeclaration {
+    pub lifetime: Option<Lifetime>,
+    pub identifier: Identifier,
+    /// `None` if the ports are declared in the body
+    pub ports: Option<Vec<TfPortItem>>,
+    pub items: Vec<TfItemDeclaration>,
+    pub statements: Vec<Statement>,
+    pub end_label: Option<Identifier>,
+    pub span: NodeSpan,
+}
+
+impl Parse for TaskDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Task]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = TaskDeclaration::default();
+        if parser.probe(Lifetime::FIRST) {
+            res.lifetime = Some(Lifetime::parse(parser)?);
+        }
+        res.identifier = Identifier::parse(parser)?;
+        if parser.probe(&[Token::LParen]) {
+            res.ports = Some(TfPortItem::parse_list(parser)?);
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.items = TfItemDeclaration::parse_items(parser)?;
+        while parser.avail() && !parser.probe(&[Token::EndTask]) {
+            res.statements.push(Statement::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::EndTask]) {
+            return None;
+        }
+        parser.advance();
+        res.end_label = parse_end_label(parser, Some(&res.identifier))?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// tf_item_declaration ::= block_item_declaration | tf_port_declaration
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum TfItemDeclaration {
+    Port(TfPortDeclaration),
+    Block(BlockItemDeclaration),
+}
+
+impl TfItemDeclaration {
+    /// Declarations before the statements of a function or task
+    pub(crate) fn parse_items(parser: &mut Parser<'_>) -> Option<Vec<Self>> {
+        let mut res = vec![];
+        loop {
+            if TfPortDirection::is_direction(parser) {
+                res.push(TfItemDeclaration::Port(TfPortDeclaration::parse(parser)?));
+            } else if BlockItemDeclaration::is_block_item(parser) {
+                res.push(TfItemDeclaration::Block(BlockItemDeclaration::parse(
+                    parser,
+                )?));
+            } else {
+                return Some(res);
+            }
+        }
+    }
+}
+
+impl Spanned for TfItemDeclaration {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            TfItemDeclaration::Port(item) => item.node_span(),
+            TfItemDeclaration::Block(item) => item.node_span(),
+        }
+    }
+}
+
+/// tf_port_item ::= { attribute_instance } [ tf_port_direction ] [ var ] data_type_or_implicit
+/// [ port_identifier { variable_dimension } [ = expression ] ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TfPortItem {
+    pub attributes: Attributes,
+    pub direction: Option<TfPortDirection>,
+    pub var: bool,
+    pub data_type: DataTypeOrImplicit,
+    /// `None` in prototypes that only give the types
+    pub identifier: Option<Identifier>,
+    pub dimensions: Vec<UnpackedDimension>,
+    /// Default value of the argument
+    pub default: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for TfPortItem {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = TfPortItem::default();
+        if parser.probe(&[Token::LParen]) {
+            res.attributes = Attributes::parse(parser)?;
+        }
+        if TfPortDirection::is_direction(parser) {
+            res.direction = Some(TfPortDirection::parse(parser)?);
+        }
+        if parser.probe(&[Token::Var]) {
+            parser.advance();
+            res.var = true;
+        }
+        if DataType::is_data_type(parser) || parser.probe(ImplicitDataType::FIRST) {
+            res.data_type = DataTypeOrImplicit::parse(parser)?;
+        } else {
+            res.data_type = DataTypeOrImplicit::ImplicitData(ImplicitDataType::default());
+        }
+        if parser.probe(&[Token::Identifier]) {
+            res.identifier = Some(Identifier::parse(parser)?);
+            while parser.probe(&[Token::LBracket]) {
+                res.dimensions.push(UnpackedDimension::parse(parser)?);
+            }
+            if parser.probe(&[Token::Equal]) {
+                parser.advance();
+                res.default = Some(Expression::parse(parser)?);
+            }
+        }
+        match parser.optional_span_from(first) {
+            Some(span) => {
+                res.span = span;
+                Some(res)
+            }
+            None => {
+                parser.probe_err(&[Token::Identifier]);
+                None
+            }
+        }
+    }
+}
+
+impl TfPortItem {
+    /// ( [ tf_port_list ] )
+    /// tf_port_list ::= tf_port_item { , tf_port_item }
+    pub(crate) fn parse_list(parser: &mut Parser<'_>) -> Option<Vec<Self>> {
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = vec![];
+        if !parser.probe(&[Token::RParen]) {
+            loop {
+                res.push(TfPortItem::parse(parser)?);
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+        }
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        Some(res)
+    }
+}
+
+/// tf_port_direction ::= port_direction | const ref
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum TfPortDirection {
+    Input,
+    Output,
+    InOut,
+    Ref,
+    ConstRef,
+}
+
+impl TfPortDirection {
+    /// Whether a direction starts at the current token, `const` alone starts
+    /// a data declaration
+    pub(crate) fn is_direction(parser: &mut Parser<'_>) -> bool {
+        parser.probe(&[Token::Input, Token::Output, Token::InOut, Token::Ref])
+            || (parser.probe(&[Token::Const]) && parser.probe_next(&[Token::Ref]))
+    }
+}
+
+impl Parse for TfPortDirection {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let tokens = [
+            Token::Input,
+            Token::Output,
+            Token::InOut,
+            Token::Ref,
+            Token::Const,
+        ];
+        if !parser.probe_err(&tokens) {
+            return None;
+        }
+        let res = match parser.peek()?.token {
+            Token::Input => TfPortDirection::Input,
+            Token::Output => TfPortDirection::Output,
+            Token::InOut => TfPortDirection::InOut,
+            Token::Ref => TfPortDirection::Ref,
+            _ => {
+                parser.advance();
+                if !parser.probe_err(&[Token::Ref]) {
+                    return None;
+                }
+                TfPortDirection::ConstRef
+            }
+        };
+        parser.advance();
+        Some(res)
+    }
+}
+
+/// tf_port_declaration ::= { attribute_instance } tf_port_direction [ var ] data_type_or_implicit list_of_tf_variable_identifiers ;
+/// list_of_tf_variable_identifiers ::= port_identifier { variable_dimension } [ = expression ]
+/// { , port_identifier { variable_dimension } [ = expression ] }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TfPortDeclaration {
+    pub direction: Option<TfPortDirection>,
+    pub var: bool,
+    pub data_type: DataTypeOrImplicit,
+    pub variables: Vec<VariableDeclAssignment>,
+    pub span: NodeSpan,
+}
+
+impl Parse for TfPortDeclaration {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = TfPortDeclaration {
+            direction: Some(TfPortDirection::parse(parser)?),
+            ..TfPortDeclaration::default()
+        };
+        if parser.probe(&[Token::Var]) {
+            parser.advance();
+            res.var = true;
+        }
+        if DataType::is_data_type(parser) || parser.probe(ImplicitDataType::FIRST) {
+            res.data_type = DataTypeOrImplicit::parse(parser)?;
+        } else {
+            res.data_type = DataTypeOrImplicit::ImplicitData(ImplicitDataType::default());
+        }
+        res.variables = VariableDeclAssignment::parse_list(parser)?;
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+spanned!(TaskDeclaration, TfPortItem, TfPortDeclaration);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_declaration() {
+        let mut parser = Parser::from(
+            "task automatic send(input [7:0] data, output logic ok = 0, const ref bit b [4]);
+               logic [3:0] count;
+               #1 ok = 1;
+             endtask : send",
+        );
+        let task = TaskDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(task.lifetime, Some(Lifetime::Automatic));
+        let ports = task.ports.as_ref().unwrap();
+        assert_eq!(ports.len(), 3);
+        assert_eq!(ports[0].direction, Some(TfPortDirection::Input));
+        assert!(ports[1].default.is_some());
+        assert_eq!(ports[2].direction, Some(TfPortDirection::ConstRef));
+        assert_eq!(ports[2].dimensions.len(), 1);
+        assert_eq!(task.items.len(), 1);
+        assert_eq!(task.statements.len(), 1);
+        assert!(task.end_label.is_some());
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("task send; endtask : recv");
+        assert!(TaskDeclaration::parse(&mut parser).is_some());
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::EndLabelMismatch("recv".to_owned(), Some("send".to_owned()))
+        );
+
+        let mut parser = Parser::from(
+            "task wait_cycles;
+               input integer_count;
+               input [3:0] a, b;
+               const logic c = 1;
+               repeat (integer_count) @(posedge clk);
+             endtask",
+        );
+        let task = TaskDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(task.ports, None);
+        assert_eq!(task.items.len(), 3);
+        match &task.items[1] {
+            TfItemDeclaration::Port(port) => assert_eq!(port.variables.len(), 2),
+            item => panic!("{:?}", item),
+        }
+        assert!(matches!(task.items[2], TfItemDeclaration::Block(_)));
+        assert_eq!(task.statements.len(), 1);
+        assert!(parser.get_diag().is_empty());
+    }
+}
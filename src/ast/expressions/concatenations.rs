@@ -0,0 +1,92 @@
+//! A.8.1 Concatenations
+
+use crate::ast::*;
+
+/// concatenation ::= { expression { , expression } }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Concatenation {
+    pub exprs: Vec<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for Concatenation {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::LBraces]) {
+            return None;
+        }
+        parser.advance();
+        if parser.probe(&[Token::RBraces]) {
+            parser.advance();
+            return Some(Concatenation {
+                exprs: vec![],
+                span: parser.span_from(first),
+            });
+        }
+        let expr = Expression::parse(parser)?;
+        Self::parse_rest(parser, first, expr)
+    }
+}
+
+impl Concatenation {
+    /// Parse a concatenation or a multiple concatenation
+    pub(crate) fn parse_expression(parser: &mut Parser<'_>) -> Option<Expression> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::LBraces]) {
+            return None;
+        }
+        parser.advance();
+        if parser.probe(&[Token::RBraces]) {
+            parser.advance();
+            return Some(Expression::Concatenation(Concatenation {
+                exprs: vec![],
+                span: parser.span_from(first),
+            }));
+        }
+        let expr = Expression::parse(parser)?;
+        if parser.probe(&[Token::LBraces]) {
+            let concatenation = Concatenation::parse(parser)?;
+            if !parser.probe_err(&[Token::RBraces]) {
+                return None;
+            }
+            parser.advance();
+            return Some(Expression::MultipleConcatenation(Box::new(
+                MultipleConcatenation {
+                    count: expr,
+                    concatenation,
+                    span: parser.span_from(first),
+                },
+            )));
+        }
+        Some(Expression::Concatenation(Self::parse_rest(
+            parser, first, expr,
+        )?))
+    }
+
+    /// Parse `{ , expression } }` after the first expression
+    fn parse_rest(parser: &mut Parser<'_>, first: TokenIndex, expr: Expression) -> Option<Self> {
+        let mut exprs = vec![expr];
+        while parser.probe(&[Token::Comma]) {
+            parser.advance();
+            exprs.push(Expression::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::RBraces]) {
+            return None;
+        }
+        parser.advance();
+        Some(Concatenation {
+            exprs,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// multiple_concatenation ::= { expression concatenation }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct MultipleConcatenation {
+    pub count: Expression,
+    pub concatenation: Concatenation,
+    pub span: NodeSpan,
+}
+
+spanned!(Concatenation, MultipleConcatenation);
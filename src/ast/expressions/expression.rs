@@ -0,0 +1,863 @@
+//! A.8.3 Expressions
+
+use crate::ast::*;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::logic_value::BitVector;
+use crate::system_tasks;
+
+/// expression ::= primary
+/// | unary_operator { attribute_instance } primary
+/// | expression binary_operator { attribute_instance } expression
+/// | conditional_expression
+/// | inside_expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum Expression {
+    Number(Number),
+    TimeLiteral(TimeLiteral),
+    StringLiteral(StringLiteral),
+    Name(Identifier),
+    Paren(Box<ParenExpression>),
+    Concatenation(Concatenation),
+    MultipleConcatenation(Box<MultipleConcatenation>),
+    AssignmentPattern(Box<AssignmentPattern>),
+    Select(Box<Select>),
+    Member(Box<MemberSelect>),
+    Call(Box<FunctionCall>),
+    SystemCall(Box<SystemTfCall>),
+    Cast(Box<Cast>),
+    Unary(Box<UnaryExpression>),
+    Binary(Box<BinaryExpression>),
+    Conditional(Box<ConditionalExpression>),
+    Inside(Box<InsideExpression>),
+    MinTypMax(Box<MinTypMaxExpression>),
+    /// `type(expr)`, a data type of kind `DataTypeKind::Reference`
+    Type(Box<DataType>),
+}
+
+impl Default for Expression {
+    fn default() -> Self {
+        Self::Number(Number::default())
+    }
+}
+
+impl Expression {
+    pub fn span(&self) -> NodeSpan {
+        match self {
+            Expression::Number(number) => NodeSpan::token(number.token),
+            Expression::TimeLiteral(time) => NodeSpan::token(time.token),
+            Expression::StringLiteral(string) => NodeSpan::token(string.token),
+            Expression::Name(identifier) => NodeSpan::token(identifier.token),
+            Expression::Paren(expr) => expr.span,
+            Expression::Concatenation(expr) => expr.span,
+            Expression::MultipleConcatenation(expr) => expr.span,
+            Expression::AssignmentPattern(expr) => expr.span,
+            Expression::Select(expr) => expr.span,
+            Expression::Member(expr) => expr.span,
+            Expression::Call(expr) => expr.span,
+            Expression::SystemCall(expr) => expr.span,
+            Expression::Cast(expr) => expr.span,
+            Expression::Unary(expr) => expr.span,
+            Expression::Binary(expr) => expr.span,
+            Expression::Conditional(expr) => expr.span,
+            Expression::Inside(expr) => expr.span,
+            Expression::MinTypMax(expr) => expr.span,
+            Expression::Type(data_type) => data_type.span,
+        }
+    }
+
+    /// Value of a constant integral expression, 11.2.1
+    ///
+    /// Numbers, operators and `$clog2` are evaluated. Returns `None` for
+    /// other expressions like parameter names, for x or z bits, for
+    /// overflow or division by zero and for results that depend on the
+    /// width of the operands. Problems with numbers and system function
+    /// calls are added to `diag`.
+    pub fn constant_value(
+        &self,
+        tokens: &[ParsedToken<'_>],
+        diag: &mut Vec<Diagnostic>,
+    ) -> Option<i64> {
+        match self {
+            Expression::Number(number) => {
+                let token = &tokens[number.token];
+                BitVector::parse(&token.text, token.span, diag)?.to_i64()
+            }
+            Expression::Paren(expr) => expr.expr.constant_value(tokens, diag),
+            Expression::Unary(expr) => {
+                let value = expr.operand.constant_value(tokens, diag)?;
+                match expr.op {
+                    UnaryOperator::Plus => Some(value),
+                    UnaryOperator::Minus => value.checked_neg(),
+                    UnaryOperator::Not => Some((value == 0) as i64),
+                    // bitwise negation and reductions depend on the width of the operand
+                    _ => None,
+                }
+            }
+            Expression::Binary(expr) => {
+                let lhs = expr.lhs.constant_value(tokens, diag)?;
+                let rhs = expr.rhs.constant_value(tokens, diag)?;
+                let shift = u32::try_from(rhs).ok();
+                match expr.op {
+                    BinaryOperator::Add => lhs.checked_add(rhs),
+                    BinaryOperator::Sub => lhs.checked_sub(rhs),
+                    BinaryOperator::Mul => lhs.checked_mul(rhs),
+                    BinaryOperator::Div => lhs.checked_div(rhs),
+                    BinaryOperator::Mod => lhs.checked_rem(rhs),
+                    BinaryOperator::Pow => lhs.checked_pow(shift?),
+                    // an unsigned operand makes the comparison unsigned, in
+                    // the width of the operands
+                    BinaryOperator::Equal
+                    | BinaryOperator::CaseEqual
+                    | BinaryOperator::Inequal
+                    | BinaryOperator::CaseInequal
+                    | BinaryOperator::LessThan
+                    | BinaryOperator::LessEqual
+                    | BinaryOperator::GreaterThan
+                    | BinaryOperator::GreaterEqual
+                        if (lhs < 0 || rhs < 0)
+                            && expr.lhs.is_signed(tokens) != expr.rhs.is_signed(tokens) =>
+                    {
+                        None
+                    }
+                    BinaryOperator::Equal | BinaryOperator::CaseEqual => Some((lhs == rhs) as i64),
+                    BinaryOperator::Inequal | BinaryOperator::CaseInequal => {
+                        Some((lhs != rhs) as i64)
+                    }
+                    BinaryOperator::LogicalAnd => Some((lhs != 0 && rhs != 0) as i64),
+                    BinaryOperator::LogicalOr => Some((lhs != 0 || rhs != 0) as i64),
+                    BinaryOperator::LessThan => Some((lhs < rhs) as i64),
+                    BinaryOperator::LessEqual => Some((lhs <= rhs) as i64),
+                    BinaryOperator::GreaterThan => Some((lhs > rhs) as i64),
+                    BinaryOperator::GreaterEqual => Some((lhs >= rhs) as i64),
+                    BinaryOperator::BitAnd => Some(lhs & rhs),
+                    BinaryOperator::BitOr => Some(lhs | rhs),
+                    BinaryOperator::BitXor => Some(lhs ^ rhs),
+                    // the bits shifted out or in depend on the width of the operand
+                    BinaryOperator::LeftShift | BinaryOperator::ArithLeftShift => {
+                        lhs.checked_shl(shift.filter(|&shift| shift < 32)?)
+                    }
+                    BinaryOperator::RightShift if lhs >= 0 => lhs.checked_shr(shift?),
+                    BinaryOperator::ArithRightShift => lhs.checked_shr(shift?),
+                    // like `~`, xnor sets the bits above the operand width
+                    _ => None,
+                }
+            }
+            Expression::Conditional(expr) => {
+                if expr.condition.constant_value(tokens, diag)? != 0 {
+                    expr.if_true.constant_value(tokens, diag)
+                } else {
+                    expr.if_false.constant_value(tokens, diag)
+                }
+            }
+            Expression::SystemCall(call) => {
+                let name = &*tokens[call.name.token].text;
+                if let Some(msg) = system_tasks::check_call(name, call.argument_count(), true) {
+                    let severity = match msg {
+                        Message::UnknownSystemTf(_) => Severity::Warning,
+                        _ => Severity::Error,
+                    };
+                    diag.push(Diagnostic {
+                        pos: call.span.resolve(tokens),
+                        msg,
+                        severity,
+                        notes: vec![],
+                    });
+                    return None;
+                }
+                match (name, &call.arguments[..]) {
+                    ("$clog2", [Some(arg)]) => {
+                        let value = arg.constant_value(tokens, diag)?;
+                        if value <= 1 {
+                            Some(0)
+                        } else {
+                            Some(64 - (value as u64 - 1).leading_zeros() as i64)
+                        }
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a constant expression is signed, 11.8.1
+    fn is_signed(&self, tokens: &[ParsedToken<'_>]) -> bool {
+        match self {
+            Expression::Number(number) => {
+                let token = &tokens[number.token];
+                BitVector::parse(&token.text, token.span, &mut vec![])
+                    .is_some_and(|value| value.signed)
+            }
+            Expression::Paren(expr) => expr.expr.is_signed(tokens),
+            Expression::Unary(expr) => match expr.op {
+                UnaryOperator::Plus | UnaryOperator::Minus => expr.operand.is_signed(tokens),
+                _ => false,
+            },
+            Expression::Binary(expr) => match expr.op {
+                BinaryOperator::Add
+                | BinaryOperator::Sub
+                | BinaryOperator::Mul
+                | BinaryOperator::Div
+                | BinaryOperator::Mod
+                | BinaryOperator::BitAnd
+                | BinaryOperator::BitOr
+                | BinaryOperator::BitXor => {
+                    expr.lhs.is_signed(tokens) && expr.rhs.is_signed(tokens)
+                }
+                BinaryOperator::Pow
+                | BinaryOperator::LeftShift
+                | BinaryOperator::RightShift
+                | BinaryOperator::ArithLeftShift
+                | BinaryOperator::ArithRightShift => expr.lhs.is_signed(tokens),
+                _ => false,
+            },
+            Expression::Conditional(expr) => {
+                expr.if_true.is_signed(tokens) && expr.if_false.is_signed(tokens)
+            }
+            // `$clog2` returns an integer
+            Expression::SystemCall(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Parse operators binding at least as tight as `precedence`
+    pub(crate) fn parse_precedence(parser: &mut Parser<'_>, precedence: u8) -> Option<Self> {
+        let first = parser.start();
+        let mut res = Self::parse_unary(parser)?;
+        while parser.avail() {
+            if parser.probe(&[Token::Question]) {
+                if precedence > CONDITIONAL_PRECEDENCE {
+                    break;
+                }
+                parser.advance();
+                let if_true = Self::parse(parser)?;
+                if !parser.probe_err(&[Token::Colon]) {
+                    return None;
+                }
+                parser.advance();
+                // right associative
+                let if_false = Self::parse_precedence(parser, CONDITIONAL_PRECEDENCE)?;
+                res = Expression::Conditional(Box::new(ConditionalExpression {
+                    condition: res,
+                    if_true,
+                    if_false,
+                    span: parser.span_from(first),
+                }));
+            } else if parser.probe(&[Token::Inside]) {
+                if precedence > INSIDE_PRECEDENCE {
+                    break;
+                }
+                parser.advance();
+                let ranges = ValueRange::parse_list(parser)?;
+                res = Expression::Inside(Box::new(InsideExpression {
+                    expr: res,
+                    ranges,
+                    span: parser.span_from(first),
+                }));
+            } else {
+                let op = match parser
+                    .peek()
+                    .and_then(|token| BinaryOperator::from_token(token.token))
+                {
+                    Some(op) if op.precedence() >= precedence => op,
+                    _ => break,
+                };
+                parser.advance();
+                let rhs = if op.is_right_associative() {
+                    Self::parse_precedence(parser, op.precedence())?
+                } else {
+                    Self::parse_precedence(parser, op.precedence() + 1)?
+                };
+                res = Expression::Binary(Box::new(BinaryExpression {
+                    lhs: res,
+                    op,
+                    rhs,
+                    span: parser.span_from(first),
+                }));
+            }
+        }
+        Some(res)
+    }
+
+    /// An expression or minimum, typical and maximum values `min:typ:max`
+    pub(crate) fn parse_mintypmax(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let min = Self::parse(parser)?;
+        if !parser.probe(&[Token::Colon]) {
+            return Some(min);
+        }
+        parser.advance();
+        let typ = Self::parse(parser)?;
+        if !parser.probe_err(&[Token::Colon]) {
+            return None;
+        }
+        parser.advance();
+        let max = Self::parse(parser)?;
+        Some(Expression::MinTypMax(Box::new(MinTypMaxExpression {
+            min,
+            typ,
+            max,
+            span: parser.span_from(first),
+        })))
+    }
+
+    /// Unary operators bind tighter than all binary operators
+    fn parse_unary(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        match parser
+            .peek()
+            .and_then(|token| UnaryOperator::from_token(token.token))
+        {
+            Some(op) => {
+                parser.advance();
+                let operand = Self::parse_unary(parser)?;
+                Some(Expression::Unary(Box::new(UnaryExpression {
+                    op,
+                    operand,
+                    span: parser.span_from(first),
+                })))
+            }
+            None => Self::parse_postfix(parser),
+        }
+    }
+}
+
+impl Parse for Expression {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        Self::parse_precedence(parser, 0)
+    }
+}
+
+impl Spanned for Expression {
+    fn node_span(&self) -> Option<NodeSpan> {
+        Some(self.span())
+    }
+}
+
+/// unary_operator { attribute_instance } primary
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct UnaryExpression {
+    pub op: UnaryOperator,
+    pub operand: Expression,
+    pub span: NodeSpan,
+}
+
+/// expression binary_operator { attribute_instance } expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct BinaryExpression {
+    pub lhs: Expression,
+    pub op: BinaryOperator,
+    pub rhs: Expression,
+    pub span: NodeSpan,
+}
+
+/// conditional_expression ::= cond_predicate ? { attribute_instance } expression : expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ConditionalExpression {
+    pub condition: Expression,
+    pub if_true: Expression,
+    pub if_false: Expression,
+    pub span: NodeSpan,
+}
+
+/// inside_expression ::= expression inside { open_range_list }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct InsideExpression {
+    pub expr: Expression,
+    pub ranges: Vec<ValueRange>,
+    pub span: NodeSpan,
+}
+
+/// mintypmax_expression ::= expression | expression : expression : expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct MinTypMaxExpression {
+    pub min: Expression,
+    pub typ: Expression,
+    pub max: Expression,
+    pub span: NodeSpan,
+}
+
+/// inc_or_dec_expression ::= inc_or_dec_operator { attribute_instance } variable_lvalue
+/// | variable_lvalue { attribute_instance } inc_or_dec_operator
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct IncOrDecExpression {
+    pub op: IncOrDecOperator,
+    /// Whether the operator is written before the operand
+    pub prefix: bool,
+    pub operand: Expression,
+    pub span: NodeSpan,
+}
+
+impl IncOrDecExpression {
+    /// `++a` or `a++`
+    pub(crate) fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if let Some(op) = parser
+            .peek()
+            .and_then(|token| IncOrDecOperator::from_token(token.token))
+        {
+            parser.advance();
+            let operand = Expression::parse_postfix(parser)?;
+            return Some(IncOrDecExpression {
+                op,
+                prefix: true,
+                operand,
+                span: parser.span_from(first),
+            });
+        }
+        let operand = Expression::parse_postfix(parser)?;
+        Self::parse_suffix(parser, first, operand)
+    }
+
+    /// The operator after `operand`, which starts at `first`
+    pub(crate) fn parse_suffix(
+        parser: &mut Parser<'_>,
+        first: usize,
+        operand: Expression,
+    ) -> Option<Self> {
+        if !parser.probe_err(&[Token::OpIncrement, Token::OpDecrement]) {
+            return None;
+        }
+        let op = IncOrDecOperator::from_token(parser.peek()?.token)?;
+        parser.advance();
+        Some(IncOrDecExpression {
+            op,
+            prefix: false,
+            operand,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// value_range ::= expression | [ expression : expression ]
+///
+/// `to` is `None` for a single value.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ValueRange {
+    pub from: Expression,
+    pub to: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ValueRange {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if parser.probe(&[Token::LBracket]) {
+            parser.advance();
+            let from = Expression::parse(parser)?;
+            if !parser.probe_err(&[Token::Colon]) {
+                return None;
+            }
+            parser.advance();
+            let to = Expression::parse(parser)?;
+            if !parser.probe_err(&[Token::RBracket]) {
+                return None;
+            }
+            parser.advance();
+            Some(ValueRange {
+                from,
+                to: Some(to),
+                span: parser.span_from(first),
+            })
+        } else {
+            let from = Expression::parse(parser)?;
+            Some(ValueRange {
+                from,
+                to: None,
+                span: parser.span_from(first),
+            })
+        }
+    }
+}
+
+impl ValueRange {
+    /// { open_range_list }
+    /// open_range_list ::= open_value_range { , open_value_range }
+    pub(crate) fn parse_list(parser: &mut Parser<'_>) -> Option<Vec<Self>> {
+        if !parser.probe_err(&[Token::LBraces]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = vec![ValueRange::parse(parser)?];
+        while parser.probe(&[Token::Comma]) {
+            parser.advance();
+            res.push(ValueRange::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::RBraces]) {
+            return None;
+        }
+        parser.advance();
+        Some(res)
+    }
+}
+
+/// param_expression ::= mintypmax_expression | data_type
+///
+/// A name may be either, it is parsed as an expression.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum ParamExpression {
+    Expression(Expression),
+    DataType(DataType),
+}
+
+impl Parse for ParamExpression {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if parser.probe(DataType::FIRST) && !parser.probe_next(&[Token::Apostrophe]) {
+            Some(ParamExpression::DataType(DataType::parse(parser)?))
+        } else {
+            Some(ParamExpression::Expression(Expression::parse_mintypmax(
+                parser,
+            )?))
+        }
+    }
+}
+
+impl Spanned for ParamExpression {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            ParamExpression::Expression(expr) => expr.node_span(),
+            ParamExpression::DataType(data_type) => data_type.node_span(),
+        }
+    }
+}
+
+spanned!(
+    IncOrDecExpression,
+    UnaryExpression,
+    BinaryExpression,
+    ConditionalExpression,
+    InsideExpression,
+    MinTypMaxExpression,
+    ValueRange
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expression with explicit parentheses
+    fn render(parser: &Parser<'_>, expr: &Expression) -> String {
+        let text = |index| parser.get_token(index).text.to_string();
+        let list = |exprs: &[Expression]| {
+            exprs
+                .iter()
+                .map(|expr| render(parser, expr))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match expr {
+            Expression::Number(number) => text(number.token),
+            Expression::TimeLiteral(time) => text(time.token),
+            Expression::StringLiteral(string) => text(string.token),
+            Expression::Name(identifier) => text(identifier.token),
+            Expression::Paren(expr) => render(parser, &expr.expr),
+            Expression::Concatenation(expr) => format!("{{{}}}", list(&expr.exprs)),
+            Expression::MultipleConcatenation(expr) => format!(
+                "{{{}{{{}}}}}",
+                render(parser, &expr.count),
+                list(&expr.concatenation.exprs)
+            ),
+            Expression::AssignmentPattern(expr) => format!("'{{{} items}}", expr.items.len()),
+            Expression::Select(expr) => {
+                let sep = match expr.kind {
+                    SelectKind::Bit => "",
+                    SelectKind::Range => ":",
+                    SelectKind::IndexedUp => "+:",
+                    SelectKind::IndexedDown => "-:",
+                };
+                let right = expr.right.as_ref().map(|right| render(parser, right));
+                format!(
+                    "{}[{}{}{}]",
+                    render(parser, &expr.value),
+                    render(parser, &expr.left),
+                    sep,
+                    right.unwrap_or_default()
+                )
+            }
+            Expression::Member(expr) => {
+                format!(
+                    "{}.{}",
+                    render(parser, &expr.value),
+                    text(expr.member.token)
+                )
+            }
+            Expression::Call(expr) => {
+                let args: Vec<String> = expr
+                    .arguments
+                    .iter()
+                    .map(|arg| {
+                        arg.as_ref()
+                            .map(|arg| render(parser, arg))
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                format!("{}({})", render(parser, &expr.callee), args.join(", "))
+            }
+            Expression::SystemCall(expr) => {
+                let args: Vec<String> = expr
+                    .arguments
+                    .iter()
+                    .map(|arg| {
+                        arg.as_ref()
+                            .map(|arg| render(parser, arg))
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                format!("{}({})", text(expr.name.token), args.join(", "))
+            }
+            Expression::Cast(expr) => {
+                let target = match &expr.target {
+                    CastType::Expression(target) => render(parser, target),
+                    CastType::DataType(data) => text(data.span.first),
+                    CastType::Signing(Signing::Signed) => "signed".to_owned(),
+                    CastType::Signing(Signing::Unsigned) => "unsigned".to_owned(),
+                    CastType::Const => "const".to_owned(),
+                };
+                format!("{}'({})", target, render(parser, &expr.expr))
+            }
+            Expression::Unary(expr) => {
+                format!("({:?} {})", expr.op, render(parser, &expr.operand))
+            }
+            Expression::Binary(expr) => format!(
+                "({} {:?} {})",
+                render(parser, &expr.lhs),
+                expr.op,
+                render(parser, &expr.rhs)
+            ),
+            Expression::Conditional(expr) => format!(
+                "({} ? {} : {})",
+                render(parser, &expr.condition),
+                render(parser, &expr.if_true),
+                render(parser, &expr.if_false)
+            ),
+            Expression::Inside(expr) => {
+                let ranges: Vec<String> = expr
+                    .ranges
+                    .iter()
+                    .map(|range| match &range.to {
+                        Some(to) => {
+                            format!("[{}:{}]", render(parser, &range.from), render(parser, to))
+                        }
+                        None => render(parser, &range.from),
+                    })
+                    .collect();
+                format!(
+                    "({} inside {{{}}})",
+                    render(parser, &expr.expr),
+                    ranges.join(", ")
+                )
+            }
+            Expression::MinTypMax(expr) => format!(
+                "{}:{}:{}",
+                render(parser, &expr.min),
+                render(parser, &expr.typ),
+                render(parser, &expr.max)
+            ),
+            Expression::Type(data_type) => match &data_type.kind {
+                DataTypeKind::Reference(reference) => match &**reference {
+                    TypeReference::Expression(expr) => format!("type({})", render(parser, expr)),
+                    TypeReference::DataType(data) => format!("type({})", text(data.span.first)),
+                },
+                kind => format!("{:?}", kind),
+            },
+        }
+    }
+
+    fn parse(text: &str) -> String {
+        let mut parser = Parser::from(text);
+        let expr = Expression::parse(&mut parser).expect(text);
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        assert!(!parser.avail(), "{} is not fully parsed", text);
+        assert_eq!(
+            expr.span(),
+            NodeSpan {
+                first: 0,
+                last: parser.finish().0.len() - 1
+            }
+        );
+        let mut parser = Parser::from(text);
+        let expr = Expression::parse(&mut parser).unwrap();
+        render(&parser, &expr)
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(parse("a + b * c"), "(a Add (b Mul c))");
+        assert_eq!(parse("a - b - c"), "((a Sub b) Sub c)");
+        assert_eq!(parse("a ** b ** c"), "((a Pow b) Pow c)");
+        assert_eq!(parse("-a ** b"), "((Minus a) Pow b)");
+        assert_eq!(parse("a << 1 + b"), "(a LeftShift (1 Add b))");
+        assert_eq!(
+            parse("a < b == c > d"),
+            "((a LessThan b) Equal (c GreaterThan d))"
+        );
+        assert_eq!(
+            parse("a & b ^ c | d && e || f"),
+            "(((((a BitAnd b) BitXor c) BitOr d) LogicalAnd e) LogicalOr f)"
+        );
+        assert_eq!(parse("a ~^ b ^~ c"), "((a BitXnor b) BitXnor c)");
+        assert_eq!(parse("!~&a"), "(Not (Nand a))");
+        assert_eq!(parse("(a + b) * c"), "((a Add b) Mul c)");
+        assert_eq!(
+            parse("(1:2:3) + (a ? b : c:d:e)"),
+            "(1:2:3 Add (a ? b : c):d:e)"
+        );
+        assert_eq!(
+            parse("type(a + 1) == type(int)"),
+            "(type((a Add 1)) Equal type(int))"
+        );
+    }
+
+    #[test]
+    fn system_verilog_operators() {
+        assert_eq!(parse("a -> b -> c"), "(a Implication (b Implication c))");
+        assert_eq!(
+            parse("a ==? b || c <-> d"),
+            "(((a WildcardEqual b) LogicalOr c) Equivalence d)"
+        );
+        assert_eq!(parse("a ? b : c -> d"), "((a ? b : c) Implication d)");
+    }
+
+    #[test]
+    fn conditional() {
+        assert_eq!(parse("a ? b : c ? d : e"), "(a ? b : (c ? d : e))");
+        assert_eq!(
+            parse("a || b ? c + 1 : d"),
+            "((a LogicalOr b) ? (c Add 1) : d)"
+        );
+        assert_eq!(parse("a ? b ? c : d : e"), "(a ? (b ? c : d) : e)");
+    }
+
+    #[test]
+    fn inside() {
+        assert_eq!(
+            parse("a + 1 inside {1, [2:3]} && b"),
+            "(((a Add 1) inside {1, [2:3]}) LogicalAnd b)"
+        );
+    }
+
+    #[test]
+    fn primaries() {
+        assert_eq!(parse("{a, b[3:0], 2'b01}"), "{a, b[3:0], 2'b01}");
+        assert_eq!(parse("{WIDTH{1'b0}}"), "{WIDTH{1'b0}}");
+        assert_eq!(parse("{}"), "{}");
+        assert_eq!(parse("a[i][j + 1]"), "a[i][(j Add 1)]");
+        assert_eq!(parse("a[i +: 4]"), "a[i+:4]");
+        assert_eq!(parse("a[i*8 -: 8]"), "a[(i Mul 8)-:8]");
+        assert_eq!(parse("top.u1[2].sig"), "top.u1[2].sig");
+        assert_eq!(parse("f(a, , b + 1)"), "f(a, , (b Add 1))");
+        assert_eq!(parse("pkg.f()"), "pkg.f()");
+        assert_eq!(parse("$clog2(N) - 1"), "($clog2(N) Sub 1)");
+        assert_eq!(parse("$time"), "$time()");
+        assert_eq!(parse("8'(a) + signed'(b)"), "(8'(a) Add signed'(b))");
+        assert_eq!(parse("logic'(a)"), "logic'(a)");
+        assert_eq!(parse("T'(a)"), "T'(a)");
+        assert_eq!(parse("\"str\""), "\"str\"");
+    }
+
+    #[test]
+    fn assignment_patterns() {
+        assert_eq!(parse("'{a, b, c}"), "'{3 items}");
+        assert_eq!(parse("'{default: 0}"), "'{1 items}");
+        assert_eq!(parse("'{4{1'b0}}"), "'{1 items}");
+
+        let mut parser = Parser::from("T'{a: 1, default: 0}");
+        let expr = Expression::parse(&mut parser).unwrap();
+        let pattern = match expr {
+            Expression::AssignmentPattern(pattern) => pattern,
+            _ => panic!("{:?}", expr),
+        };
+        assert_eq!(
+            pattern.pattern_type,
+            Some(Expression::Name(Identifier { token: 0 }))
+        );
+        assert_eq!(pattern.items[1].key, Some(PatternKey::Default));
+        assert_eq!(pattern.span, NodeSpan { first: 0, last: 9 });
+
+        let mut parser = Parser::from("'{4{a, b}}");
+        let expr = Expression::parse(&mut parser).unwrap();
+        match expr {
+            Expression::AssignmentPattern(pattern) => {
+                assert_eq!(
+                    pattern.replication,
+                    Some(Expression::Number(Number { token: 1 }))
+                );
+                assert_eq!(pattern.items.len(), 2);
+            }
+            _ => panic!("{:?}", expr),
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut parser = Parser::from("a + ");
+        assert_eq!(Expression::parse(&mut parser), None);
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::ExpectedExpression("end of file".to_owned())
+        );
+
+        let mut parser = Parser::from("(a + b");
+        assert_eq!(Expression::parse(&mut parser), None);
+        assert_eq!(parser.get_diag().len(), 1);
+    }
+
+    #[test]
+    fn constant_value() {
+        let value = |text| {
+            let mut parser = Parser::from(text);
+            let expr = Expression::parse(&mut parser).unwrap();
+            let (tokens, _) = parser.finish();
+            expr.constant_value(&tokens, &mut vec![])
+        };
+        assert_eq!(value("-4'sd5 + 1"), Some(-4));
+        assert_eq!(value("(1 << 4) >> 1"), Some(8));
+        assert_eq!(value("-8 >>> 1"), Some(-4));
+        assert_eq!(value("3 > 2 ? 10 : 20"), Some(10));
+        assert_eq!(value("$clog2(256) + $clog2(257)"), Some(17));
+        assert_eq!(value("4'hF == 15 && !0"), Some(1));
+        assert_eq!(value("1 / 0"), None);
+        assert_eq!(value("4'b1x + 1"), None);
+        assert_eq!(value("N - 1"), None);
+        assert_eq!(value("~4'b0101"), None);
+        assert_eq!(value("4'b0101 ~^ 4'b0011"), None);
+        assert_eq!(value("-1 < 1"), Some(1));
+        assert_eq!(value("-1 < 'h1"), None);
+        assert_eq!(value("-1 == 32'hFFFF_FFFF"), None);
+        assert_eq!(value("8'd3 > 8'd2"), Some(1));
+        assert_eq!(value("-8 >> 1"), None);
+        assert_eq!(value("8 >> 1"), Some(4));
+        assert_eq!(value("1 << 31"), Some(1 << 31));
+        assert_eq!(value("1 << 32"), None);
+
+        let mut parser = Parser::from("$clog2(4, 2) + $foo + $random");
+        let expr = Expression::parse(&mut parser).unwrap();
+        let (tokens, _) = parser.finish();
+        let mut diag = vec![];
+        assert_eq!(expr.constant_value(&tokens, &mut diag), None);
+        assert_eq!(diag.len(), 1);
+        assert_eq!(diag[0].severity, Severity::Error);
+        assert!(matches!(diag[0].msg, Message::SystemTfArguments(..)));
+
+        for (text, msg, severity) in [
+            (
+                "$foo",
+                Message::UnknownSystemTf("$foo".to_owned()),
+                Severity::Warning,
+            ),
+            (
+                "$random",
+                Message::NonConstantSystemTf("$random".to_owned()),
+                Severity::Error,
+            ),
+        ] {
+            let mut parser = Parser::from(text);
+            let expr = Expression::parse(&mut parser).unwrap();
+            let (tokens, _) = parser.finish();
+            let mut diag = vec![];
+            assert_eq!(expr.constant_value(&tokens, &mut diag), None);
+            assert_eq!(diag[0].msg, msg);
+            assert_eq!(diag[0].severity, severity);
+        }
+    }
+}
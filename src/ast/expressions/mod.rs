@@ -1,4 +1,17 @@
 //! A.8 Expressions
 
+mod concatenations;
+mod expression;
 mod numbers;
+mod operators;
+mod primaries;
+mod strings;
+mod subroutine_calls;
+
+pub use concatenations::*;
+pub use expression::*;
 pub use numbers::*;
+pub use operators::*;
+pub use primaries::*;
+pub use strings::*;
+pub use subroutine_calls::*;
@@ -20,7 +20,7 @@ impl Parse for Number {
                 parser.err(
                     token.span.from,
                     token.span.to,
-                    Message::UnexpectedToken(Token::Number, token.text.to_owned()),
+                    Message::UnexpectedToken(Token::Number, token.text.to_string()),
                 );
                 return None;
             }
@@ -34,3 +34,9 @@ impl Parse for Number {
         None
     }
 }
+
+impl Spanned for Number {
+    fn node_span(&self) -> Option<NodeSpan> {
+        Some(NodeSpan::token(self.token))
+    }
+}
@@ -0,0 +1,156 @@
+//! A.8.6 Operators
+
+use crate::ast::*;
+
+/// unary_operator ::= + | - | ! | ~ | & | ~& | | | ~| | ^ | ~^ | ^~
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum UnaryOperator {
+    Plus,
+    Minus,
+    Not,
+    BitNeg,
+    And,
+    Nand,
+    Or,
+    Nor,
+    Xor,
+    Xnor,
+}
+
+impl UnaryOperator {
+    pub fn from_token(token: Token) -> Option<Self> {
+        Some(match token {
+            Token::OpPlus => UnaryOperator::Plus,
+            Token::OpMinus => UnaryOperator::Minus,
+            Token::OpNot => UnaryOperator::Not,
+            Token::OpBitNeg => UnaryOperator::BitNeg,
+            Token::OpBitAnd => UnaryOperator::And,
+            Token::OpNand => UnaryOperator::Nand,
+            Token::OpBitOr => UnaryOperator::Or,
+            Token::OpNor => UnaryOperator::Nor,
+            Token::OpBitXor => UnaryOperator::Xor,
+            Token::OpBitEquiv1 | Token::OpBitEquiv2 => UnaryOperator::Xnor,
+            _ => return None,
+        })
+    }
+}
+
+/// binary_operator ::= + | - | * | / | % | == | != | === | !== | && | || | **
+/// | < | <= | > | >= | & | | | ^ | ^~ | ~^ | >> | << | >>> | <<<
+/// | ==? | !=? | -> | <->
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    Equal,
+    Inequal,
+    CaseEqual,
+    CaseInequal,
+    WildcardEqual,
+    WildcardInequal,
+    LogicalAnd,
+    LogicalOr,
+    LessThan,
+    LessEqual,
+    GreaterThan,
+    GreaterEqual,
+    BitAnd,
+    BitOr,
+    BitXor,
+    BitXnor,
+    LeftShift,
+    RightShift,
+    ArithLeftShift,
+    ArithRightShift,
+    Implication,
+    Equivalence,
+}
+
+/// Binding power of the conditional operator `?:`
+pub(crate) const CONDITIONAL_PRECEDENCE: u8 = 2;
+/// Binding power of `inside`, the same as the relational operators
+pub(crate) const INSIDE_PRECEDENCE: u8 = 9;
+
+impl BinaryOperator {
+    pub fn from_token(token: Token) -> Option<Self> {
+        Some(match token {
+            Token::OpPlus => BinaryOperator::Add,
+            Token::OpMinus => BinaryOperator::Sub,
+            Token::OpMultiply => BinaryOperator::Mul,
+            Token::OpDivide => BinaryOperator::Div,
+            Token::OpMod => BinaryOperator::Mod,
+            Token::OpPow => BinaryOperator::Pow,
+            Token::OpEqual => BinaryOperator::Equal,
+            Token::OpInequal => BinaryOperator::Inequal,
+            Token::OpCaseEqual => BinaryOperator::CaseEqual,
+            Token::OpCaseInequal => BinaryOperator::CaseInequal,
+            Token::OpWildcardEqual => BinaryOperator::WildcardEqual,
+            Token::OpWildcardInequal => BinaryOperator::WildcardInequal,
+            Token::OpAnd => BinaryOperator::LogicalAnd,
+            Token::OpOr => BinaryOperator::LogicalOr,
+            Token::OpLessThan => BinaryOperator::LessThan,
+            Token::OpLessEqual => BinaryOperator::LessEqual,
+            Token::OpGreaterThan => BinaryOperator::GreaterThan,
+            Token::OpGreaterEqual => BinaryOperator::GreaterEqual,
+            Token::OpBitAnd => BinaryOperator::BitAnd,
+            Token::OpBitOr => BinaryOperator::BitOr,
+            Token::OpBitXor => BinaryOperator::BitXor,
+            Token::OpBitEquiv1 | Token::OpBitEquiv2 => BinaryOperator::BitXnor,
+            Token::OpLeftShift => BinaryOperator::LeftShift,
+            Token::OpRightShift => BinaryOperator::RightShift,
+            Token::OpArithLeftShift => BinaryOperator::ArithLeftShift,
+            Token::OpArithRightShift => BinaryOperator::ArithRightShift,
+            Token::OpImplication => BinaryOperator::Implication,
+            Token::OpEquivalence => BinaryOperator::Equivalence,
+            _ => return None,
+        })
+    }
+
+    /// Binding power from Table 11-2, higher binds tighter
+    pub fn precedence(self) -> u8 {
+        use BinaryOperator::*;
+        match self {
+            Implication | Equivalence => 1,
+            LogicalOr => 3,
+            LogicalAnd => 4,
+            BitOr => 5,
+            BitXor | BitXnor => 6,
+            BitAnd => 7,
+            Equal | Inequal | CaseEqual | CaseInequal | WildcardEqual | WildcardInequal => 8,
+            LessThan | LessEqual | GreaterThan | GreaterEqual => INSIDE_PRECEDENCE,
+            LeftShift | RightShift | ArithLeftShift | ArithRightShift => 10,
+            Add | Sub => 11,
+            Mul | Div | Mod => 12,
+            Pow => 13,
+        }
+    }
+
+    /// `->` and `<->` are right associative, all others are left associative
+    pub fn is_right_associative(self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Implication | BinaryOperator::Equivalence
+        )
+    }
+}
+
+/// inc_or_dec_operator ::= ++ | --
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum IncOrDecOperator {
+    Increment,
+    Decrement,
+}
+
+impl IncOrDecOperator {
+    pub fn from_token(token: Token) -> Option<Self> {
+        match token {
+            Token::OpIncrement => Some(IncOrDecOperator::Increment),
+            Token::OpDecrement => Some(IncOrDecOperator::Decrement),
+            _ => None,
+        }
+    }
+}
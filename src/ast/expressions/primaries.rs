@@ -0,0 +1,300 @@
+//! A.8.4 Primaries
+
+use crate::ast::*;
+
+impl Expression {
+    /// primary ::= primary_literal
+    /// | system_tf_call
+    /// | hierarchical_identifier select
+    /// | empty_unpacked_array_concatenation
+    /// | concatenation [ [ range_expression ] ]
+    /// | multiple_concatenation [ [ range_expression ] ]
+    /// | function_subroutine_call
+    /// | ( mintypmax_expression )
+    /// | cast
+    /// | assignment_pattern_expression
+    /// | type_reference
+    pub(crate) fn parse_postfix(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = Self::parse_primary(parser)?;
+        loop {
+            if parser.probe(&[Token::LBracket]) {
+                res = Expression::Select(Box::new(Select::parse_suffix(parser, first, res)?));
+            } else if parser.probe(&[Token::Dot]) {
+                parser.advance();
+                let member = Identifier::parse(parser)?;
+                res = Expression::Member(Box::new(MemberSelect {
+                    value: res,
+                    member,
+                    span: parser.span_from(first),
+                }));
+            } else if parser.probe(&[Token::LParen])
+                && matches!(res, Expression::Name(_) | Expression::Member(_))
+            {
+                res =
+                    Expression::Call(Box::new(FunctionCall::parse_arguments(parser, first, res)?));
+            } else if parser.probe(&[Token::Apostrophe]) && parser.probe_next(&[Token::LParen]) {
+                res = Expression::Cast(Box::new(Cast::parse_operand(
+                    parser,
+                    first,
+                    CastType::Expression(res),
+                )?));
+            } else if parser.probe(&[Token::ApostropheLBraces]) {
+                let mut pattern = AssignmentPattern::parse(parser)?;
+                pattern.pattern_type = Some(res);
+                pattern.span = parser.span_from(first);
+                res = Expression::AssignmentPattern(Box::new(pattern));
+            } else {
+                break;
+            }
+        }
+        Some(res)
+    }
+
+    fn parse_primary(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let token = match parser.peek() {
+            Some(token) => token.token,
+            None => {
+                parser.err(
+                    parser.location_from(),
+                    parser.location_to(),
+                    Message::ExpectedExpression(parser.current_text()),
+                );
+                return None;
+            }
+        };
+        match token {
+            Token::Number => Some(Expression::Number(Number::parse(parser)?)),
+            Token::TimeLiteral => Some(Expression::TimeLiteral(TimeLiteral::parse(parser)?)),
+            Token::StringLiteral => Some(Expression::StringLiteral(StringLiteral::parse(parser)?)),
+            Token::Identifier => Some(Expression::Name(Identifier::parse(parser)?)),
+            Token::SystemIdentifier => Some(Expression::SystemCall(Box::new(SystemTfCall::parse(
+                parser,
+            )?))),
+            Token::LParen => {
+                parser.advance();
+                let expr = Expression::parse_mintypmax(parser)?;
+                if !parser.probe_err(&[Token::RParen]) {
+                    return None;
+                }
+                parser.advance();
+                Some(Expression::Paren(Box::new(ParenExpression {
+                    expr,
+                    span: parser.span_from(first),
+                })))
+            }
+            Token::Type => Some(Expression::Type(Box::new(DataType::parse(parser)?))),
+            Token::LBraces => Concatenation::parse_expression(parser),
+            Token::ApostropheLBraces => Some(Expression::AssignmentPattern(Box::new(
+                AssignmentPattern::parse(parser)?,
+            ))),
+            Token::Signed
+            | Token::Unsigned
+            | Token::Const
+            | Token::Bit
+            | Token::Logic
+            | Token::Reg
+            | Token::Byte
+            | Token::ShortInt
+            | Token::Int
+            | Token::LongInt
+            | Token::Integer
+            | Token::Time
+            | Token::ShortReal
+            | Token::Real
+            | Token::Realtime
+            | Token::String
+                if parser.probe_next(&[Token::Apostrophe]) =>
+            {
+                Some(Expression::Cast(Box::new(Cast::parse(parser)?)))
+            }
+            _ => {
+                parser.err(
+                    parser.location_from(),
+                    parser.location_to(),
+                    Message::ExpectedExpression(parser.current_text()),
+                );
+                None
+            }
+        }
+    }
+}
+
+/// time_literal ::= unsigned_number time_unit | fixed_point_number time_unit
+/// time_unit ::= s | ms | us | ns | ps | fs
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TimeLiteral {
+    pub token: TokenIndex,
+}
+
+impl Parse for TimeLiteral {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if !parser.probe_err(&[Token::TimeLiteral]) {
+            return None;
+        }
+        let res = TimeLiteral {
+            token: parser.index(),
+        };
+        parser.advance();
+        Some(res)
+    }
+}
+
+impl Spanned for TimeLiteral {
+    fn node_span(&self) -> Option<NodeSpan> {
+        Some(NodeSpan::token(self.token))
+    }
+}
+
+/// ( mintypmax_expression )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ParenExpression {
+    pub expr: Expression,
+    pub span: NodeSpan,
+}
+
+/// select ::= [ expression ]
+/// | [ constant_expression : constant_expression ]
+/// | [ expression +: constant_expression ]
+/// | [ expression -: constant_expression ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Select {
+    pub value: Expression,
+    pub kind: SelectKind,
+    pub left: Expression,
+    /// Right bound or width, `None` for a bit-select
+    pub right: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum SelectKind {
+    #[default]
+    Bit,
+    Range,
+    IndexedUp,
+    IndexedDown,
+}
+
+impl Select {
+    /// Parse `[ ... ]` selecting from `value` that starts at `first`
+    pub(crate) fn parse_suffix(
+        parser: &mut Parser<'_>,
+        first: TokenIndex,
+        value: Expression,
+    ) -> Option<Self> {
+        if !parser.probe_err(&[Token::LBracket]) {
+            return None;
+        }
+        parser.advance();
+        let left = Expression::parse(parser)?;
+        let kind = if parser.probe(&[Token::Colon]) {
+            parser.advance();
+            SelectKind::Range
+        } else if parser.probe(&[Token::PlusColon]) {
+            parser.advance();
+            SelectKind::IndexedUp
+        } else if parser.probe(&[Token::MinusColon]) {
+            parser.advance();
+            SelectKind::IndexedDown
+        } else {
+            SelectKind::Bit
+        };
+        let right = match kind {
+            SelectKind::Bit => None,
+            _ => Some(Expression::parse(parser)?),
+        };
+        if !parser.probe_err(&[Token::RBracket]) {
+            return None;
+        }
+        parser.advance();
+        Some(Select {
+            value,
+            kind,
+            left,
+            right,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// hierarchical_identifier ::= { identifier constant_bit_select . } identifier
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct MemberSelect {
+    pub value: Expression,
+    pub member: Identifier,
+    pub span: NodeSpan,
+}
+
+/// cast ::= casting_type ' ( expression )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Cast {
+    pub target: CastType,
+    pub expr: Expression,
+    pub span: NodeSpan,
+}
+
+/// casting_type ::= simple_type | constant_primary | signing | string | const
+///
+/// Sizes and type names are expressions.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum CastType {
+    Expression(Expression),
+    DataType(DataType),
+    Signing(Signing),
+    #[default]
+    Const,
+}
+
+impl Parse for Cast {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let target = if parser.probe(&[Token::Signed, Token::Unsigned]) {
+            CastType::Signing(Signing::parse(parser)?)
+        } else if parser.probe(&[Token::Const]) {
+            parser.advance();
+            CastType::Const
+        } else if parser.probe(DataType::FIRST) {
+            CastType::DataType(DataType::parse(parser)?)
+        } else {
+            return match Expression::parse_postfix(parser)? {
+                Expression::Cast(cast) => Some(*cast),
+                _ => {
+                    parser.err(
+                        parser.location_from(),
+                        parser.location_to(),
+                        Message::UnexpectedToken(Token::Apostrophe, parser.current_text()),
+                    );
+                    None
+                }
+            };
+        };
+        Self::parse_operand(parser, first, target)
+    }
+}
+
+impl Cast {
+    fn parse_operand(parser: &mut Parser<'_>, first: TokenIndex, target: CastType) -> Option<Self> {
+        if !parser.probe_err(&[Token::Apostrophe]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let expr = Expression::parse(parser)?;
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        Some(Cast {
+            target,
+            expr,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+spanned!(ParenExpression, Select, MemberSelect, Cast);
@@ -0,0 +1,131 @@
+//! A.8.8 Strings
+
+use crate::ast::*;
+
+/// string_literal ::= " { Any_ASCII_Characters } "
+/// | """ { Any_ASCII_Characters } """
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct StringLiteral {
+    pub token: TokenIndex,
+}
+
+impl Parse for StringLiteral {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if parser.probe_err(&[Token::StringLiteral]) {
+            let res = StringLiteral {
+                token: parser.index(),
+            };
+            parser.advance();
+            return Some(res);
+        }
+        None
+    }
+}
+
+impl StringLiteral {
+    /// Characters of a string literal with escape sequences replaced, 5.9.1
+    ///
+    /// Strings are byte sequences, `\ddd` and `\xdd` may produce bytes that
+    /// are not UTF-8. A backslash before a newline continues the string on
+    /// the next line. An unknown escape `\c` is the character `c`.
+    pub fn decode(text: &str) -> Vec<u8> {
+        let body = match text.strip_prefix("\"\"\"") {
+            Some(body) => body.strip_suffix("\"\"\"").unwrap_or(body),
+            None => {
+                let body = text.strip_prefix('"').unwrap_or(text);
+                body.strip_suffix('"').unwrap_or(body)
+            }
+        };
+        let mut res = vec![];
+        let mut chars = body.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                let mut buf = [0; 4];
+                res.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                continue;
+            }
+            let ch = match chars.next() {
+                Some(ch) => ch,
+                None => break,
+            };
+            let byte = match ch {
+                'n' => b'\n',
+                't' => b'\t',
+                'v' => 0x0b,
+                'f' => 0x0c,
+                'a' => 0x07,
+                '0'..='7' => {
+                    let mut value = ch.to_digit(8).unwrap();
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|ch| ch.to_digit(8)) {
+                            Some(digit) => {
+                                value = value * 8 + digit;
+                                chars.next();
+                            }
+                            None => break,
+                        }
+                    }
+                    value as u8
+                }
+                'x' if chars.peek().is_some_and(|ch| ch.is_ascii_hexdigit()) => {
+                    let mut value = 0;
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|ch| ch.to_digit(16)) {
+                            Some(digit) => {
+                                value = value * 16 + digit;
+                                chars.next();
+                            }
+                            None => break,
+                        }
+                    }
+                    value as u8
+                }
+                '\r' => {
+                    if chars.peek() == Some(&'\n') {
+                        chars.next();
+                    }
+                    continue;
+                }
+                '\n' => continue,
+                ch => {
+                    let mut buf = [0; 4];
+                    res.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                    continue;
+                }
+            };
+            res.push(byte);
+        }
+        res
+    }
+}
+
+impl Spanned for StringLiteral {
+    fn node_span(&self) -> Option<NodeSpan> {
+        Some(NodeSpan::token(self.token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        assert_eq!(StringLiteral::decode(r#""a\tb\n""#), b"a\tb\n");
+        assert_eq!(
+            StringLiteral::decode(r#""\v\f\a\\\"""#),
+            b"\x0b\x0c\x07\\\""
+        );
+        assert_eq!(StringLiteral::decode(r#""\101\1011\0""#), b"AA1\0");
+        assert_eq!(StringLiteral::decode(r#""\x41\x4g\xff""#), b"A\x04g\xff");
+        assert_eq!(StringLiteral::decode(r#""\q""#), b"q");
+        assert_eq!(StringLiteral::decode("\"a\\\nb\\\r\nc\""), b"abc");
+        assert_eq!(
+            StringLiteral::decode("\"\"\"say \"hi\"\n\\tnow\"\"\""),
+            b"say \"hi\"\n\tnow"
+        );
+        assert_eq!(StringLiteral::decode("\"é\""), "é".as_bytes());
+        // unterminated
+        assert_eq!(StringLiteral::decode("\"abc"), b"abc");
+    }
+}
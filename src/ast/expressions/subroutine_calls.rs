@@ -0,0 +1,219 @@
+//! A.8.2 Subroutine calls
+
+use crate::ast::*;
+
+/// tf_call ::= ps_or_hierarchical_tf_identifier { attribute_instance } [ ( list_of_arguments ) ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct FunctionCall {
+    pub callee: Expression,
+    /// Omitted arguments are `None`
+    pub arguments: Vec<Option<Expression>>,
+    /// Arguments `.name(expr)` after the ordered ones
+    pub named: Vec<NamedArgument>,
+    pub span: NodeSpan,
+}
+
+/// . identifier ( [ expression ] )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct NamedArgument {
+    pub identifier: Identifier,
+    /// `None` for `.name()`
+    pub value: Option<Expression>,
+    pub span: NodeSpan,
+}
+
+impl FunctionCall {
+    pub(crate) fn parse_arguments(
+        parser: &mut Parser<'_>,
+        first: TokenIndex,
+        callee: Expression,
+    ) -> Option<Self> {
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = FunctionCall {
+            callee,
+            ..FunctionCall::default()
+        };
+        (res.arguments, res.named) = parse_list_of_arguments(parser)?;
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// list_of_arguments ::= [ expression ] { , [ expression ] } { , . identifier ( [ expression ] ) }
+/// | . identifier ( [ expression ] ) { , . identifier ( [ expression ] ) }
+///
+/// Parses the arguments up to the closing `)`.
+fn parse_list_of_arguments(
+    parser: &mut Parser<'_>,
+) -> Option<(Vec<Option<Expression>>, Vec<NamedArgument>)> {
+    let mut arguments = vec![];
+    let mut named = vec![];
+    if parser.probe(&[Token::RParen]) {
+        return Some((arguments, named));
+    }
+    loop {
+        if parser.probe(&[Token::Dot]) {
+            named.push(NamedArgument::parse(parser)?);
+        } else if !named.is_empty() {
+            // ordered arguments cannot follow named ones
+            parser.probe_err(&[Token::Dot]);
+            return None;
+        } else if parser.probe(&[Token::Comma, Token::RParen]) {
+            arguments.push(None);
+        } else {
+            arguments.push(Some(Expression::parse(parser)?));
+        }
+        if parser.probe(&[Token::Comma]) {
+            parser.advance();
+        } else {
+            break;
+        }
+    }
+    Some((arguments, named))
+}
+
+impl Parse for NamedArgument {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Dot]) {
+            return None;
+        }
+        parser.advance();
+        let identifier = Identifier::parse(parser)?;
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let mut value = None;
+        if !parser.probe(&[Token::RParen]) {
+            value = Some(Expression::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        Some(NamedArgument {
+            identifier,
+            value,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// system_tf_call ::= system_tf_identifier [ ( list_of_arguments ) ]
+/// | system_tf_identifier ( data_type [ , expression ] )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SystemTfCall {
+    pub name: SystemIdentifier,
+    /// Data type given as the first argument, like `$bits(logic [7:0])`
+    pub data_type: Option<DataType>,
+    /// Omitted arguments are `None`, a call without parentheses has none
+    pub arguments: Vec<Option<Expression>>,
+    pub span: NodeSpan,
+}
+
+impl Parse for SystemTfCall {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = SystemTfCall {
+            name: SystemIdentifier::parse(parser)?,
+            ..SystemTfCall::default()
+        };
+        if parser.probe(&[Token::LParen]) {
+            parser.advance();
+            if parser.probe(DataType::FIRST) {
+                res.data_type = Some(DataType::parse(parser)?);
+                if parser.probe(&[Token::Comma]) {
+                    parser.advance();
+                    res.arguments.push(Some(Expression::parse(parser)?));
+                }
+            } else if !parser.probe(&[Token::RParen]) {
+                loop {
+                    if parser.probe(&[Token::Comma, Token::RParen]) {
+                        res.arguments.push(None);
+                    } else {
+                        res.arguments.push(Some(Expression::parse(parser)?));
+                    }
+                    if parser.probe(&[Token::Comma]) {
+                        parser.advance();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            if !parser.probe_err(&[Token::RParen]) {
+                return None;
+            }
+            parser.advance();
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl SystemTfCall {
+    /// Number of arguments, counting the data type
+    pub fn argument_count(&self) -> usize {
+        self.arguments.len() + usize::from(self.data_type.is_some())
+    }
+}
+
+spanned!(FunctionCall, NamedArgument, SystemTfCall);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_tf_call() {
+        let mut parser = Parser::from("$display(\"%d %d\", a, , b + 1)");
+        let call = SystemTfCall::parse(&mut parser).unwrap();
+        assert_eq!(call.name.token, 0);
+        assert_eq!(call.arguments.len(), 4);
+        assert_eq!(call.arguments[2], None);
+        assert_eq!(call.span, NodeSpan { first: 0, last: 10 });
+
+        let mut parser = Parser::from("$time + 1");
+        let call = SystemTfCall::parse(&mut parser).unwrap();
+        assert_eq!(call.argument_count(), 0);
+        assert_eq!(call.span, NodeSpan::token(0));
+
+        let mut parser = Parser::from("$bits(logic [7:0])");
+        let call = SystemTfCall::parse(&mut parser).unwrap();
+        assert!(call.data_type.is_some());
+        assert_eq!(call.argument_count(), 1);
+        assert!(parser.get_diag().is_empty());
+    }
+
+    #[test]
+    fn function_call() {
+        let mut parser = Parser::from("f(a, , .x(1), .y())");
+        let call = match Expression::parse(&mut parser).unwrap() {
+            Expression::Call(call) => call,
+            expr => panic!("{:?}", expr),
+        };
+        assert_eq!(call.arguments.len(), 2);
+        assert_eq!(call.arguments[1], None);
+        assert_eq!(call.named.len(), 2);
+        assert_eq!(call.named[0].identifier, Identifier { token: 6 });
+        assert!(call.named[0].value.is_some());
+        assert_eq!(call.named[1].value, None);
+        assert_eq!(call.span, NodeSpan { first: 0, last: 15 });
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("f(.x(1), 2)");
+        assert_eq!(Expression::parse(&mut parser), None);
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::UnexpectedTokens(vec![Token::Dot], "2".to_owned())
+        );
+    }
+}
@@ -8,16 +8,19 @@ use serde::{Deserialize, Serialize};
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct Attributes {
     pub attrs: Vec<Attribute>,
+    pub span: Option<NodeSpan>,
 }
 
 impl Parse for Attributes {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = Self::default();
+        let first = parser.start();
         while parser.probe(&[Token::LParen]) {
             if let Some(attr) = Attribute::parse(parser) {
                 res.attrs.push(attr);
             }
         }
+        res.span = parser.optional_span_from(first);
         Some(res)
     }
 }
@@ -28,10 +31,12 @@ impl Parse for Attributes {
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct Attribute {
     pub attrs: Vec<Identifier>,
+    pub span: NodeSpan,
 }
 
 impl Parse for Attribute {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
         if let Some(token1) = parser.peek() {
             if token1.token == Token::LParen {
                 parser.advance();
@@ -53,6 +58,7 @@ impl Parse for Attribute {
                                     // TODO: error msg
                                 }
                                 parser.advance();
+                                res.span = parser.span_from(first);
                                 return Some(res);
                             } else {
                                 break;
@@ -66,6 +72,14 @@ impl Parse for Attribute {
     }
 }
 
+impl Spanned for Attributes {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.span
+    }
+}
+
+spanned!(Attribute);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,5 +97,13 @@ mod tests {
         let attr = attr.unwrap();
         assert_eq!(attr.attrs[0].token, 2);
         assert_eq!(attr.attrs[1].token, 4);
+        assert_eq!(attr.span, NodeSpan { first: 0, last: 6 });
+
+        let mut parser = Parser::from("(* a *) (* b *) wire");
+        let attrs = Attributes::parse(&mut parser).unwrap();
+        assert_eq!(attrs.span, Some(NodeSpan { first: 0, last: 9 }));
+        let mut parser = Parser::from("wire");
+        let attrs = Attributes::parse(&mut parser).unwrap();
+        assert_eq!(attrs.span, None);
     }
 }
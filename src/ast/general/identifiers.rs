@@ -18,7 +18,7 @@ impl Parse for Identifier {
                 parser.err(
                     token.span.from,
                     token.span.to,
-                    Message::UnexpectedToken(Token::Identifier, token.text.to_owned()),
+                    Message::UnexpectedToken(Token::Identifier, token.text.to_string()),
                 );
                 return None;
             }
@@ -33,6 +33,37 @@ impl Parse for Identifier {
     }
 }
 
+impl Spanned for Identifier {
+    fn node_span(&self) -> Option<NodeSpan> {
+        Some(NodeSpan::token(self.token))
+    }
+}
+
+/// system_tf_identifier ::= $[ a-zA-Z0-9_$ ]{ [ a-zA-Z0-9_$ ] }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SystemIdentifier {
+    pub token: TokenIndex,
+}
+
+impl Parse for SystemIdentifier {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        if parser.probe_err(&[Token::SystemIdentifier]) {
+            let res = SystemIdentifier {
+                token: parser.index(),
+            };
+            parser.advance();
+            return Some(res);
+        }
+        None
+    }
+}
+
+impl Spanned for SystemIdentifier {
+    fn node_span(&self) -> Option<NodeSpan> {
+        Some(NodeSpan::token(self.token))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
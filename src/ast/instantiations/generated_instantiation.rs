@@ -0,0 +1,596 @@
+//! A.4.2 Generated instantiation
+
+use crate::ast::*;
+
+/// generate_region ::= generate { generate_item } endgenerate
+///
+/// A generate region does not form a scope, its items belong to the
+/// enclosing module or generate block.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct GenerateRegion {
+    pub items: Vec<NonPortModuleItem>,
+    pub span: NodeSpan,
+}
+
+impl Parse for GenerateRegion {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Generate]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = GenerateRegion::default();
+        while parser.avail() && !parser.probe(&[Token::EndGenerate]) {
+            res.items.push(NonPortModuleItem::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::EndGenerate]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// loop_generate_construct ::= for ( genvar_initialization ; genvar_expression ; genvar_iteration ) generate_block
+/// genvar_iteration ::= genvar_identifier assignment_operator genvar_expression
+/// | inc_or_dec_operator genvar_identifier
+/// | genvar_identifier inc_or_dec_operator
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct LoopGenerateConstruct {
+    pub initialization: GenvarInitialization,
+    pub condition: Expression,
+    /// `genvar_iteration`, parsed like the step of a `for` statement
+    pub iteration: ForStep,
+    pub block: GenerateBlock,
+    pub span: NodeSpan,
+}
+
+impl Parse for LoopGenerateConstruct {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::For]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let initialization = GenvarInitialization::parse(parser)?;
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        let condition = Expression::parse(parser)?;
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        let iteration = ForStep::parse(parser)?;
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        let block = GenerateBlock::parse(parser)?;
+        Some(LoopGenerateConstruct {
+            initialization,
+            condition,
+            iteration,
+            block,
+            span: parser.span_from(first),
+        })
+    }
+}
+
+/// genvar_initialization ::= [ genvar ] genvar_identifier = constant_expression
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct GenvarInitialization {
+    /// Whether the genvar is declared by `genvar`
+    pub genvar: bool,
+    pub identifier: Identifier,
+    pub value: Expression,
+    pub span: NodeSpan,
+}
+
+impl Parse for GenvarInitialization {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = GenvarInitialization::default();
+        if parser.probe(&[Token::GenVar]) {
+            parser.advance();
+            res.genvar = true;
+        }
+        res.identifier = Identifier::parse(parser)?;
+        if !parser.probe_err(&[Token::Equal]) {
+            return None;
+        }
+        parser.advance();
+        res.value = Expression::parse(parser)?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// if_generate_construct ::= if ( constant_expression ) generate_block [ else generate_block ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct IfGenerateConstruct {
+    pub condition: Expression,
+    pub block: GenerateBlock,
+    pub else_block: Option<GenerateBlock>,
+    pub span: NodeSpan,
+}
+
+impl Parse for IfGenerateConstruct {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::If]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = IfGenerateConstruct {
+            condition: parse_paren_expression(parser)?,
+            block: GenerateBlock::parse(parser)?,
+            ..IfGenerateConstruct::default()
+        };
+        if parser.probe(&[Token::Else]) {
+            parser.advance();
+            res.else_block = Some(GenerateBlock::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// case_generate_construct ::= case ( constant_expression ) case_generate_item { case_generate_item } endcase
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct CaseGenerateConstruct {
+    pub expr: Expression,
+    pub items: Vec<CaseGenerateItem>,
+    pub span: NodeSpan,
+}
+
+impl Parse for CaseGenerateConstruct {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Case]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = CaseGenerateConstruct {
+            expr: parse_paren_expression(parser)?,
+            ..CaseGenerateConstruct::default()
+        };
+        loop {
+            res.items.push(CaseGenerateItem::parse(parser)?);
+            if !parser.avail() || parser.probe(&[Token::EndCase]) {
+                break;
+            }
+        }
+        if !parser.probe_err(&[Token::EndCase]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// case_generate_item ::= constant_expression { , constant_expression } : generate_block
+/// | default [ : ] generate_block
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct CaseGenerateItem {
+    /// Empty for `default`
+    pub labels: Vec<Expression>,
+    pub block: GenerateBlock,
+    pub span: NodeSpan,
+}
+
+impl Parse for CaseGenerateItem {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = CaseGenerateItem::default();
+        if parser.probe(&[Token::Default]) {
+            parser.advance();
+            if parser.probe(&[Token::Colon]) {
+                parser.advance();
+            }
+        } else {
+            loop {
+                res.labels.push(Expression::parse(parser)?);
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+            if !parser.probe_err(&[Token::Colon]) {
+                return None;
+            }
+            parser.advance();
+        }
+        res.block = GenerateBlock::parse(parser)?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// generate_block ::= generate_item
+/// | [ generate_block_identifier : ] begin [ : generate_block_identifier ] { generate_item } end [ : generate_block_identifier ]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct GenerateBlock {
+    /// Name before `begin` or after `begin :`
+    pub label: Option<Identifier>,
+    /// Whether the items are enclosed by `begin` and `end`
+    pub begin: bool,
+    pub items: Vec<NonPortModuleItem>,
+    pub end_label: Option<Identifier>,
+    /// `genblk<n>` name of an unnamed block, see `name_generate_blocks`
+    pub implicit_name: Option<String>,
+    pub span: NodeSpan,
+}
+
+impl Parse for GenerateBlock {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = GenerateBlock::default();
+        if parser.probe(&[Token::Identifier]) && parser.probe_next(&[Token::Colon]) {
+            res.label = Some(Identifier::parse(parser)?);
+            parser.advance();
+            if !parser.probe_err(&[Token::Begin]) {
+                return None;
+            }
+        }
+        if !parser.probe(&[Token::Begin]) {
+            res.items.push(NonPortModuleItem::parse(parser)?);
+            res.span = parser.span_from(first);
+            return Some(res);
+        }
+        parser.advance();
+        res.begin = true;
+        if let Some(label) = parse_block_label(parser)? {
+            res.label = Some(label);
+        }
+        while parser.avail() && !parser.probe(&[Token::End]) {
+            res.items.push(NonPortModuleItem::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::End]) {
+            return None;
+        }
+        parser.advance();
+        res.end_label = parse_end_label(parser, res.label.as_ref())?;
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl GenerateBlock {
+    /// The conditional generate construct that is the only item of a block
+    /// without `begin`, it is directly nested in the enclosing construct
+    fn directly_nested(&mut self) -> Option<&mut NonPortModuleItem> {
+        if self.begin {
+            return None;
+        }
+        match self.items.as_mut_slice() {
+            [item @ (NonPortModuleItem::IfGenerate(_) | NonPortModuleItem::CaseGenerate(_))] => {
+                Some(item)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Assign `genblk<n>` names to the unnamed generate blocks of a scope
+///
+/// The generate constructs of a scope are numbered from 1 in textual order
+/// and the blocks of the `n`th construct are named `genblk<n>`. Zeros are
+/// inserted before `n` while the name conflicts with a name declared in
+/// `declared` (IEEE 1800-2017 27.6).
+pub(crate) fn name_generate_blocks<'a>(
+    items: impl IntoIterator<Item = &'a mut NonPortModuleItem>,
+    declared: &[String],
+    parser: &Parser<'_>,
+) {
+    let mut number = 0;
+    for item in items {
+        name_item(item, &mut number, declared, parser);
+    }
+}
+
+fn name_item(
+    item: &mut NonPortModuleItem,
+    number: &mut usize,
+    declared: &[String],
+    parser: &Parser<'_>,
+) {
+    match item {
+        NonPortModuleItem::GenerateRegion(region) => {
+            for item in &mut region.items {
+                name_item(item, number, declared, parser);
+            }
+        }
+        NonPortModuleItem::LoopGenerate(_)
+        | NonPortModuleItem::IfGenerate(_)
+        | NonPortModuleItem::CaseGenerate(_) => {
+            *number += 1;
+            let name = implicit_name(*number, declared);
+            name_construct(item, &name, parser);
+        }
+        _ => {}
+    }
+}
+
+/// Name the blocks of a generate construct and of its directly nested
+/// constructs
+fn name_construct(item: &mut NonPortModuleItem, name: &str, parser: &Parser<'_>) {
+    let blocks: Vec<&mut GenerateBlock> = match item {
+        NonPortModuleItem::LoopGenerate(construct) => {
+            return name_block(&mut construct.block, name, parser)
+        }
+        NonPortModuleItem::IfGenerate(construct) => std::iter::once(&mut construct.block)
+            .chain(construct.else_block.as_mut())
+            .collect(),
+        NonPortModuleItem::CaseGenerate(construct) => construct
+            .items
+            .iter_mut()
+            .map(|item| &mut item.block)
+            .collect(),
+        _ => return,
+    };
+    for block in blocks {
+        match block.directly_nested() {
+            Some(nested) => name_construct(nested, name, parser),
+            None => name_block(block, name, parser),
+        }
+    }
+}
+
+fn name_block(block: &mut GenerateBlock, name: &str, parser: &Parser<'_>) {
+    if block.label.is_none() {
+        block.implicit_name = Some(name.to_owned());
+    }
+    let declared = declared_names(&block.items, parser);
+    name_generate_blocks(&mut block.items, &declared, parser);
+}
+
+fn implicit_name(number: usize, declared: &[String]) -> String {
+    let mut zeros = String::new();
+    loop {
+        let name = format!("genblk{zeros}{number}");
+        if !declared.contains(&name) {
+            return name;
+        }
+        zeros.push('0');
+    }
+}
+
+/// Names declared by the items of a scope
+pub(crate) fn declared_names<'a>(
+    items: impl IntoIterator<Item = &'a NonPortModuleItem>,
+    parser: &Parser<'_>,
+) -> Vec<String> {
+    let mut identifiers = vec![];
+    for item in items {
+        collect_declared(item, &mut identifiers);
+    }
+    identifiers
+        .into_iter()
+        .map(|identifier| parser.get_token(identifier.token).text.to_string())
+        .collect()
+}
+
+fn collect_declared<'a>(item: &'a NonPortModuleItem, identifiers: &mut Vec<&'a Identifier>) {
+    match item {
+        NonPortModuleItem::Parameter(declaration) => identifiers.extend(
+            declaration
+                .assignments
+                .iter()
+                .map(|assignment| &assignment.identifier),
+        ),
+        NonPortModuleItem::Genvar(declaration) => identifiers.extend(&declaration.identifiers),
+        NonPortModuleItem::Net(declaration) => identifiers.extend(
+            declaration
+                .assignments
+                .iter()
+                .map(|assignment| &assignment.identifier),
+        ),
+        NonPortModuleItem::Data(declaration) => identifiers.extend(
+            declaration
+                .assignments
+                .iter()
+                .map(|assignment| &assignment.identifier),
+        ),
+        NonPortModuleItem::Type(declaration) => identifiers.push(&declaration.identifier),
+        NonPortModuleItem::NetType(declaration) => identifiers.push(&declaration.identifier),
+        NonPortModuleItem::Function(declaration) => identifiers.push(&declaration.identifier),
+        NonPortModuleItem::Task(declaration) => identifiers.push(&declaration.identifier),
+        NonPortModuleItem::Instantiation(instantiation) => identifiers.extend(
+            instantiation
+                .instances
+                .iter()
+                .map(|instance| &instance.identifier),
+        ),
+        NonPortModuleItem::GenerateRegion(region) => {
+            for item in &region.items {
+                collect_declared(item, identifiers);
+            }
+        }
+        NonPortModuleItem::LoopGenerate(construct) => {
+            identifiers.extend(&construct.block.label);
+        }
+        NonPortModuleItem::IfGenerate(construct) => {
+            collect_labels(&construct.block, identifiers);
+            if let Some(block) = &construct.else_block {
+                collect_labels(block, identifiers);
+            }
+        }
+        NonPortModuleItem::CaseGenerate(construct) => {
+            for item in &construct.items {
+                collect_labels(&item.block, identifiers);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Names of a block and the blocks of its directly nested constructs
+fn collect_labels<'a>(block: &'a GenerateBlock, identifiers: &mut Vec<&'a Identifier>) {
+    identifiers.extend(&block.label);
+    if let [item @ (NonPortModuleItem::IfGenerate(_) | NonPortModuleItem::CaseGenerate(_))] =
+        block.items.as_slice()
+    {
+        if !block.begin {
+            collect_declared(item, identifiers);
+        }
+    }
+}
+
+spanned!(
+    GenerateRegion,
+    LoopGenerateConstruct,
+    GenvarInitialization,
+    IfGenerateConstruct,
+    CaseGenerateConstruct,
+    CaseGenerateItem,
+    GenerateBlock
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loop_generate() {
+        let mut parser = Parser::from(
+            "for (genvar i = 0; i < N; i++) begin : g
+               wire [i:0] w;
+               adder u (.a(w));
+             end : g",
+        );
+        let construct = LoopGenerateConstruct::parse(&mut parser).unwrap();
+        assert!(construct.initialization.genvar);
+        assert!(matches!(construct.iteration, ForStep::IncOrDec(_)));
+        assert_eq!(construct.block.label, Some(Identifier { token: 16 }));
+        assert_eq!(construct.block.end_label, Some(Identifier { token: 37 }));
+        assert_eq!(construct.block.items.len(), 2);
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("for (i = 0; i < 4; i = i + 1) assign a[i] = b[i];");
+        let construct = LoopGenerateConstruct::parse(&mut parser).unwrap();
+        assert!(!construct.initialization.genvar);
+        assert!(matches!(construct.iteration, ForStep::Assignment(_)));
+        assert!(!construct.block.begin);
+        assert!(matches!(
+            construct.block.items[0],
+            NonPortModuleItem::ContinuousAssign(_)
+        ));
+        assert!(parser.get_diag().is_empty());
+    }
+
+    #[test]
+    fn conditional_generate() {
+        let mut parser =
+            Parser::from("if (W > 8) wire a; else if (W > 4) begin : mid wire b; end else wire c;");
+        let construct = IfGenerateConstruct::parse(&mut parser).unwrap();
+        let else_block = construct.else_block.as_ref().unwrap();
+        assert!(matches!(
+            else_block.items[0],
+            NonPortModuleItem::IfGenerate(_)
+        ));
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser =
+            Parser::from("case (W) 1, 2: wire a; 4: begin wire b; end default wire c; endcase");
+        let construct = CaseGenerateConstruct::parse(&mut parser).unwrap();
+        assert_eq!(construct.items.len(), 3);
+        assert_eq!(construct.items[0].labels.len(), 2);
+        assert!(construct.items[1].block.begin);
+        assert!(construct.items[2].labels.is_empty());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("generate genvar i, j; if (1) wire a; endgenerate");
+        let region = GenerateRegion::parse(&mut parser).unwrap();
+        assert_eq!(region.items.len(), 2);
+        assert!(parser.get_diag().is_empty());
+    }
+
+    #[test]
+    fn implicit_names() {
+        // IEEE 1800-2017 27.6
+        let mut parser = Parser::from(
+            "module top;
+               parameter genblk2 = 0;
+               genvar i;
+               if (genblk2) wire a; else wire b;
+               if (genblk2) wire a; else wire b;
+               for (i = 0; i < 1; i = i + 1) begin : g1
+                 if (1) wire a;
+               end
+               generate
+                 for (i = 0; i < 1; i = i + 1)
+                   if (1) wire a;
+               endgenerate
+               if (1) wire a;
+               else if (2) wire b;
+               else begin : named wire c; end
+             endmodule",
+        );
+        let m = ModuleDeclaration::parse(&mut parser).unwrap();
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        let items: Vec<&NonPortModuleItem> = m
+            .items
+            .iter()
+            .map(|item| match item {
+                ModuleItem::NonPort(item) => item,
+                item => panic!("{:?}", item),
+            })
+            .collect();
+        let name = |block: &GenerateBlock| block.implicit_name.clone();
+        let if_construct = |item: &NonPortModuleItem| match item {
+            NonPortModuleItem::IfGenerate(construct) => construct.as_ref().clone(),
+            item => panic!("{:?}", item),
+        };
+
+        let first = if_construct(items[2]);
+        assert_eq!(name(&first.block).as_deref(), Some("genblk1"));
+        assert_eq!(
+            name(first.else_block.as_ref().unwrap()).as_deref(),
+            Some("genblk1")
+        );
+        let second = if_construct(items[3]);
+        assert_eq!(name(&second.block).as_deref(), Some("genblk02"));
+
+        let g1 = match items[4] {
+            NonPortModuleItem::LoopGenerate(construct) => &construct.block,
+            item => panic!("{:?}", item),
+        };
+        assert_eq!(name(g1), None);
+        assert_eq!(
+            name(&if_construct(&g1.items[0]).block).as_deref(),
+            Some("genblk1")
+        );
+
+        let genblk4 = match items[5] {
+            NonPortModuleItem::GenerateRegion(region) => match &region.items[0] {
+                NonPortModuleItem::LoopGenerate(construct) => &construct.block,
+                item => panic!("{:?}", item),
+            },
+            item => panic!("{:?}", item),
+        };
+        assert_eq!(name(genblk4).as_deref(), Some("genblk4"));
+        // the `if` is the only item of the loop block, but loops do not nest directly
+        assert_eq!(
+            name(&if_construct(&genblk4.items[0]).block).as_deref(),
+            Some("genblk1")
+        );
+
+        // directly nested `else if` blocks belong to the outer construct
+        let genblk5 = if_construct(items[6]);
+        assert_eq!(name(&genblk5.block).as_deref(), Some("genblk5"));
+        let nested = if_construct(&genblk5.else_block.as_ref().unwrap().items[0]);
+        assert_eq!(name(&nested.block).as_deref(), Some("genblk5"));
+        assert_eq!(name(nested.else_block.as_ref().unwrap()), None);
+        assert_eq!(name(genblk5.else_block.as_ref().unwrap()), None);
+    }
+}
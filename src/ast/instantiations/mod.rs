@@ -0,0 +1,7 @@
+//! A.4 Instantiations
+
+mod generated_instantiation;
+mod module_instantiation;
+
+pub use generated_instantiation::*;
+pub use module_instantiation::*;
@@ -0,0 +1,336 @@
+//! A.4.1.1 Module instantiation
+
+use crate::ast::*;
+
+/// module_instantiation ::= module_identifier [ parameter_value_assignment ]
+/// hierarchical_instance { , hierarchical_instance } ;
+///
+/// Interface instantiations have the same syntax.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ModuleInstantiation {
+    pub module: Identifier,
+    pub parameters: Option<ParameterValueAssignment>,
+    pub instances: Vec<HierarchicalInstance>,
+    pub span: NodeSpan,
+}
+
+impl ModuleInstantiation {
+    /// Whether the next tokens start an instantiation, a module name
+    /// followed by parameters or an instance name
+    pub(crate) fn is_instantiation(parser: &Parser<'_>) -> bool {
+        let mut tokens = parser.lookahead();
+        tokens.next() == Some(Token::Identifier)
+            && matches!(tokens.next(), Some(Token::Sharp | Token::Identifier))
+    }
+}
+
+impl Parse for ModuleInstantiation {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = ModuleInstantiation {
+            module: Identifier::parse(parser)?,
+            ..ModuleInstantiation::default()
+        };
+        if parser.probe(&[Token::Sharp]) {
+            res.parameters = Some(ParameterValueAssignment::parse(parser)?);
+        }
+        loop {
+            res.instances.push(HierarchicalInstance::parse(parser)?);
+            if !parser.probe(&[Token::Comma]) {
+                break;
+            }
+            parser.advance();
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// parameter_value_assignment ::= # ( [ list_of_parameter_value_assignments ] )
+/// list_of_parameter_value_assignments ::= ordered_parameter_assignment { , ordered_parameter_assignment }
+/// | named_parameter_assignment { , named_parameter_assignment }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ParameterValueAssignment {
+    pub assignments: Vec<ParameterAssignment>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ParameterValueAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Sharp]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = ParameterValueAssignment::default();
+        if !parser.probe(&[Token::RParen]) {
+            loop {
+                let assignment = ParameterAssignment::parse(parser)?;
+                if let Some(previous) = res.assignments.last() {
+                    if previous.name.is_some() != assignment.name.is_some() {
+                        let span = parser.span(assignment.span);
+                        parser.err(span.from, span.to, Message::MixedConnections);
+                    }
+                }
+                res.assignments.push(assignment);
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+        }
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// ordered_parameter_assignment ::= param_expression
+/// named_parameter_assignment ::= . parameter_identifier ( [ param_expression ] )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ParameterAssignment {
+    /// `None` for an ordered assignment
+    pub name: Option<Identifier>,
+    /// `None` for `.name()`
+    pub value: Option<ParamExpression>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ParameterAssignment {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = ParameterAssignment::default();
+        if parser.probe(&[Token::Dot]) {
+            parser.advance();
+            res.name = Some(Identifier::parse(parser)?);
+            if !parser.probe_err(&[Token::LParen]) {
+                return None;
+            }
+            parser.advance();
+            if !parser.probe(&[Token::RParen]) {
+                res.value = Some(ParamExpression::parse(parser)?);
+            }
+            if !parser.probe_err(&[Token::RParen]) {
+                return None;
+            }
+            parser.advance();
+        } else {
+            res.value = Some(ParamExpression::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// hierarchical_instance ::= name_of_instance ( [ list_of_port_connections ] )
+/// name_of_instance ::= instance_identifier { unpacked_dimension }
+/// list_of_port_connections ::= ordered_port_connection { , ordered_port_connection }
+/// | named_port_connection { , named_port_connection }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct HierarchicalInstance {
+    pub identifier: Identifier,
+    /// Dimensions of an instance array
+    pub dimensions: Vec<UnpackedDimension>,
+    pub connections: Vec<PortConnection>,
+    pub span: NodeSpan,
+}
+
+impl Parse for HierarchicalInstance {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = HierarchicalInstance {
+            identifier: Identifier::parse(parser)?,
+            ..HierarchicalInstance::default()
+        };
+        while parser.probe(&[Token::LBracket]) {
+            res.dimensions.push(UnpackedDimension::parse(parser)?);
+        }
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe(&[Token::RParen]) {
+            loop {
+                let connection = PortConnection::parse(parser)?;
+                if let Some(previous) = res.connections.last() {
+                    if previous.is_ordered() != connection.is_ordered() {
+                        match connection.span {
+                            Some(span) => {
+                                let span = parser.span(span);
+                                parser.err(span.from, span.to, Message::MixedConnections);
+                            }
+                            None => parser.err(
+                                parser.location_from(),
+                                parser.location_to(),
+                                Message::MixedConnections,
+                            ),
+                        }
+                    }
+                }
+                res.connections.push(connection);
+                if !parser.probe(&[Token::Comma]) {
+                    break;
+                }
+                parser.advance();
+            }
+        }
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// ordered_port_connection ::= { attribute_instance } [ expression ]
+/// named_port_connection ::= { attribute_instance } . port_identifier [ ( [ expression ] ) ]
+/// | { attribute_instance } .*
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct PortConnection {
+    pub attributes: Attributes,
+    pub kind: PortConnectionKind,
+    /// `None` for an empty ordered connection
+    pub span: Option<NodeSpan>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum PortConnectionKind {
+    /// `expr`, `None` if the port is left unconnected
+    Ordered(Option<Expression>),
+    /// `.name(expr)`, `.name()` has no expression
+    Named(Identifier, Option<Expression>),
+    /// `.name`, connected to the signal of the same name
+    Implicit(Identifier),
+    /// `.*`
+    #[default]
+    Wildcard,
+}
+
+impl PortConnection {
+    fn is_ordered(&self) -> bool {
+        matches!(self.kind, PortConnectionKind::Ordered(_))
+    }
+}
+
+impl Parse for PortConnection {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let mut res = PortConnection::default();
+        if parser.probe(&[Token::LParen]) && parser.probe_next(&[Token::OpMultiply]) {
+            res.attributes = Attributes::parse(parser)?;
+        }
+        res.kind = if parser.probe(&[Token::DotStar]) {
+            parser.advance();
+            PortConnectionKind::Wildcard
+        } else if parser.probe(&[Token::Dot]) {
+            parser.advance();
+            let name = Identifier::parse(parser)?;
+            if parser.probe(&[Token::LParen]) {
+                parser.advance();
+                let mut expr = None;
+                if !parser.probe(&[Token::RParen]) {
+                    expr = Some(Expression::parse(parser)?);
+                }
+                if !parser.probe_err(&[Token::RParen]) {
+                    return None;
+                }
+                parser.advance();
+                PortConnectionKind::Named(name, expr)
+            } else {
+                PortConnectionKind::Implicit(name)
+            }
+        } else if parser.probe(&[Token::Comma, Token::RParen]) {
+            PortConnectionKind::Ordered(None)
+        } else {
+            PortConnectionKind::Ordered(Some(Expression::parse(parser)?))
+        };
+        res.span = parser.optional_span_from(first);
+        Some(res)
+    }
+}
+
+spanned!(
+    ModuleInstantiation,
+    ParameterValueAssignment,
+    ParameterAssignment,
+    HierarchicalInstance
+);
+
+impl Spanned for PortConnection {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_instantiation() {
+        let mut parser =
+            Parser::from("adder #(.W(8), .D(), .T(logic [3:0])) u0 (.a(x[3:0]), .b, .c(), .*), u1 [3:0] (.a(y));");
+        assert!(ModuleInstantiation::is_instantiation(&parser));
+        let inst = ModuleInstantiation::parse(&mut parser).unwrap();
+        let parameters = inst.parameters.as_ref().unwrap();
+        assert_eq!(parameters.assignments.len(), 3);
+        assert_eq!(
+            parameters.assignments[0].name,
+            Some(Identifier { token: 4 })
+        );
+        assert_eq!(parameters.assignments[1].value, None);
+        assert!(matches!(
+            parameters.assignments[2].value,
+            Some(ParamExpression::DataType(_))
+        ));
+        assert_eq!(inst.instances.len(), 2);
+        let connections = &inst.instances[0].connections;
+        assert!(matches!(
+            connections[0].kind,
+            PortConnectionKind::Named(_, Some(Expression::Select(_)))
+        ));
+        assert!(matches!(
+            connections[1].kind,
+            PortConnectionKind::Implicit(_)
+        ));
+        assert!(matches!(
+            connections[2].kind,
+            PortConnectionKind::Named(_, None)
+        ));
+        assert_eq!(connections[3].kind, PortConnectionKind::Wildcard);
+        assert_eq!(inst.instances[1].dimensions.len(), 1);
+        assert!(!parser.avail());
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("mux #(4, 2) m (a, , (* keep *) b, );");
+        let inst = ModuleInstantiation::parse(&mut parser).unwrap();
+        assert_eq!(inst.parameters.as_ref().unwrap().assignments[1].name, None);
+        let connections = &inst.instances[0].connections;
+        assert_eq!(connections.len(), 4);
+        assert_eq!(connections[1].kind, PortConnectionKind::Ordered(None));
+        assert_eq!(connections[1].span, None);
+        assert_eq!(connections[2].attributes.attrs.len(), 1);
+        assert_eq!(connections[3].kind, PortConnectionKind::Ordered(None));
+        assert!(parser.get_diag().is_empty());
+
+        let mut parser = Parser::from("m u ();");
+        let inst = ModuleInstantiation::parse(&mut parser).unwrap();
+        assert!(inst.instances[0].connections.is_empty());
+
+        let mut parser = Parser::from("m u (a, .b(c));");
+        assert!(ModuleInstantiation::parse(&mut parser).is_some());
+        assert_eq!(parser.get_diag()[0].msg, Message::MixedConnections);
+    }
+}
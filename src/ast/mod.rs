@@ -1,16 +1,24 @@
 //! Verilog AST
 
-use crate::{diagnostic::Message, lexer::Token, parser::Parser};
+use crate::{
+    diagnostic::Message,
+    lexer::{ParsedToken, Span, Token},
+    parser::Parser,
+};
 use serde::{Deserialize, Serialize};
 
+mod behavioral_statements;
 mod declarations;
 mod expressions;
 mod general;
+mod instantiations;
 mod source_text;
 
+pub use behavioral_statements::*;
 pub use declarations::*;
 pub use expressions::*;
 pub use general::*;
+pub use instantiations::*;
 pub use source_text::*;
 
 type TokenIndex = usize;
@@ -18,3 +26,80 @@ type TokenIndex = usize;
 pub trait Parse: Sized {
     fn parse(parser: &mut Parser) -> Option<Self>;
 }
+
+/// First and last token of a node, both inclusive
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, Default)]
+pub struct NodeSpan {
+    pub first: TokenIndex,
+    pub last: TokenIndex,
+}
+
+impl NodeSpan {
+    pub fn token(index: TokenIndex) -> NodeSpan {
+        NodeSpan {
+            first: index,
+            last: index,
+        }
+    }
+
+    /// Smallest span covering both spans
+    pub fn join(self, other: NodeSpan) -> NodeSpan {
+        NodeSpan {
+            first: self.first.min(other.first),
+            last: self.last.max(other.last),
+        }
+    }
+
+    /// Location in the source
+    ///
+    /// A node may start and end in different files when it spans an
+    /// `include, the span then ends at the last token in the file of the
+    /// first token.
+    pub fn resolve(&self, tokens: &[ParsedToken<'_>]) -> Span {
+        let first = &tokens[self.first];
+        let last = tokens[self.first..=self.last]
+            .iter()
+            .rev()
+            .find(|token| token.span.file == first.span.file)
+            .unwrap_or(first);
+        Span {
+            file: first.span.file,
+            from: first.span.from,
+            to: last.span.to,
+        }
+    }
+}
+
+/// Nodes that know their tokens
+///
+/// Nodes without tokens, like an empty attribute list, have no span.
+pub trait Spanned {
+    fn node_span(&self) -> Option<NodeSpan>;
+}
+
+impl<T: Spanned> Spanned for Option<T> {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.as_ref().and_then(Spanned::node_span)
+    }
+}
+
+impl<T: Spanned> Spanned for Vec<T> {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.iter()
+            .filter_map(Spanned::node_span)
+            .reduce(NodeSpan::join)
+    }
+}
+
+/// Implement `Spanned` for nodes with a `span` field
+macro_rules! spanned {
+    ($($s:ident),*) => {
+        $(impl Spanned for $s {
+            fn node_span(&self) -> Option<NodeSpan> {
+                Some(self.span)
+            }
+        })*
+    };
+}
+
+pub(crate) use spanned;
@@ -25,11 +25,242 @@ impl Parse for ModuleItem {
                     return Some(ModuleItem::Port(port));
                 }
             }
+        } else if parser.probe(NonPortModuleItem::FIRST)
+            || DataDeclaration::is_data_declaration(parser)
+            || NetDeclaration::is_user_net(parser)
+            || ModuleInstantiation::is_instantiation(parser)
+        {
+            return Some(ModuleItem::NonPort(NonPortModuleItem::parse(parser)?));
         }
         None
     }
 }
 
-/// non_port_module_item ::=
+impl Spanned for ModuleItem {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            ModuleItem::Port(port) => port.node_span(),
+            ModuleItem::NonPort(item) => item.node_span(),
+        }
+    }
+}
+
+/// non_port_module_item ::= generate_region | module_or_generate_item
+/// module_or_generate_item ::= parameter_override | module_instantiation | module_common_item
+/// module_common_item ::= module_or_generate_item_declaration
+/// | continuous_assign
+/// | initial_construct
+/// | final_construct
+/// | always_construct
+/// | loop_generate_construct
+/// | conditional_generate_construct
+/// module_or_generate_item_declaration ::= net_declaration | data_declaration
+/// | type_declaration | nettype_declaration | genvar_declaration
+/// | local_parameter_declaration ; | parameter_declaration ;
+/// | task_declaration | function_declaration | dpi_import_export
+/// conditional_generate_construct ::= if_generate_construct | case_generate_construct
+///
+/// `foo_t x;` declares a variable if `foo_t` is declared by a `typedef`
+/// earlier in the module or has a package or class scope, otherwise it
+/// declares a net of a user-defined net type.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum NonPortModuleItem {
+    Parameter(ParameterDeclaration),
+    Defparam(ParameterOverride),
+    Genvar(GenvarDeclaration),
+    GenerateRegion(GenerateRegion),
+    LoopGenerate(Box<LoopGenerateConstruct>),
+    IfGenerate(Box<IfGenerateConstruct>),
+    CaseGenerate(CaseGenerateConstruct),
+    Function(FunctionDeclaration),
+    Task(TaskDeclaration),
+    Dpi(DpiImportExport),
+    Net(NetDeclaration),
+    Data(DataDeclaration),
+    Type(TypeDeclaration),
+    NetType(NetTypeDeclaration),
+    ContinuousAssign(ContinuousAssign),
+    Instantiation(ModuleInstantiation),
+    Initial(InitialConstruct),
+    Final(FinalConstruct),
+    Always(AlwaysConstruct),
+}
+
+impl NonPortModuleItem {
+    /// Tokens that start a non-port module item
+    pub(crate) const FIRST: &'static [Token] = &[
+        Token::Supply0,
+        Token::Supply1,
+        Token::Tri,
+        Token::TriAnd,
+        Token::TriOr,
+        Token::TriReg,
+        Token::Tri0,
+        Token::Tri1,
+        Token::Uwire,
+        Token::Wire,
+        Token::Wand,
+        Token::Wor,
+        Token::Interconnect,
+        Token::NetType,
+        Token::TypeDef,
+        Token::Parameter,
+        Token::Localparam,
+        Token::DefParam,
+        Token::GenVar,
+        Token::Generate,
+        Token::For,
+        Token::If,
+        Token::Case,
+        Token::Function,
+        Token::Task,
+        Token::Import,
+        Token::Export,
+        Token::Assign,
+        Token::Initial,
+        Token::Final,
+        Token::Always,
+        Token::AlwaysComb,
+        Token::AlwaysLatch,
+        Token::AlwaysFf,
+    ];
+}
+
+impl Parse for NonPortModuleItem {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        match parser.peek().map(|token| token.token) {
+            Some(Token::Parameter | Token::Localparam) => {
+                let declaration = ParameterDeclaration::parse(parser)?;
+                if !parser.probe_err(&[Token::Semicolon]) {
+                    return None;
+                }
+                parser.advance();
+                Some(NonPortModuleItem::Parameter(declaration))
+            }
+            Some(Token::DefParam) => Some(NonPortModuleItem::Defparam(ParameterOverride::parse(
+                parser,
+            )?)),
+            Some(Token::GenVar) => {
+                Some(NonPortModuleItem::Genvar(GenvarDeclaration::parse(parser)?))
+            }
+            Some(Token::Generate) => Some(NonPortModuleItem::GenerateRegion(
+                GenerateRegion::parse(parser)?,
+            )),
+            Some(Token::For) => Some(NonPortModuleItem::LoopGenerate(Box::new(
+                LoopGenerateConstruct::parse(parser)?,
+            ))),
+            Some(Token::If) => Some(NonPortModuleItem::IfGenerate(Box::new(
+                IfGenerateConstruct::parse(parser)?,
+            ))),
+            Some(Token::Case) => Some(NonPortModuleItem::CaseGenerate(
+                CaseGenerateConstruct::parse(parser)?,
+            )),
+            Some(Token::Function) => Some(NonPortModuleItem::Function(FunctionDeclaration::parse(
+                parser,
+            )?)),
+            Some(Token::Task) => Some(NonPortModuleItem::Task(TaskDeclaration::parse(parser)?)),
+            Some(Token::Import | Token::Export) => {
+                Some(NonPortModuleItem::Dpi(DpiImportExport::parse(parser)?))
+            }
+            Some(Token::NetType) => {
+                let declaration = NetTypeDeclaration::parse(parser)?;
+                parser.declare_net_type(declaration.identifier.token);
+                Some(NonPortModuleItem::NetType(declaration))
+            }
+            Some(Token::Assign) => Some(NonPortModuleItem::ContinuousAssign(
+                ContinuousAssign::parse(parser)?,
+            )),
+            Some(Token::Initial) => {
+                Some(NonPortModuleItem::Initial(InitialConstruct::parse(parser)?))
+            }
+            Some(Token::Final) => Some(NonPortModuleItem::Final(FinalConstruct::parse(parser)?)),
+            Some(Token::Always | Token::AlwaysComb | Token::AlwaysLatch | Token::AlwaysFf) => {
+                Some(NonPortModuleItem::Always(AlwaysConstruct::parse(parser)?))
+            }
+            Some(Token::TypeDef) => {
+                let declaration = TypeDeclaration::parse(parser)?;
+                parser.declare_type(declaration.identifier.token);
+                Some(NonPortModuleItem::Type(declaration))
+            }
+            Some(Token::Identifier)
+                if TypeName::is_scoped(parser)
+                    || (parser.is_type_name() && DataDeclaration::is_data_declaration(parser)) =>
+            {
+                Some(NonPortModuleItem::Data(DataDeclaration::parse(parser)?))
+            }
+            Some(Token::Identifier) if !NetDeclaration::is_user_net(parser) => Some(
+                NonPortModuleItem::Instantiation(ModuleInstantiation::parse(parser)?),
+            ),
+            // names not declared by `nettype` are taken to be data types
+            Some(Token::Identifier) if !parser.is_net_type_name() => {
+                Some(NonPortModuleItem::Data(DataDeclaration::parse(parser)?))
+            }
+            Some(token)
+                if token != Token::Identifier && DataDeclaration::is_data_declaration(parser) =>
+            {
+                Some(NonPortModuleItem::Data(DataDeclaration::parse(parser)?))
+            }
+            _ => Some(NonPortModuleItem::Net(NetDeclaration::parse(parser)?)),
+        }
+    }
+}
+
+impl Spanned for NonPortModuleItem {
+    fn node_span(&self) -> Option<NodeSpan> {
+        match self {
+            NonPortModuleItem::Parameter(item) => item.node_span(),
+            NonPortModuleItem::Defparam(item) => item.node_span(),
+            NonPortModuleItem::Genvar(item) => item.node_span(),
+            NonPortModuleItem::GenerateRegion(item) => item.node_span(),
+            NonPortModuleItem::LoopGenerate(item) => item.node_span(),
+            NonPortModuleItem::IfGenerate(item) => item.node_span(),
+            NonPortModuleItem::CaseGenerate(item) => item.node_span(),
+            NonPortModuleItem::Function(item) => item.node_span(),
+            NonPortModuleItem::Task(item) => item.node_span(),
+            NonPortModuleItem::Dpi(item) => item.node_span(),
+            NonPortModuleItem::Net(item) => item.node_span(),
+            NonPortModuleItem::Data(item) => item.node_span(),
+            NonPortModuleItem::Type(item) => item.node_span(),
+            NonPortModuleItem::NetType(item) => item.node_span(),
+            NonPortModuleItem::ContinuousAssign(item) => item.node_span(),
+            NonPortModuleItem::Instantiation(item) => item.node_span(),
+            NonPortModuleItem::Initial(item) => item.node_span(),
+            NonPortModuleItem::Final(item) => item.node_span(),
+            NonPortModuleItem::Always(item) => item.node_span(),
+        }
+    }
+}
+
+/// parameter_override ::= defparam list_of_defparam_assignments ;
+/// list_of_defparam_assignments ::= defparam_assignment { , defparam_assignment }
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
-pub struct NonPortModuleItem {}
+pub struct ParameterOverride {
+    pub assignments: Vec<DefparamAssignment>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ParameterOverride {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        if !parser.probe_err(&[Token::DefParam]) {
+            return None;
+        }
+        parser.advance();
+        let mut res = ParameterOverride::default();
+        loop {
+            res.assignments.push(DefparamAssignment::parse(parser)?);
+            if !parser.probe(&[Token::Comma]) {
+                break;
+            }
+            parser.advance();
+        }
+        if !parser.probe_err(&[Token::Semicolon]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+spanned!(ParameterOverride);
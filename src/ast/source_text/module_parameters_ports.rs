@@ -1,17 +1,252 @@
 //! A.1.3 Module parameters and ports
 use crate::ast::*;
 
+/// parameter_port_list ::= # ( list_of_param_assignments { , parameter_port_declaration } )
+/// | # ( parameter_port_declaration { , parameter_port_declaration } )
+/// | # ( )
+/// parameter_port_declaration ::= parameter_declaration
+/// | local_parameter_declaration
+/// | data_type list_of_param_assignments
+/// | type list_of_type_assignments
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ParameterPortList {
+    pub declarations: Vec<ParameterDeclaration>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ParameterPortList {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let mut res = ParameterPortList::default();
+        let first = parser.start();
+        if !parser.probe_err(&[Token::Sharp]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        if !parser.probe(&[Token::RParen]) {
+            loop {
+                res.declarations
+                    .push(ParameterDeclaration::parse_optional_keyword(parser)?);
+                if parser.probe(&[Token::Comma]) {
+                    parser.advance();
+                } else {
+                    break;
+                }
+            }
+        }
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// list_of_ports ::= ( port { , port } )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct ListOfPorts {
+    pub ports: Vec<NonAnsiPort>,
+    pub span: NodeSpan,
+}
+
+impl Parse for ListOfPorts {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let mut res = ListOfPorts::default();
+        let first = parser.start();
+        if !parser.probe_err(&[Token::LParen]) {
+            return None;
+        }
+        parser.advance();
+        loop {
+            res.ports.push(NonAnsiPort::parse(parser)?);
+            if parser.probe(&[Token::Comma]) {
+                parser.advance();
+            } else {
+                break;
+            }
+        }
+        if !parser.probe_err(&[Token::RParen]) {
+            return None;
+        }
+        parser.advance();
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+impl ListOfPorts {
+    /// Whether the port list at `(` is not ANSI, as its first port has no
+    /// direction or type, an empty list `()` is parsed as ANSI
+    pub(crate) fn is_list_of_ports(parser: &Parser<'_>) -> bool {
+        let mut tokens = parser.lookahead().skip(1);
+        match tokens.next() {
+            Some(Token::Dot | Token::LBraces | Token::Comma) => true,
+            Some(Token::Identifier) => matches!(
+                tokens.next(),
+                Some(Token::Comma | Token::RParen | Token::LBracket)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Port references of all ports
+    pub fn references(&self) -> impl Iterator<Item = &PortReference> {
+        self.ports
+            .iter()
+            .filter_map(|port| port.expression.as_ref())
+            .flat_map(|expression| expression.references.iter())
+    }
+}
+
+/// port ::= [ port_expression ] | . port_identifier ( [ port_expression ] )
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct NonAnsiPort {
+    /// Name of an explicitly named port `.name(expression)`
+    pub name: Option<Identifier>,
+    pub expression: Option<PortExpression>,
+    /// `None` for an empty port
+    pub span: Option<NodeSpan>,
+}
+
+impl Parse for NonAnsiPort {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let mut res = NonAnsiPort::default();
+        let first = parser.start();
+        if parser.probe(&[Token::Dot]) {
+            parser.advance();
+            res.name = Some(Identifier::parse(parser)?);
+            if !parser.probe_err(&[Token::LParen]) {
+                return None;
+            }
+            parser.advance();
+            if !parser.probe(&[Token::RParen]) {
+                res.expression = Some(PortExpression::parse(parser)?);
+            }
+            if !parser.probe_err(&[Token::RParen]) {
+                return None;
+            }
+            parser.advance();
+        } else if !parser.probe(&[Token::Comma, Token::RParen]) {
+            res.expression = Some(PortExpression::parse(parser)?);
+        }
+        res.span = parser.optional_span_from(first);
+        Some(res)
+    }
+}
+
+impl NonAnsiPort {
+    /// Name of an explicitly named port or of a port that is a plain
+    /// identifier, other ports have no name
+    pub fn port_name(&self) -> Option<&Identifier> {
+        if self.name.is_some() {
+            return self.name.as_ref();
+        }
+        let expression = self.expression.as_ref()?;
+        match expression.references.as_slice() {
+            [PortReference {
+                identifier,
+                expression: Expression::Name(_),
+            }] if !expression.concatenation => Some(identifier),
+            _ => None,
+        }
+    }
+}
+
+/// port_expression ::= port_reference | { port_reference { , port_reference } }
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct PortExpression {
+    pub references: Vec<PortReference>,
+    /// Whether the references are concatenated by `{ }`
+    pub concatenation: bool,
+    pub span: NodeSpan,
+}
+
+impl Parse for PortExpression {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let mut res = PortExpression::default();
+        let first = parser.start();
+        if parser.probe(&[Token::LBraces]) {
+            parser.advance();
+            res.concatenation = true;
+            loop {
+                res.references.push(PortReference::parse(parser)?);
+                if parser.probe(&[Token::Comma]) {
+                    parser.advance();
+                } else {
+                    break;
+                }
+            }
+            if !parser.probe_err(&[Token::RBraces]) {
+                return None;
+            }
+            parser.advance();
+        } else {
+            res.references.push(PortReference::parse(parser)?);
+        }
+        res.span = parser.span_from(first);
+        Some(res)
+    }
+}
+
+/// port_reference ::= port_identifier constant_select
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct PortReference {
+    pub identifier: Identifier,
+    /// The identifier with its selects, like `x[3:0]`
+    pub expression: Expression,
+}
+
+impl Parse for PortReference {
+    fn parse(parser: &mut Parser<'_>) -> Option<Self> {
+        let first = parser.start();
+        let identifier = Identifier::parse(parser)?;
+        let mut expression = Expression::Name(identifier.clone());
+        while parser.probe(&[Token::LBracket]) {
+            expression =
+                Expression::Select(Box::new(Select::parse_suffix(parser, first, expression)?));
+        }
+        Some(PortReference {
+            identifier,
+            expression,
+        })
+    }
+}
+
+impl Spanned for PortReference {
+    fn node_span(&self) -> Option<NodeSpan> {
+        Some(self.expression.span())
+    }
+}
+
+impl Spanned for NonAnsiPort {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.span
+    }
+}
+
 /// list_of_port_declarations ::= ( [ { attribute_instance } ansi_port_declaration { , { attribute_instance } ansi_port_declaration } ] )
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct Ports {
     pub ports: Vec<(Attributes, Port)>,
+    /// `None` for a header without port list
+    pub span: Option<NodeSpan>,
 }
 
 impl Parse for Ports {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = Ports::default();
+        let first = parser.start();
         if parser.probe_err(&[Token::LParen]) {
             parser.advance();
+            if parser.probe(&[Token::RParen]) {
+                parser.advance();
+                res.span = parser.optional_span_from(first);
+                return Some(res);
+            }
             loop {
                 let attrs = if parser.probe(&[Token::LParen]) {
                     Attributes::parse(parser).unwrap_or_default()
@@ -32,10 +267,17 @@ impl Parse for Ports {
                 }
             }
         }
+        res.span = parser.optional_span_from(first);
         Some(res)
     }
 }
 
+impl Spanned for Ports {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.span
+    }
+}
+
 /// ansi_port_declaration ::= [ net_port_header ] port_identifier { unpacked_dimension }
 /// net_port_header ::= [ port_direction ] net_port_type
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
@@ -44,11 +286,13 @@ pub struct Port {
     pub net_port_type: Option<NetPortType>,
     pub identifier: Identifier,
     pub dimensions: Vec<UnpackedDimension>,
+    pub span: NodeSpan,
 }
 
 impl Parse for Port {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = Port::default();
+        let first = parser.start();
         if parser.probe(&[Token::Input, Token::Output, Token::InOut, Token::Ref]) {
             res.direction = PortDirection::parse(parser);
         }
@@ -65,14 +309,9 @@ impl Parse for Port {
             Token::Wire,
             Token::Wand,
             Token::Wor,
-            // data_type_or_implicit
-            Token::Bit,
-            Token::Logic,
-            Token::Reg,
-            Token::Signed,
-            Token::Unsigned,
-            Token::LBracket,
-        ]) {
+        ]) || DataType::is_data_type(parser)
+            || parser.probe(ImplicitDataType::FIRST)
+        {
             res.net_port_type = NetPortType::parse(parser);
         }
 
@@ -82,8 +321,11 @@ impl Parse for Port {
                 while parser.probe(&[Token::LBracket]) {
                     if let Some(dimension) = UnpackedDimension::parse(parser) {
                         res.dimensions.push(dimension);
+                    } else {
+                        break;
                     }
                 }
+                res.span = parser.span_from(first);
                 return Some(res);
             }
         }
@@ -122,16 +364,56 @@ impl Parse for PortDirection {
 }
 
 /// net_type ::= supply0 | supply1 | tri | triand | trior | trireg | tri0 | tri1 | uwire | wire | wand | wor
-#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum NetType {
+    Supply0,
+    Supply1,
+    Tri,
+    TriAnd,
+    TriOr,
+    TriReg,
+    Tri0,
+    Tri1,
+    Uwire,
     Wire,
+    Wand,
+    Wor,
+}
+
+impl NetType {
+    /// Tokens of the net types
+    pub(crate) const FIRST: &'static [Token] = &[
+        Token::Supply0,
+        Token::Supply1,
+        Token::Tri,
+        Token::TriAnd,
+        Token::TriOr,
+        Token::TriReg,
+        Token::Tri0,
+        Token::Tri1,
+        Token::Uwire,
+        Token::Wire,
+        Token::Wand,
+        Token::Wor,
+    ];
 }
 
 impl Parse for NetType {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         if let Some(token) = parser.peek() {
             let res = match token.token {
+                Token::Supply0 => Some(NetType::Supply0),
+                Token::Supply1 => Some(NetType::Supply1),
+                Token::Tri => Some(NetType::Tri),
+                Token::TriAnd => Some(NetType::TriAnd),
+                Token::TriOr => Some(NetType::TriOr),
+                Token::TriReg => Some(NetType::TriReg),
+                Token::Tri0 => Some(NetType::Tri0),
+                Token::Tri1 => Some(NetType::Tri1),
+                Token::Uwire => Some(NetType::Uwire),
                 Token::Wire => Some(NetType::Wire),
+                Token::Wand => Some(NetType::Wand),
+                Token::Wor => Some(NetType::Wor),
                 _ => None,
             };
             if res.is_some() {
@@ -178,6 +460,22 @@ impl Parse for PortDeclaration {
     }
 }
 
+impl Spanned for PortDeclaration {
+    fn node_span(&self) -> Option<NodeSpan> {
+        let (attributes, decl) = match self {
+            PortDeclaration::InOut(attributes, decl) => (attributes, decl.span),
+            PortDeclaration::Input(attributes, decl) => (attributes, decl.span),
+            PortDeclaration::Output(attributes, decl) => (attributes, decl.span),
+        };
+        Some(match attributes.span {
+            Some(span) => span.join(decl),
+            None => decl,
+        })
+    }
+}
+
+spanned!(ParameterPortList, ListOfPorts, PortExpression, Port);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,5 +486,20 @@ mod tests {
         let m = Ports::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().ports.len(), 2);
         assert_eq!(m.as_ref().unwrap().ports[0].1.direction, None);
+        assert_eq!(
+            m.as_ref().unwrap().span,
+            Some(NodeSpan { first: 0, last: 17 })
+        );
+        assert_eq!(
+            m.as_ref().unwrap().ports[1].1.span,
+            NodeSpan { first: 9, last: 16 }
+        );
+
+        let mut parser = Parser::from("(* keep *) input [1:0] a, b");
+        let m = PortDeclaration::parse(&mut parser);
+        assert_eq!(
+            m.as_ref().unwrap().node_span(),
+            Some(NodeSpan { first: 0, last: 13 })
+        );
     }
 }
@@ -6,11 +6,13 @@ use crate::ast::*;
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct SourceText {
     pub modules: Vec<ModuleDeclaration>,
+    pub span: Option<NodeSpan>,
 }
 
 impl Parse for SourceText {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = SourceText::default();
+        let first = parser.start();
         while parser.avail() {
             if parser.probe(&[Token::Module, Token::MacroModule]) {
                 if let Some(module) = ModuleDeclaration::parse(parser) {
@@ -22,20 +24,25 @@ impl Parse for SourceText {
                 parser.advance();
             }
         }
+        res.span = parser.optional_span_from(first);
         Some(res)
     }
 }
 
-/// module_declaration ::= module_ansi_header { module_item } endmodule
+/// module_declaration ::= module_nonansi_header { module_item } endmodule
+/// | module_ansi_header { module_item } endmodule
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct ModuleDeclaration {
     pub header: ModuleHeader,
     pub items: Vec<ModuleItem>,
+    pub span: NodeSpan,
 }
 
 impl Parse for ModuleDeclaration {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = ModuleDeclaration::default();
+        let first = parser.start();
+        parser.clear_types();
         if let Some(header) = ModuleHeader::parse(parser) {
             res.header = header;
             // TODO: module_item
@@ -48,6 +55,9 @@ impl Parse for ModuleDeclaration {
             }
             if parser.probe_err(&[Token::EndModule]) {
                 parser.advance();
+                res.span = parser.span_from(first);
+                res.check_ports(parser);
+                res.name_generate_blocks(parser);
                 return Some(res);
             }
         }
@@ -55,33 +65,138 @@ impl Parse for ModuleDeclaration {
     }
 }
 
-/// module_ansi_header ::= { attribute_instance } module_keyword module_identifier [ list_of_port_declarations ] ;
+impl ModuleDeclaration {
+    /// Assign `genblk<n>` names to the unnamed generate blocks
+    fn name_generate_blocks(&mut self, parser: &Parser<'_>) {
+        let items = self.items.iter().filter_map(|item| match item {
+            ModuleItem::NonPort(item) => Some(item),
+            ModuleItem::Port(_) => None,
+        });
+        let mut declared = declared_names(items, parser);
+
+        let header = &self.header;
+        let mut identifiers: Vec<&Identifier> = header
+            .parameters
+            .iter()
+            .flat_map(|parameters| &parameters.declarations)
+            .flat_map(|declaration| &declaration.assignments)
+            .map(|assignment| &assignment.identifier)
+            .collect();
+        identifiers.extend(header.ports.ports.iter().map(|(_, port)| &port.identifier));
+        if let Some(list) = &header.list_of_ports {
+            identifiers.extend(list.ports.iter().filter_map(|port| port.name.as_ref()));
+            identifiers.extend(list.references().map(|reference| &reference.identifier));
+        }
+        declared.extend(
+            identifiers
+                .into_iter()
+                .map(|identifier| parser.get_token(identifier.token).text.to_string()),
+        );
+
+        let items = self.items.iter_mut().filter_map(|item| match item {
+            ModuleItem::NonPort(item) => Some(item),
+            ModuleItem::Port(_) => None,
+        });
+        name_generate_blocks(items, &declared, parser);
+    }
+
+    /// Diagnose ports of the header without a port declaration in the body,
+    /// port declarations of names not in the header and ports listed twice
+    fn check_ports(&self, parser: &mut Parser<'_>) {
+        let declared: Vec<Identifier> = self
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::Port(PortDeclaration::InOut(_, decl)) => Some(&decl.identifiers),
+                ModuleItem::Port(PortDeclaration::Input(_, decl)) => Some(&decl.identifiers),
+                ModuleItem::Port(PortDeclaration::Output(_, decl)) => Some(&decl.identifiers),
+                _ => None,
+            })
+            .flat_map(|identifiers| identifiers.ports.iter().map(|(id, _)| id.clone()))
+            .collect();
+        let listed: Vec<&PortReference> = match &self.header.list_of_ports {
+            Some(list) => list.references().collect(),
+            None => vec![],
+        };
+        let text = |parser: &Parser<'_>, identifier: &Identifier| {
+            parser.get_token(identifier.token).text.to_string()
+        };
+
+        let mut names: Vec<String> = vec![];
+        for identifier in self
+            .header
+            .list_of_ports
+            .iter()
+            .flat_map(|list| list.ports.iter())
+            .filter_map(|port| port.port_name())
+        {
+            let name = text(parser, identifier);
+            if names.contains(&name) {
+                let span = parser.span(NodeSpan::token(identifier.token));
+                parser.err(span.from, span.to, Message::DuplicatePort(name));
+            } else {
+                names.push(name);
+            }
+        }
+        for reference in &listed {
+            let name = text(parser, &reference.identifier);
+            if !declared.iter().any(|id| text(parser, id) == name) {
+                let span = parser.span(NodeSpan::token(reference.identifier.token));
+                parser.err(span.from, span.to, Message::MissingPortDirection(name));
+            }
+        }
+        for identifier in &declared {
+            let name = text(parser, identifier);
+            if !listed
+                .iter()
+                .any(|reference| text(parser, &reference.identifier) == name)
+            {
+                let span = parser.span(NodeSpan::token(identifier.token));
+                parser.err(span.from, span.to, Message::NotInPortList(name));
+            }
+        }
+    }
+}
+
+/// module_nonansi_header ::= { attribute_instance } module_keyword module_identifier [ parameter_port_list ] list_of_ports ;
+/// module_ansi_header ::= { attribute_instance } module_keyword module_identifier [ parameter_port_list ] [ list_of_port_declarations ] ;
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct ModuleHeader {
     pub attributes: Attributes,
     pub identifier: Identifier,
+    pub parameters: Option<ParameterPortList>,
     pub ports: Ports,
+    /// Ports of a non-ANSI header, their directions are declared in the body
+    pub list_of_ports: Option<ListOfPorts>,
+    pub span: NodeSpan,
 }
 
 impl Parse for ModuleHeader {
     fn parse(parser: &mut Parser<'_>) -> Option<Self> {
         let mut res = ModuleHeader::default();
+        let first = parser.start();
         if parser.probe(&[Token::LParen]) {
             if let Some(attrs) = Attributes::parse(parser) {
                 res.attributes = attrs;
             }
         }
-        if parser.probe_err(&[Token::Module]) {
+        if parser.probe_err(&[Token::Module, Token::MacroModule]) {
             parser.advance();
             let identifier = Identifier::parse(parser)?;
             res.identifier = identifier;
-            if parser.probe(&[Token::LParen]) {
+            if parser.probe(&[Token::Sharp]) {
+                res.parameters = Some(ParameterPortList::parse(parser)?);
+            }
+            if parser.probe(&[Token::LParen]) && ListOfPorts::is_list_of_ports(parser) {
+                res.list_of_ports = Some(ListOfPorts::parse(parser)?);
+            } else if parser.probe(&[Token::LParen]) {
                 if let Some(ports) = Ports::parse(parser) {
                     res.ports = ports;
                 }
             }
             if parser.probe_err(&[Token::Semicolon]) {
                 parser.advance();
+                res.span = parser.span_from(first);
                 return Some(res);
             }
         }
@@ -89,6 +204,14 @@ impl Parse for ModuleHeader {
     }
 }
 
+impl Spanned for SourceText {
+    fn node_span(&self) -> Option<NodeSpan> {
+        self.span
+    }
+}
+
+spanned!(ModuleDeclaration, ModuleHeader);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,11 +221,16 @@ mod tests {
         let mut parser = Parser::from("module test; endmodule");
         let m = ModuleDeclaration::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().header.identifier.token, 1);
+        assert_eq!(m.as_ref().unwrap().header.ports.span, None);
 
         let mut parser = Parser::from("module test(); endmodule");
         let m = ModuleDeclaration::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().header.identifier.token, 1);
         assert_eq!(m.as_ref().unwrap().header.ports.ports.len(), 0);
+        assert_eq!(
+            m.as_ref().unwrap().header.ports.span,
+            Some(NodeSpan { first: 2, last: 3 })
+        );
     }
 
     #[test]
@@ -135,7 +263,8 @@ mod tests {
                 .unwrap()
                 .data_type_or_implicit,
             DataTypeOrImplicit::Data(DataType {
-                integer_type: IntegerVectorType::Logic,
+                kind: DataTypeKind::IntegerVector(IntegerVectorType::Logic),
+                span: NodeSpan::token(3),
                 ..DataType::default()
             })
         );
@@ -160,11 +289,13 @@ mod tests {
                 .unwrap()
                 .data_type_or_implicit,
             DataTypeOrImplicit::Data(DataType {
-                integer_type: IntegerVectorType::Reg,
+                kind: DataTypeKind::IntegerVector(IntegerVectorType::Reg),
                 dimensions: vec![PackedDimension {
-                    from: Some(Number { token: 6 }),
-                    to: Some(Number { token: 8 })
+                    from: Some(Expression::Number(Number { token: 6 })),
+                    to: Some(Expression::Number(Number { token: 8 })),
+                    span: NodeSpan { first: 5, last: 9 },
                 }],
+                span: NodeSpan { first: 4, last: 9 },
                 ..DataType::default()
             })
         );
@@ -191,6 +322,212 @@ mod tests {
         assert_eq!(m.as_ref().unwrap().items.len(), 1);
     }
 
+    #[test]
+    fn module_span() {
+        let mut parser = Parser::from("// top\nmodule test(input a);\n  output b;\nendmodule");
+        let m = SourceText::parse(&mut parser).unwrap();
+        assert_eq!(m.span, Some(NodeSpan { first: 1, last: 11 }));
+        let module = &m.modules[0];
+        assert_eq!(module.span, NodeSpan { first: 1, last: 11 });
+        assert_eq!(module.header.span, NodeSpan { first: 1, last: 7 });
+        assert_eq!(
+            module.items[0].node_span(),
+            Some(NodeSpan { first: 8, last: 9 })
+        );
+
+        let span = parser.span(module.header.span);
+        assert_eq!((span.from.row, span.from.col), (1, 0));
+        assert_eq!((span.to.row, span.to.col), (1, 20));
+        let span = parser.span(module.span);
+        assert_eq!((span.to.row, span.to.col), (3, 8));
+    }
+
+    #[test]
+    fn non_ansi_ports() {
+        let mut parser = Parser::from(
+            "module m(a, .b(x[3:0]), {c, d[1]}, , .e());
+               input a;
+               output [3:0] x;
+               inout c, d;
+             endmodule",
+        );
+        let m = ModuleDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(parser.get_diag().len(), 0, "{:?}", parser.get_diag());
+        let list = m.header.list_of_ports.as_ref().unwrap();
+        assert_eq!(list.ports.len(), 5);
+        assert_eq!(list.ports[1].name, Some(Identifier { token: 6 }));
+        assert!(matches!(
+            list.ports[1].expression.as_ref().unwrap().references[0].expression,
+            Expression::Select(_)
+        ));
+        let concatenation = list.ports[2].expression.as_ref().unwrap();
+        assert!(concatenation.concatenation);
+        assert_eq!(concatenation.references.len(), 2);
+        assert_eq!(list.ports[3], NonAnsiPort::default());
+        assert_eq!(list.ports[4].expression, None);
+        assert_eq!(list.references().count(), 4);
+        assert_eq!(m.header.ports.ports.len(), 0);
+
+        let mut parser = Parser::from("module m(a, b); input a; output c; endmodule");
+        ModuleDeclaration::parse(&mut parser).unwrap();
+        let msgs: Vec<Message> = parser.get_diag().iter().map(|d| d.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                Message::MissingPortDirection("b".to_owned()),
+                Message::NotInPortList("c".to_owned()),
+            ]
+        );
+        assert_eq!(parser.get_diag()[0].pos.from.col, 12);
+
+        let mut parser =
+            Parser::from("module m(a, .b(a), {a}, a[0], .b(c)); input a, c; endmodule");
+        ModuleDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(parser.get_diag().len(), 1, "{:?}", parser.get_diag());
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::DuplicatePort("b".to_owned())
+        );
+        assert_eq!(parser.get_diag()[0].pos.from.col, 31);
+
+        let mut parser = Parser::from("module m(a, a); input a; endmodule");
+        ModuleDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(parser.get_diag().len(), 1);
+        assert_eq!(
+            parser.get_diag()[0].msg,
+            Message::DuplicatePort("a".to_owned())
+        );
+        assert_eq!(
+            parser.get_diag()[0].msg.to_string(),
+            "Port a appears more than once in the port list"
+        );
+    }
+
+    #[test]
+    fn subroutines() {
+        let mut parser = Parser::from(
+            r#"module tb;
+                 import "DPI-C" function void c_log(input bit [7:0] level);
+                 export "DPI-C" task run;
+                 task run; c_log(0); endtask
+                 function bit [7:0] double(bit [7:0] x); return x * 2; endfunction
+               endmodule"#,
+        );
+        let m = ModuleDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(parser.get_diag().len(), 0, "{:?}", parser.get_diag());
+        assert_eq!(m.items.len(), 4);
+        assert!(matches!(
+            m.items[0],
+            ModuleItem::NonPort(NonPortModuleItem::Dpi(_))
+        ));
+        assert!(matches!(
+            m.items[2],
+            ModuleItem::NonPort(NonPortModuleItem::Task(_))
+        ));
+        assert!(matches!(
+            m.items[3],
+            ModuleItem::NonPort(NonPortModuleItem::Function(_))
+        ));
+    }
+
+    #[test]
+    fn empty_port_list() {
+        for text in [
+            "module m(); endmodule",
+            "module m #(parameter W = 8) (); endmodule",
+            "module m ( /* none */ ); endmodule",
+        ] {
+            let mut parser = Parser::from(text);
+            let m = ModuleDeclaration::parse(&mut parser).unwrap();
+            assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+            assert_eq!(m.header.ports.ports.len(), 0);
+            assert!(m.header.ports.span.is_some());
+            assert_eq!(m.header.list_of_ports, None);
+        }
+    }
+
+    #[test]
+    fn data_declarations() {
+        let mut parser = Parser::from(
+            "module types;
+               typedef enum { IDLE, BUSY } state_t;
+               int count = 0;
+               pkg::word_t data;
+               state_t state;
+               task step; typedef struct packed { bit a; } flags_t; flags_t f; endtask
+             endmodule",
+        );
+        let m = ModuleDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(parser.get_diag().len(), 0, "{:?}", parser.get_diag());
+        assert_eq!(m.items.len(), 5);
+        assert!(matches!(
+            m.items[0],
+            ModuleItem::NonPort(NonPortModuleItem::Type(_))
+        ));
+        assert!(matches!(
+            m.items[1],
+            ModuleItem::NonPort(NonPortModuleItem::Data(_))
+        ));
+        assert!(matches!(
+            m.items[2],
+            ModuleItem::NonPort(NonPortModuleItem::Data(_))
+        ));
+        // `state_t` is declared by the typedef above
+        assert!(matches!(
+            m.items[3],
+            ModuleItem::NonPort(NonPortModuleItem::Data(_))
+        ));
+        match &m.items[4] {
+            ModuleItem::NonPort(NonPortModuleItem::Task(task)) => {
+                assert!(matches!(
+                    task.items[0],
+                    TfItemDeclaration::Block(BlockItemDeclaration::Type(..))
+                ));
+                assert!(matches!(
+                    task.items[1],
+                    TfItemDeclaration::Block(BlockItemDeclaration::Data(..))
+                ));
+            }
+            item => panic!("{:?}", item),
+        }
+
+        // net type names are not known outside of the module, other names
+        // are taken to be data types
+        let mut parser = Parser::from(
+            "module a; nettype logic t; t x; endmodule
+             module b; t y; endmodule",
+        );
+        let source = SourceText::parse(&mut parser).unwrap();
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        assert!(matches!(
+            source.modules[0].items[1],
+            ModuleItem::NonPort(NonPortModuleItem::Net(_))
+        ));
+        assert!(matches!(
+            source.modules[1].items[0],
+            ModuleItem::NonPort(NonPortModuleItem::Data(_))
+        ));
+
+        let mut parser = Parser::from(
+            "module c #(parameter type T = int) ();
+               foo_t x;
+               T [3:0] y;
+               cls#(8)::t z;
+               int q[$], d[];
+             endmodule",
+        );
+        let m = ModuleDeclaration::parse(&mut parser).unwrap();
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        assert_eq!(m.items.len(), 4);
+        for item in &m.items {
+            assert!(
+                matches!(item, ModuleItem::NonPort(NonPortModuleItem::Data(_))),
+                "{:?}",
+                item
+            );
+        }
+    }
+
     #[test]
     fn source_text_comments() {
         let mut parser = Parser::from("// some comment");
@@ -203,6 +540,12 @@ mod tests {
         let mut parser = Parser::from("module a;endmodule module b;endmodule");
         let m = SourceText::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().modules.len(), 2);
+
+        let mut parser = Parser::from("macromodule a(input x);endmodule module b;endmodule");
+        let m = SourceText::parse(&mut parser).unwrap();
+        assert!(parser.get_diag().is_empty(), "{:?}", parser.get_diag());
+        assert_eq!(m.modules.len(), 2);
+        assert_eq!(m.modules[0].header.ports.ports.len(), 1);
     }
 
     #[test]
@@ -243,6 +586,88 @@ endmodule "#,
         );
         let m = SourceText::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().modules.len(), 1);
+        // escaped identifiers end at white space, `\Add0~6_combout;` takes
+        // the semicolon and the declaration runs into the instance, `16habab`
+        // is a number followed by an identifier
+        let items = &m.as_ref().unwrap().modules[0].items;
+        assert_eq!(items.len(), 12);
+        match &items[11] {
+            ModuleItem::NonPort(NonPortModuleItem::Defparam(defparam)) => {
+                assert!(matches!(
+                    defparam.assignments[0].parameter,
+                    Expression::Member(..)
+                ))
+            }
+            item => panic!("{:?}", item),
+        }
+        let msgs: Vec<Message> = parser.get_diag().iter().map(|d| d.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                Message::UnexpectedTokens(vec![Token::Semicolon], "\\Add0~6".to_owned()),
+                Message::UnexpectedTokens(vec![Token::Semicolon], "habab".to_owned()),
+                Message::DuplicatePort("signal1".to_owned()),
+                Message::NotInPortList("signal2".to_owned()),
+            ]
+        );
+        assert_eq!(parser.get_diag()[0].pos.from.row, 21);
+        assert_eq!(parser.get_diag()[1].pos.from.row, 29);
+    }
+
+    #[test]
+    fn module_parameters() {
+        let mut parser = Parser::from(
+            "module m #(parameter W = 8, D = 2, type T = logic, localparam [W-1:0] M = '1)
+             (input [W-1:0] a);
+               parameter signed [3:0] P = 4'sd3, Q = P;
+               localparam logic [1:0] C [2] = '{1, 2};
+               parameter type U = T;
+             endmodule",
+        );
+        let m = ModuleDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(parser.get_diag().len(), 0, "{:?}", parser.get_diag());
+        let parameters = m.header.parameters.as_ref().unwrap();
+        assert_eq!(parameters.declarations.len(), 3);
+        assert_eq!(
+            parameters.declarations[0].keyword,
+            Some(ParameterKeyword::Parameter)
+        );
+        assert_eq!(parameters.declarations[0].assignments.len(), 2);
+        assert_eq!(parameters.declarations[1].keyword, None);
+        assert_eq!(
+            parameters.declarations[1].parameter_type,
+            ParameterType::Type
+        );
+        assert!(matches!(
+            parameters.declarations[1].assignments[0].value,
+            Some(ParamExpression::DataType(_))
+        ));
+        assert_eq!(
+            parameters.declarations[2].keyword,
+            Some(ParameterKeyword::Localparam)
+        );
+        assert_eq!(m.header.ports.ports.len(), 1);
+
+        assert_eq!(m.items.len(), 3);
+        let declarations: Vec<&ParameterDeclaration> = m
+            .items
+            .iter()
+            .map(|item| match item {
+                ModuleItem::NonPort(NonPortModuleItem::Parameter(declaration)) => declaration,
+                item => panic!("{:?}", item),
+            })
+            .collect();
+        assert_eq!(declarations[0].assignments.len(), 2);
+        assert!(matches!(
+            declarations[0].parameter_type,
+            ParameterType::Data(DataTypeOrImplicit::ImplicitData(_))
+        ));
+        assert_eq!(declarations[1].assignments[0].dimensions.len(), 1);
+        assert_eq!(declarations[2].parameter_type, ParameterType::Type);
+
+        let mut parser = Parser::from("module m #(); endmodule");
+        let m = ModuleDeclaration::parse(&mut parser).unwrap();
+        assert_eq!(m.header.parameters.unwrap().declarations.len(), 0);
         assert_eq!(parser.get_diag().len(), 0);
     }
 
@@ -260,7 +685,15 @@ endmodule "#,
         );
         let m = SourceText::parse(&mut parser);
         assert_eq!(m.as_ref().unwrap().modules.len(), 1);
-        assert_eq!(parser.get_diag().len(), 0);
+        assert_eq!(m.as_ref().unwrap().modules[0].items.len(), 1);
+        let msgs: Vec<Message> = parser.get_diag().iter().map(|d| d.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                Message::MissingPortDirection("signal1".to_owned()),
+                Message::MissingPortDirection("signal2".to_owned()),
+            ]
+        );
     }
 
     #[test]
@@ -0,0 +1,277 @@
+//! Compilation of multiple files
+
+use crate::ast::{ModuleInstantiation, ModuleItem, NodeSpan, NonPortModuleItem, SourceText};
+use crate::diagnostic::{Diagnostic, Message, Severity};
+use crate::filelist::{Filelist, FilelistPath};
+use crate::lexer::{Lexer, LexerOptions};
+use crate::parser::Parser;
+use crate::preprocessor::{Preprocessor, PreprocessorOptions};
+use crate::source::{FileId, SourceManager};
+use crate::syntax_tree::SyntaxTree;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A parsed source file
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CompilationUnit {
+    pub file: FileId,
+    /// Whether the file is a library file given by `-v` or found in a `-y`
+    /// directory
+    pub library: bool,
+    pub tree: SyntaxTree,
+}
+
+/// All files of a design
+///
+/// Files are preprocessed in order, so macros defined in one file are
+/// visible in the following files.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Compilation {
+    pub sources: SourceManager,
+    pub filelist: Filelist,
+    pub units: Vec<CompilationUnit>,
+    /// Diagnostics that do not belong to a file, like missing files
+    pub diag: Vec<Diagnostic>,
+}
+
+impl Compilation {
+    pub fn from_filelist(path: impl AsRef<Path>) -> Compilation {
+        let mut sources = SourceManager::new();
+        let filelist = Filelist::load(path, &mut sources);
+        Self::new(filelist, sources)
+    }
+
+    pub fn new(mut filelist: Filelist, sources: SourceManager) -> Compilation {
+        let mut res = Compilation {
+            sources,
+            filelist: Filelist::default(),
+            units: vec![],
+            diag: std::mem::take(&mut filelist.diag),
+        };
+        let mut options = PreprocessorOptions {
+            incdirs: filelist.incdirs.clone(),
+            ..PreprocessorOptions::default()
+        };
+        for define in &filelist.defines {
+            options.define(define);
+        }
+
+        let files = filelist.files.iter().map(|path| (path, false));
+        let library_files = filelist.library_files.iter().map(|path| (path, true));
+        for (path, library) in files.chain(library_files) {
+            res.compile(path, library, &mut options);
+        }
+        res.resolve_modules(&filelist, &mut options);
+        res.filelist = filelist;
+        res
+    }
+
+    fn compile(&mut self, path: &FilelistPath, library: bool, options: &mut PreprocessorOptions) {
+        let file = match self.sources.load(&path.path) {
+            Ok(file) => file,
+            Err(err) => {
+                self.diag.push(Diagnostic {
+                    pos: path.span,
+                    msg: Message::CannotReadFile(path.path.display().to_string(), err.to_string()),
+                    severity: Severity::Error,
+                    notes: vec![],
+                });
+                return;
+            }
+        };
+
+        let text = self.sources.text(file);
+        let lexer = Lexer::lex_with_options(
+            &text,
+            LexerOptions {
+                file,
+                ..LexerOptions::default()
+            },
+        );
+        let pp = Preprocessor::preprocess_with_sources(lexer, options, &mut self.sources);
+        options.macros = pp
+            .macros
+            .iter()
+            .map(|(name, def)| (name.clone(), def.clone().into_owned()))
+            .collect();
+        let parser = Parser::from_preprocessor(pp);
+        let tree = SyntaxTree::from_parser(text.clone(), parser);
+        self.units.push(CompilationUnit {
+            file,
+            library,
+            tree,
+        });
+    }
+
+    /// Load the library directory files of instantiated modules that are not
+    /// defined by any file, and report those found nowhere
+    fn resolve_modules(&mut self, filelist: &Filelist, options: &mut PreprocessorOptions) {
+        let mut searched = HashSet::new();
+        loop {
+            let defined: HashSet<&str> = self
+                .units
+                .iter()
+                .flat_map(|unit| {
+                    unit.tree
+                        .root()
+                        .modules
+                        .iter()
+                        .map(|module| unit.tree.text_of(&module.header.identifier))
+                })
+                .collect();
+            let mut missing = vec![];
+            for unit in &self.units {
+                for module in &unit.tree.root().modules {
+                    let items = module.items.iter().filter_map(|item| match item {
+                        ModuleItem::NonPort(item) => Some(item),
+                        ModuleItem::Port(_) => None,
+                    });
+                    for instantiation in instantiations(items) {
+                        let name = unit.tree.text_of(&instantiation.module);
+                        if !defined.contains(name) {
+                            let span = unit
+                                .tree
+                                .span_of(NodeSpan::token(instantiation.module.token));
+                            missing.push((name.to_owned(), span));
+                        }
+                    }
+                }
+            }
+
+            let mut loaded = vec![];
+            for (name, span) in &missing {
+                if !searched.insert(name.clone()) {
+                    continue;
+                }
+                match filelist.find_library_file(name) {
+                    Some(path) => loaded.push(FilelistPath { path, span: *span }),
+                    None => self.diag.push(Diagnostic {
+                        pos: *span,
+                        msg: Message::UndefinedModule(name.clone()),
+                        severity: Severity::Error,
+                        notes: vec![],
+                    }),
+                }
+            }
+            if loaded.is_empty() {
+                return;
+            }
+            for path in &loaded {
+                self.compile(path, true, options);
+            }
+        }
+    }
+
+    /// Parsed files that are not library files
+    pub fn source_texts(&self) -> impl Iterator<Item = &SourceText> {
+        self.units
+            .iter()
+            .filter(|unit| !unit.library)
+            .map(|unit| unit.tree.root())
+    }
+
+    /// All diagnostics of the filelist and the files
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diag
+            .iter()
+            .chain(self.units.iter().flat_map(|unit| unit.tree.diagnostics()))
+    }
+}
+
+/// Module instantiations of module items, including those in generate
+/// constructs
+fn instantiations<'a>(
+    items: impl IntoIterator<Item = &'a NonPortModuleItem>,
+) -> Vec<&'a ModuleInstantiation> {
+    let mut res = vec![];
+    for item in items {
+        match item {
+            NonPortModuleItem::Instantiation(instantiation) => res.push(instantiation),
+            NonPortModuleItem::GenerateRegion(region) => res.extend(instantiations(&region.items)),
+            NonPortModuleItem::LoopGenerate(construct) => {
+                res.extend(instantiations(&construct.block.items))
+            }
+            NonPortModuleItem::IfGenerate(construct) => {
+                res.extend(instantiations(&construct.block.items));
+                if let Some(block) = &construct.else_block {
+                    res.extend(instantiations(&block.items));
+                }
+            }
+            NonPortModuleItem::CaseGenerate(construct) => {
+                for item in &construct.items {
+                    res.extend(instantiations(&item.block.items));
+                }
+            }
+            _ => {}
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filelist() {
+        let compilation = Compilation::from_filelist("testcase/filelist/top.f");
+        let files: Vec<&Path> = compilation
+            .units
+            .iter()
+            .map(|unit| compilation.sources.path(unit.file))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                Path::new("testcase/filelist/rtl/adder.v"),
+                Path::new("testcase/filelist/rtl/top.v"),
+                Path::new("testcase/filelist/lib/cells.v"),
+            ]
+        );
+        assert!(compilation.units[2].library);
+        assert_eq!(compilation.source_texts().count(), 2);
+        let top = &compilation.units[1].tree;
+        assert_eq!(top.root().modules.len(), 1);
+        assert_eq!(top.text_of(&top.root().modules[0].header.identifier), "top");
+
+        let diag: Vec<String> = compilation
+            .diagnostics()
+            .map(|diag| compilation.sources.render(diag))
+            .collect();
+        assert_eq!(diag.len(), 2, "{:?}", diag);
+        assert!(diag[0]
+            .starts_with("testcase/filelist/top.f:3:0: Warning: Unknown filelist option: -bogus"));
+        assert!(diag[1].starts_with(
+            "testcase/filelist/top.f:5:0: Error: Cannot read file testcase/filelist/rtl/missing.v"
+        ));
+    }
+
+    #[test]
+    fn library_directory() {
+        let compilation = Compilation::from_filelist("testcase/filelist/libdir.f");
+        let files: Vec<&Path> = compilation
+            .units
+            .iter()
+            .map(|unit| compilation.sources.path(unit.file))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                Path::new("testcase/filelist/rtl/chip.v"),
+                Path::new("testcase/filelist/ydir/cell_a.v"),
+                Path::new("testcase/filelist/ydir/cell_b.v"),
+            ]
+        );
+        assert!(compilation.units[1].library && compilation.units[2].library);
+        assert_eq!(compilation.source_texts().count(), 1);
+
+        let diag: Vec<String> = compilation
+            .diagnostics()
+            .map(|diag| compilation.sources.render(diag))
+            .collect();
+        assert_eq!(diag.len(), 1, "{:?}", diag);
+        assert!(diag[0].starts_with(
+            "testcase/filelist/rtl/chip.v:4:4: Error: Module is not defined: missing_cell"
+        ));
+    }
+}
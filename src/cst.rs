@@ -0,0 +1,828 @@
+//! Lossless concrete syntax tree
+//!
+//! Every byte of the input belongs to a token or to trivia (whitespace,
+//! comments and characters the lexer skipped), and trivia is attached to the
+//! following token, so printing the tree gives back the input. Tokens are
+//! grouped into the nodes of the AST the parser builds, directives get a
+//! node of their own.
+
+use crate::ast::{NodeSpan, Parse, SourceText};
+use crate::lexer::{Lexer, ParsedToken, Span, Token};
+use crate::parser::Parser;
+use crate::preprocessor::{Preprocessor, PreprocessorOptions};
+use crate::source::FileId;
+use serde::{ser, Deserialize, Serialize};
+use std::fmt;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum TriviaKind {
+    Whitespace,
+    Comment,
+    /// Characters rejected by the lexer
+    Skipped,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct CstToken {
+    pub leading: Vec<Trivia>,
+    pub token: Token,
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum CstElement {
+    Node(CstNode),
+    Token(CstToken),
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct CstNode {
+    /// Name of the AST type, `SourceText` for the root or `Directive`
+    pub kind: String,
+    pub children: Vec<CstElement>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct Cst {
+    pub root: CstNode,
+    /// Trivia after the last token
+    pub trailing: Vec<Trivia>,
+}
+
+/// Split text between tokens into trivia
+fn gap_trivia(text: &str, trivia: &mut Vec<Trivia>) {
+    let mut start = 0;
+    let mut kind = None;
+    for (index, ch) in text.char_indices() {
+        // a backslash before a newline continues a line, like whitespace
+        let next_kind = if ch.is_whitespace() || (ch == '\\' && is_continuation(&text[index..])) {
+            TriviaKind::Whitespace
+        } else {
+            TriviaKind::Skipped
+        };
+        if kind != Some(next_kind) {
+            if let Some(kind) = kind {
+                trivia.push(Trivia {
+                    kind,
+                    text: text[start..index].to_owned(),
+                });
+            }
+            start = index;
+            kind = Some(next_kind);
+        }
+    }
+    if let Some(kind) = kind {
+        trivia.push(Trivia {
+            kind,
+            text: text[start..].to_owned(),
+        });
+    }
+}
+
+fn is_continuation(text: &str) -> bool {
+    text.starts_with("\\\n") || text.starts_with("\\\r")
+}
+
+/// Node of the AST with its token span
+struct AstNode {
+    kind: &'static str,
+    span: Option<NodeSpan>,
+}
+
+/// Serializer that visits the AST and records its nodes
+///
+/// Every AST struct with a `span` field is a grammar node, so serializing
+/// the tree finds all nodes without a visitor for each type.
+struct Collector<'n> {
+    nodes: &'n mut Vec<AstNode>,
+    /// Integers of the visited value, used to read a `NodeSpan`
+    numbers: &'n mut Vec<usize>,
+}
+
+/// Fields and elements of a value visited by a `Collector`
+struct Compound<'n> {
+    nodes: &'n mut Vec<AstNode>,
+    numbers: &'n mut Vec<usize>,
+    /// Node of the struct whose fields are visited
+    node: Option<usize>,
+}
+
+impl<'n> Compound<'n> {
+    fn visit<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), fmt::Error> {
+        value.serialize(Collector {
+            nodes: self.nodes,
+            numbers: self.numbers,
+        })
+    }
+}
+
+impl<'n> Collector<'n> {
+    fn compound(self, node: Option<usize>) -> Compound<'n> {
+        Compound {
+            nodes: self.nodes,
+            numbers: self.numbers,
+            node,
+        }
+    }
+}
+
+impl<'n> ser::Serializer for Collector<'n> {
+    type Ok = ();
+    type Error = fmt::Error;
+    type SerializeSeq = Compound<'n>;
+    type SerializeTuple = Compound<'n>;
+    type SerializeTupleStruct = Compound<'n>;
+    type SerializeTupleVariant = Compound<'n>;
+    type SerializeMap = Compound<'n>;
+    type SerializeStruct = Compound<'n>;
+    type SerializeStructVariant = Compound<'n>;
+
+    fn serialize_bool(self, _: bool) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_i8(self, _: i8) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_i16(self, _: i16) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_i32(self, _: i32) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_i64(self, _: i64) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_u8(self, _: u8) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_u16(self, _: u16) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_u32(self, _: u32) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_u64(self, value: u64) -> Result<(), fmt::Error> {
+        self.numbers.push(value as usize);
+        Ok(())
+    }
+    fn serialize_f32(self, _: f32) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_f64(self, _: f64) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_char(self, _: char) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_str(self, _: &str) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_none(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), fmt::Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), fmt::Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), fmt::Error> {
+        value.serialize(self)
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Compound<'n>, fmt::Error> {
+        Ok(self.compound(None))
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Compound<'n>, fmt::Error> {
+        Ok(self.compound(None))
+    }
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Compound<'n>, fmt::Error> {
+        Ok(self.compound(None))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'n>, fmt::Error> {
+        Ok(self.compound(None))
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Compound<'n>, fmt::Error> {
+        Ok(self.compound(None))
+    }
+    fn serialize_struct(self, name: &'static str, _: usize) -> Result<Compound<'n>, fmt::Error> {
+        self.nodes.push(AstNode {
+            kind: name,
+            span: None,
+        });
+        let node = self.nodes.len() - 1;
+        Ok(self.compound(Some(node)))
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'n>, fmt::Error> {
+        Ok(self.compound(None))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = fmt::Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), fmt::Error> {
+        self.visit(value)
+    }
+    fn end(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = fmt::Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), fmt::Error> {
+        self.visit(value)
+    }
+    fn end(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = fmt::Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), fmt::Error> {
+        self.visit(value)
+    }
+    fn end(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = fmt::Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), fmt::Error> {
+        self.visit(value)
+    }
+    fn end(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = fmt::Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), fmt::Error> {
+        self.visit(key)
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), fmt::Error> {
+        self.visit(value)
+    }
+    fn end(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = fmt::Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), fmt::Error> {
+        match self.node {
+            Some(node) if key == "span" => {
+                // a `NodeSpan` or an `Option<NodeSpan>`
+                let mut numbers = vec![];
+                value.serialize(Collector {
+                    nodes: &mut vec![],
+                    numbers: &mut numbers,
+                })?;
+                if let [first, last] = numbers[..] {
+                    self.nodes[node].span = Some(NodeSpan { first, last });
+                }
+                Ok(())
+            }
+            _ => self.visit(value),
+        }
+    }
+    fn end(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = fmt::Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), fmt::Error> {
+        self.visit(value)
+    }
+    fn end(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+}
+
+/// Byte range of an AST node in the input
+struct Range {
+    kind: &'static str,
+    start: usize,
+    end: usize,
+}
+
+/// Byte ranges of the AST nodes below `root`, outer nodes first
+///
+/// Only tokens of the input count, tokens of included files and macro
+/// expansions have no place in it.
+fn node_ranges(root: &SourceText, parsed: &[ParsedToken<'_>], file: FileId) -> Vec<Range> {
+    let mut nodes = vec![];
+    root.serialize(Collector {
+        nodes: &mut nodes,
+        numbers: &mut vec![],
+    })
+    .expect("visit the AST");
+    let mut ranges = vec![];
+    // the first node is the root itself
+    for node in nodes.iter().skip(1) {
+        let span = match node.span {
+            Some(span) if span.last < parsed.len() => span,
+            _ => continue,
+        };
+        let mut tokens = parsed[span.first..=span.last]
+            .iter()
+            .filter(|token| token.span.file == file && token.expansion.is_none());
+        if let Some(first) = tokens.next() {
+            let last = tokens.next_back().unwrap_or(first);
+            ranges.push(Range {
+                kind: node.kind,
+                start: first.span.from.offset,
+                end: last.span.from.offset + last.text.len(),
+            });
+        }
+    }
+    // stable, so nodes with the same range keep the outer node first
+    ranges.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+    ranges
+}
+
+/// Directives whose arguments are the tokens up to the end of the line
+const LINE_DIRECTIVES: &[&str] = &[
+    "`begin_keywords",
+    "`default_nettype",
+    "`elsif",
+    "`ifdef",
+    "`ifndef",
+    "`include",
+    "`line",
+    "`pragma",
+    "`timescale",
+    "`unconnected_drive",
+    "`undef",
+];
+
+/// Open node and where it ends
+struct Frame {
+    node: CstNode,
+    /// End of the byte range of an AST node
+    end: usize,
+    /// Row of a directive that takes the rest of the line
+    line: Option<usize>,
+}
+
+struct Builder {
+    stack: Vec<Frame>,
+}
+
+impl Builder {
+    fn top(&mut self) -> &mut Frame {
+        self.stack.last_mut().unwrap()
+    }
+
+    fn open(&mut self, kind: &str, end: usize, line: Option<usize>) {
+        self.stack.push(Frame {
+            node: CstNode {
+                kind: kind.to_owned(),
+                children: vec![],
+            },
+            end,
+            line,
+        });
+    }
+
+    fn close(&mut self) {
+        let frame = self.stack.pop().unwrap();
+        self.top().node.children.push(CstElement::Node(frame.node));
+    }
+
+    fn push(&mut self, token: CstToken) {
+        self.top().node.children.push(CstElement::Token(token));
+    }
+
+    /// Add a token, `ranges` are the AST nodes starting at it
+    fn token(&mut self, token: CstToken, ranges: &[Range]) {
+        let start = token.span.from.offset;
+        loop {
+            let frame = self.stack.last().unwrap();
+            let closes = match frame.line {
+                Some(row) => {
+                    token.span.from.row != row
+                        || token.token == Token::Directive
+                        || !ranges.is_empty()
+                }
+                None => frame.end <= start,
+            };
+            if !closes || self.stack.len() == 1 {
+                break;
+            }
+            self.close();
+        }
+
+        for range in ranges {
+            // AST nodes nest, except when macros mix up their tokens
+            if range.end <= self.stack.last().unwrap().end {
+                self.open(range.kind, range.end, None);
+            }
+        }
+
+        match token.token {
+            Token::Directive => {
+                if LINE_DIRECTIVES.contains(&token.text.as_str()) {
+                    self.open("Directive", usize::MAX, Some(token.span.to.row));
+                    self.push(token);
+                } else {
+                    self.open("Directive", usize::MAX, None);
+                    self.push(token);
+                    self.close();
+                }
+            }
+            Token::MacroText => {
+                // belongs to the preceding `define
+                if let Some(CstElement::Node(node)) = self.top().node.children.last_mut() {
+                    if node.kind == "Directive" {
+                        node.children.push(CstElement::Token(token));
+                        return;
+                    }
+                }
+                self.push(token);
+            }
+            _ => self.push(token),
+        }
+    }
+
+    fn finish(mut self) -> CstNode {
+        while self.stack.len() > 1 {
+            self.close();
+        }
+        self.stack.pop().unwrap().node
+    }
+}
+
+impl Cst {
+    /// Lex, preprocess and parse `input` and build its tree
+    pub fn parse(input: &str) -> Cst {
+        let lexer = Lexer::lex(input);
+        let tokens = lexer.tokens.clone();
+        let pp = Preprocessor::preprocess(lexer, &PreprocessorOptions::default());
+        let mut parser = Parser::from_preprocessor(pp);
+        let root = SourceText::parse(&mut parser).unwrap_or_default();
+        let (parsed, _) = parser.finish();
+        Self::from_tokens(input, &tokens, &root, &parsed)
+    }
+
+    /// Build the tree from tokens of the lexer, before preprocessing, and
+    /// the AST `root` the parser built from the tokens `parsed`
+    ///
+    /// Every token of the lexer is in the tree once, the tokens of an AST
+    /// node are grouped in a node named after its type.
+    pub fn from_tokens(
+        input: &str,
+        tokens: &[ParsedToken<'_>],
+        root: &SourceText,
+        parsed: &[ParsedToken<'_>],
+    ) -> Cst {
+        let file = tokens
+            .first()
+            .map(|token| token.span.file)
+            .unwrap_or_default();
+        let ranges = node_ranges(root, parsed, file);
+        let mut next_range = 0;
+        let mut builder = Builder { stack: vec![] };
+        builder.open("SourceText", usize::MAX, None);
+        let mut leading = vec![];
+        let mut pos = 0;
+        for token in tokens {
+            let start = token.span.from.offset;
+            let end = start + token.text.len();
+            assert!(
+                start >= pos && input.get(start..end) == Some(token.text.as_ref()),
+                "token {:?} is not at its offset in the input",
+                token
+            );
+            gap_trivia(&input[pos..start], &mut leading);
+            pos = end;
+            if token.token == Token::Comment {
+                leading.push(Trivia {
+                    kind: TriviaKind::Comment,
+                    text: token.text.to_string(),
+                });
+                continue;
+            }
+            // skip nodes starting in comments or trivia
+            while next_range < ranges.len() && ranges[next_range].start < start {
+                next_range += 1;
+            }
+            let first_range = next_range;
+            while next_range < ranges.len() && ranges[next_range].start == start {
+                next_range += 1;
+            }
+            builder.token(
+                CstToken {
+                    leading: std::mem::take(&mut leading),
+                    token: token.token,
+                    text: token.text.to_string(),
+                    span: token.span,
+                },
+                &ranges[first_range..next_range],
+            );
+        }
+        gap_trivia(&input[pos..], &mut leading);
+        Cst {
+            root: builder.finish(),
+            trailing: leading,
+        }
+    }
+}
+impl CstNode {
+    /// Tokens of the node in source order
+    pub fn tokens(&self) -> Vec<&CstToken> {
+        let mut res = vec![];
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => res.extend(node.tokens()),
+                CstElement::Token(token) => res.push(token),
+            }
+        }
+        res
+    }
+}
+
+impl fmt::Display for Trivia {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl fmt::Display for CstToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        for trivia in &self.leading {
+            write!(f, "{}", trivia)?;
+        }
+        write!(f, "{}", self.text)
+    }
+}
+
+impl fmt::Display for CstNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => write!(f, "{}", node)?,
+                CstElement::Token(token) => write!(f, "{}", token)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "{}", self.root)?;
+        for trivia in &self.trailing {
+            write!(f, "{}", trivia)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kinds of the child nodes and texts of the child tokens
+    fn shape(node: &CstNode) -> Vec<String> {
+        node.children
+            .iter()
+            .map(|child| match child {
+                CstElement::Node(node) => node.kind.clone(),
+                CstElement::Token(token) => token.text.clone(),
+            })
+            .collect()
+    }
+
+    fn child(node: &CstNode, index: usize) -> &CstNode {
+        match &node.children[index] {
+            CstElement::Node(node) => node,
+            child => panic!("{:?}", child),
+        }
+    }
+
+    /// Check that printing gives back the input and that every token of
+    /// the lexer is in the tree once
+    fn assert_lossless(input: &str) {
+        let cst = Cst::parse(input);
+        assert_eq!(cst.to_string(), input);
+        let mut texts = vec![];
+        for token in cst.root.tokens() {
+            for trivia in &token.leading {
+                if trivia.kind == TriviaKind::Comment {
+                    texts.push((None, trivia.text.clone()));
+                }
+            }
+            texts.push((Some(token.span.from.offset), token.text.clone()));
+        }
+        for trivia in &cst.trailing {
+            if trivia.kind == TriviaKind::Comment {
+                texts.push((None, trivia.text.clone()));
+            }
+        }
+        let expected: Vec<(Option<usize>, String)> = Lexer::lex(input)
+            .tokens
+            .iter()
+            .map(|token| {
+                let offset = (token.token != Token::Comment).then_some(token.span.from.offset);
+                (offset, token.text.to_string())
+            })
+            .collect();
+        assert_eq!(texts, expected, "{:?}", input);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let inputs = [
+            "",
+            "  \n",
+            "module m; endmodule // trailing",
+            "/* header */ module m; endmodule",
+            "module m(input a, output b);\r\n  assign b = a; /* block\r\n comment */\r\nendmodule\r\n",
+            "`define FOO(x) \\\n  (x + 1) // comment\nwire [`FOO(1):0] w;\n",
+            "`define W 4\nmodule m; wire [`W-1:0] /* c */ w; endmodule\n",
+            "wire \u{e9}\u{548c} = \"str\u{e9}\"; \\esc@ped ;",
+            "/* unterminated",
+            "begin end end ) ] } fork",
+        ];
+        for input in inputs {
+            assert_lossless(input);
+        }
+
+        let entries = std::fs::read_dir("testcase/good").expect("list testcase/good");
+        for entry in entries {
+            let path = entry.unwrap().path();
+            let input = std::fs::read_to_string(&path).unwrap();
+            assert_lossless(&input);
+        }
+
+        // generated inputs from pieces of source text
+        let pieces = [
+            "module",
+            "m",
+            ";",
+            "endmodule",
+            "/* c */",
+            "// c\n",
+            " ",
+            "\n",
+            "(",
+            ")",
+            "wire",
+            "[1:0]",
+            "`define X 1\n",
+            "`X",
+            "assign",
+            "=",
+            "a",
+            "1'b0",
+            "\"s\"",
+            "begin",
+            "end",
+            "\\e ",
+            "\u{e9}",
+            "\r\n",
+            ",",
+            "input",
+        ];
+        let mut seed = 1u64;
+        for _ in 0..2000 {
+            let mut input = String::new();
+            for _ in 0..seed % 24 {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                input.push_str(pieces[(seed >> 33) as usize % pieces.len()]);
+            }
+            assert_lossless(&input);
+        }
+    }
+
+    #[test]
+    fn trivia() {
+        let cst = Cst::parse("a /* c */ \\\n b \u{a7} \n");
+        let tokens = cst.root.tokens();
+        assert_eq!(tokens.len(), 2);
+        let kinds: Vec<TriviaKind> = tokens[1].leading.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TriviaKind::Whitespace,
+                TriviaKind::Comment,
+                TriviaKind::Whitespace
+            ]
+        );
+        assert_eq!(tokens[1].leading[2].text, " \\\n ");
+        let kinds: Vec<TriviaKind> = cst.trailing.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TriviaKind::Whitespace,
+                TriviaKind::Skipped,
+                TriviaKind::Whitespace
+            ]
+        );
+    }
+
+    #[test]
+    fn nodes() {
+        let cst = Cst::parse(
+            "`timescale 1ns/1ps\nmodule m (input [1:0] a);\n  always @(a) begin\n    x = 1;\n  end\n  wire w;\n  import \"DPI-C\" function void f();\nendmodule",
+        );
+        assert_eq!(shape(&cst.root), vec!["Directive", "ModuleDeclaration"]);
+        let module = child(&cst.root, 1);
+        assert_eq!(
+            shape(module),
+            vec![
+                "ModuleHeader",
+                "AlwaysConstruct",
+                "NetDeclaration",
+                "DpiImportExport",
+                "endmodule"
+            ]
+        );
+        assert_eq!(shape(child(module, 0)), vec!["module", "m", "Ports", ";"]);
+        assert_eq!(shape(child(module, 1)), vec!["always", "Statement"]);
+        assert_eq!(
+            shape(child(module, 2)),
+            vec!["wire", "NetDeclAssignment", ";"]
+        );
+        assert_eq!(
+            shape(child(&cst.root, 0)),
+            vec!["`timescale", "1ns", "/", "1ps"]
+        );
+
+        // the tokens of a macro use stay in the node of the expansion
+        let cst = Cst::parse("`define W 4\nmodule m; wire [`W-1:0] w; endmodule");
+        assert_eq!(shape(&cst.root), vec!["Directive", "ModuleDeclaration"]);
+        let wire = child(child(&cst.root, 1), 1);
+        assert_eq!(wire.kind, "NetDeclaration");
+        assert_eq!(wire.to_string(), " wire [`W-1:0] w;");
+    }
+}
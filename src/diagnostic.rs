@@ -18,6 +18,44 @@ pub enum Message {
     UnexpectedChar(char),
     UnexpectedToken(Token, String),
     UnexpectedTokens(Vec<Token>, String),
+    UnknownKeywordVersion(String),
+    MissingKeywordVersion,
+    UnmatchedEndKeywords,
+    UnmatchedConditional(String),
+    ConditionalAfterElse(String),
+    UnterminatedConditional,
+    UndefinedMacro(String),
+    RecursiveMacro(String),
+    MissingMacroArguments(String),
+    TooManyMacroArguments(String),
+    MissingMacroArgument(String, String),
+    UnterminatedMacroArguments(String),
+    UnterminatedStringification,
+    UnterminatedString,
+    InMacroExpansion(String),
+    InPredefinedMacroExpansion(String),
+    IncludeNotFound(String),
+    IncludeCycle(String),
+    CannotReadFile(String, String),
+    UnknownFilelistOption(String),
+    MissingOptionArgument(String),
+    RecursiveFilelist(String),
+    UndefinedEnvironmentVariable(String),
+    UndefinedModule(String),
+    ExpectedExpression(String),
+    UnknownSystemTf(String),
+    SystemTfArguments(String, String),
+    NonConstantSystemTf(String),
+    IllegalDigit(char, String),
+    InvalidNumberSize(String),
+    NumberTruncated(String, usize),
+    NumberTooWide(String, usize),
+    MixedConnections,
+    MissingPortDirection(String),
+    NotInPortList(String),
+    DuplicatePort(String),
+    /// End label and the name at the start, if any
+    EndLabelMismatch(String, Option<String>),
 }
 
 impl fmt::Display for Message {
@@ -29,6 +67,86 @@ impl fmt::Display for Message {
             UnexpectedChar(ch) => write!(f, "Unexpected character: {}", ch),
             UnexpectedToken(token, s) => write!(f, "Expected {:?}, but got {}", token, s),
             UnexpectedTokens(tokens, s) => write!(f, "Expected {:?}, but got {}", tokens, s),
+            UnknownKeywordVersion(s) => write!(f, "Unknown keyword version: {}", s),
+            MissingKeywordVersion => write!(f, "Expected version specifier after `begin_keywords"),
+            UnmatchedEndKeywords => write!(f, "`end_keywords without matching `begin_keywords"),
+            UnmatchedConditional(s) => write!(f, "{} without matching `ifdef or `ifndef", s),
+            ConditionalAfterElse(s) => write!(f, "{} after `else", s),
+            UnterminatedConditional => write!(f, "Conditional is not closed by `endif"),
+            UndefinedMacro(s) => write!(f, "Macro is not defined: {}", s),
+            RecursiveMacro(s) => write!(f, "Macro expands to itself: {}", s),
+            MissingMacroArguments(s) => write!(f, "Expected arguments for macro {}", s),
+            TooManyMacroArguments(s) => write!(f, "Too many arguments for macro {}", s),
+            MissingMacroArgument(s, arg) => {
+                write!(f, "Missing argument {} for macro {}", arg, s)
+            }
+            UnterminatedMacroArguments(s) => {
+                write!(f, "Arguments of macro {} are not closed", s)
+            }
+            UnterminatedStringification => write!(f, "`\" is not closed"),
+            UnterminatedString => write!(f, "String literal is not closed"),
+            InMacroExpansion(s) => write!(f, "in expansion of macro {} defined", s),
+            InPredefinedMacroExpansion(s) => {
+                write!(f, "in expansion of predefined macro {}", s)
+            }
+            IncludeNotFound(s) => write!(f, "Cannot find included file: {}", s),
+            IncludeCycle(s) => write!(f, "File includes itself: {}", s),
+            CannotReadFile(s, err) => write!(f, "Cannot read file {}: {}", s, err),
+            UnknownFilelistOption(s) => write!(f, "Unknown filelist option: {}", s),
+            MissingOptionArgument(s) => write!(f, "Expected argument after {}", s),
+            RecursiveFilelist(s) => write!(f, "Filelist includes itself: {}", s),
+            UndefinedEnvironmentVariable(s) => {
+                write!(f, "Environment variable is not defined: {}", s)
+            }
+            UndefinedModule(s) => write!(f, "Module is not defined: {}", s),
+            ExpectedExpression(s) => write!(f, "Expected expression, but got {}", s),
+            UnknownSystemTf(s) => write!(f, "Unknown system task or function: {}", s),
+            SystemTfArguments(s, arity) => write!(f, "Expected {} arguments for {}", arity, s),
+            NonConstantSystemTf(s) => {
+                write!(
+                    f,
+                    "System function cannot be used in a constant expression: {}",
+                    s
+                )
+            }
+            IllegalDigit(ch, s) => write!(f, "Illegal digit {:?} in number {}", ch, s),
+            InvalidNumberSize(s) => write!(f, "Invalid size of number {}", s),
+            NumberTruncated(s, width) => write!(
+                f,
+                "Value of {} does not fit in {} bits and is truncated",
+                s, width
+            ),
+            NumberTooWide(s, width) => write!(
+                f,
+                "Size of {} exceeds the maximum width of {} bits",
+                s, width
+            ),
+            MixedConnections => write!(f, "Ordered and named connections cannot be mixed"),
+            MissingPortDirection(s) => {
+                write!(f, "Port {} is not declared as input, output or inout", s)
+            }
+            NotInPortList(s) => write!(f, "{} is declared as a port, but not in the port list", s),
+            DuplicatePort(s) => write!(f, "Port {} appears more than once in the port list", s),
+            EndLabelMismatch(s, Some(name)) => {
+                write!(f, "End label {} does not match the name {}", s, name)
+            }
+            EndLabelMismatch(s, None) => write!(f, "End label {} of an unnamed block", s),
+        }
+    }
+}
+
+/// Additional information attached to a diagnostic
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct Note {
+    pub pos: Option<Span>,
+    pub msg: Message,
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        match self.pos {
+            Some(pos) => write!(f, "{} at {}", self.msg, pos.from),
+            None => write!(f, "{}", self.msg),
         }
     }
 }
@@ -38,6 +156,8 @@ pub struct Diagnostic {
     pub pos: Span,
     pub msg: Message,
     pub severity: Severity,
+    #[serde(default)]
+    pub notes: Vec<Note>,
 }
 
 impl fmt::Display for Diagnostic {
@@ -46,6 +166,10 @@ impl fmt::Display for Diagnostic {
             f,
             "{:?} at ({}-{}): {}",
             self.severity, self.pos.from, self.pos.to, self.msg
-        )
+        )?;
+        for note in &self.notes {
+            write!(f, "\n    {}", note)?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+use structopt::StructOpt;
+use verilog_lang::compilation::Compilation;
+
+#[derive(StructOpt)]
+struct Args {
+    /// Filelist
+    #[structopt(short, long)]
+    filelist: PathBuf,
+}
+
+#[paw::main]
+fn main(args: Args) {
+    let compilation = Compilation::from_filelist(&args.filelist);
+    for diag in compilation.diagnostics() {
+        eprintln!("{}", compilation.sources.render(diag));
+    }
+    for unit in &compilation.units {
+        println!("{}", compilation.sources.path(unit.file).display());
+        println!("{:?}", unit.tree.root());
+    }
+}
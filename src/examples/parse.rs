@@ -1,22 +1,33 @@
-use std::fs::File;
-use std::io::Read;
 use std::path::PathBuf;
 use structopt::StructOpt;
-use verilog_lang::{ast::*, parser::Parser};
+use verilog_lang::{
+    preprocessor::PreprocessorOptions, source::SourceManager, syntax_tree::SyntaxTree,
+};
 
 #[derive(StructOpt)]
 struct Args {
     #[structopt(short, long)]
     file: PathBuf,
+    #[structopt(short = "D", long)]
+    define: Vec<String>,
+    #[structopt(short = "I", long)]
+    incdir: Vec<PathBuf>,
 }
 
 #[paw::main]
 fn main(args: Args) {
-    let mut file = File::open(args.file).unwrap();
-    let mut content = String::new();
-    file.read_to_string(&mut content).unwrap();
-    let mut parser = Parser::from(&content);
-    let m = SourceText::parse(&mut parser);
-    println!("{:?}", parser);
-    println!("{:?}", m);
+    let mut sources = SourceManager::new();
+    let file = sources.load(&args.file).unwrap();
+    let mut options = PreprocessorOptions {
+        incdirs: args.incdir,
+        ..PreprocessorOptions::default()
+    };
+    for define in &args.define {
+        options.define(define);
+    }
+    let tree = SyntaxTree::parse_file(file, &options, &mut sources);
+    for diag in tree.diagnostics() {
+        eprintln!("{}", sources.render(diag));
+    }
+    println!("{:?}", tree.root());
 }
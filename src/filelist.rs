@@ -0,0 +1,393 @@
+//! Filelists
+//!
+//! A filelist (`.f`) holds command line arguments of a simulator, one or more
+//! per line:
+//!
+//! - source files
+//! - `+incdir+<dir>[+<dir>...]` include directories
+//! - `+define+<name>[=<value>][+...]` predefined macros
+//! - `+libext+<ext>[+<ext>...]` extensions of library files
+//! - `-v <file>` library file
+//! - `-y <dir>` library directory
+//! - `-f <file>` nested filelist, paths relative to the working directory
+//! - `-F <file>` nested filelist, paths relative to the filelist
+//!
+//! Comments start with `//` or `#`, `$VAR`, `${VAR}` and `$(VAR)` are
+//! replaced by environment variables.
+
+use crate::diagnostic::{Diagnostic, Message, Severity};
+use crate::lexer::{Location, Span};
+use crate::source::{FileId, SourceManager};
+use std::path::{Path, PathBuf};
+
+/// A path in a filelist and where it is written
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FilelistPath {
+    pub path: PathBuf,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Filelist {
+    pub files: Vec<FilelistPath>,
+    pub incdirs: Vec<PathBuf>,
+    /// `NAME` or `NAME=VALUE`
+    pub defines: Vec<String>,
+    /// Files given by `-v`
+    pub library_files: Vec<FilelistPath>,
+    /// Directories given by `-y`
+    pub library_dirs: Vec<PathBuf>,
+    /// Extensions given by `+libext+`
+    pub libext: Vec<String>,
+    pub diag: Vec<Diagnostic>,
+}
+
+/// Split filelist text into arguments
+fn arguments(text: &str, file: FileId) -> Vec<(String, Span)> {
+    let mut res = vec![];
+    let mut chars = text.chars().peekable();
+    let mut loc = Location::default();
+    let mut arg: Option<(String, Location)> = None;
+    let mut to = loc;
+    let mut quoted = false;
+    let mut line_comment = false;
+    let mut block_comment = false;
+
+    while let Some(ch) = chars.next() {
+        let here = loc;
+        loc.offset += ch.len_utf8();
+        if ch == '\n' {
+            loc.row += 1;
+            loc.col = 0;
+        } else {
+            loc.col += 1;
+        }
+
+        if line_comment {
+            line_comment = ch != '\n';
+            continue;
+        }
+        if block_comment {
+            if ch == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                loc.col += 1;
+                loc.offset += 1;
+                block_comment = false;
+            }
+            continue;
+        }
+        if quoted {
+            if ch == '"' {
+                quoted = false;
+            } else if let Some((arg, _)) = &mut arg {
+                arg.push(ch);
+            }
+            to = here;
+            continue;
+        }
+
+        match ch {
+            '"' => {
+                quoted = true;
+                arg.get_or_insert((String::new(), here));
+                to = here;
+            }
+            '/' if arg.is_none() && chars.peek() == Some(&'/') => line_comment = true,
+            '/' if arg.is_none() && chars.peek() == Some(&'*') => {
+                chars.next();
+                loc.col += 1;
+                loc.offset += 1;
+                block_comment = true;
+            }
+            '#' if arg.is_none() => line_comment = true,
+            ch if ch.is_whitespace() => {
+                if let Some((text, from)) = arg.take() {
+                    res.push((text, Span { file, from, to }));
+                }
+            }
+            ch => {
+                arg.get_or_insert((String::new(), here)).0.push(ch);
+                to = here;
+            }
+        }
+    }
+    if let Some((text, from)) = arg.take() {
+        res.push((text, Span { file, from, to }));
+    }
+    res
+}
+
+/// Replace `$VAR`, `${VAR}` and `$(VAR)`, returns undefined variables
+fn expand_env(arg: &str) -> (String, Vec<String>) {
+    let mut res = String::new();
+    let mut undefined = vec![];
+    let mut rest = arg;
+    while let Some(index) = rest.find('$') {
+        res.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let (name, len) = match rest.chars().next() {
+            Some(open @ ('{' | '(')) => {
+                let close = if open == '{' { '}' } else { ')' };
+                match rest.find(close) {
+                    Some(end) => (&rest[1..end], end + 1),
+                    None => ("", 0),
+                }
+            }
+            _ => {
+                let end = rest
+                    .find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+                    .unwrap_or(rest.len());
+                (&rest[..end], end)
+            }
+        };
+        if name.is_empty() {
+            res.push('$');
+            continue;
+        }
+        match std::env::var(name) {
+            Ok(value) => res.push_str(&value),
+            Err(_) => undefined.push(name.to_owned()),
+        }
+        rest = &rest[len..];
+    }
+    res.push_str(rest);
+    (res, undefined)
+}
+
+impl Filelist {
+    /// Read a filelist like `-f`, relative paths are kept as is
+    pub fn load(path: impl AsRef<Path>, sources: &mut SourceManager) -> Filelist {
+        let mut res = Filelist::default();
+        match sources.load(path.as_ref()) {
+            Ok(file) => res.read(file, None, sources),
+            Err(err) => res.diag.push(Diagnostic {
+                pos: Span {
+                    file: FileId::default(),
+                    from: Location::default(),
+                    to: Location::default(),
+                },
+                msg: Message::CannotReadFile(path.as_ref().display().to_string(), err.to_string()),
+                severity: Severity::Error,
+                notes: vec![],
+            }),
+        }
+        res
+    }
+
+    fn err(&mut self, pos: Span, msg: Message) {
+        self.diag.push(Diagnostic {
+            pos,
+            msg,
+            severity: Severity::Error,
+            notes: vec![],
+        });
+    }
+
+    fn warn(&mut self, pos: Span, msg: Message) {
+        self.diag.push(Diagnostic {
+            pos,
+            msg,
+            severity: Severity::Warning,
+            notes: vec![],
+        });
+    }
+
+    /// Read the arguments of `file`, relative paths are joined to `base`
+    fn read(&mut self, file: FileId, base: Option<&Path>, sources: &mut SourceManager) {
+        let text = sources.text(file);
+        let resolve = |path: &str| match base {
+            Some(base) if Path::new(path).is_relative() => base.join(path),
+            _ => PathBuf::from(path),
+        };
+        let mut args = arguments(&text, file).into_iter();
+        while let Some((arg, span)) = args.next() {
+            let (arg, undefined) = expand_env(&arg);
+            for name in undefined {
+                self.warn(span, Message::UndefinedEnvironmentVariable(name));
+            }
+
+            if let Some(dirs) = arg.strip_prefix("+incdir+") {
+                self.incdirs
+                    .extend(dirs.split('+').filter(|s| !s.is_empty()).map(resolve));
+            } else if let Some(defines) = arg.strip_prefix("+define+") {
+                self.defines.extend(
+                    defines
+                        .split('+')
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_owned),
+                );
+            } else if let Some(exts) = arg.strip_prefix("+libext+") {
+                self.libext
+                    .extend(exts.split('+').filter(|s| !s.is_empty()).map(str::to_owned));
+            } else if matches!(arg.as_str(), "-f" | "-F" | "-v" | "-y") {
+                let (value, value_span) = match args.next() {
+                    Some((value, value_span)) => {
+                        let (value, undefined) = expand_env(&value);
+                        for name in undefined {
+                            self.warn(value_span, Message::UndefinedEnvironmentVariable(name));
+                        }
+                        (value, value_span)
+                    }
+                    None => {
+                        self.err(span, Message::MissingOptionArgument(arg));
+                        continue;
+                    }
+                };
+                let path = resolve(&value);
+                match arg.as_str() {
+                    "-v" => self.library_files.push(FilelistPath {
+                        path,
+                        span: value_span,
+                    }),
+                    "-y" => self.library_dirs.push(path),
+                    _ => {
+                        if sources.is_cyclic(file, &path) {
+                            self.err(value_span, Message::RecursiveFilelist(value));
+                            continue;
+                        }
+                        match sources.include(&path, value_span) {
+                            Ok(nested) => {
+                                if arg == "-F" {
+                                    let dir = path.parent().unwrap_or(Path::new("")).to_owned();
+                                    self.read(nested, Some(&dir), sources);
+                                } else {
+                                    self.read(nested, None, sources);
+                                }
+                            }
+                            Err(err) => self
+                                .err(value_span, Message::CannotReadFile(value, err.to_string())),
+                        }
+                    }
+                }
+            } else if arg.starts_with('-') || arg.starts_with('+') {
+                self.warn(span, Message::UnknownFilelistOption(arg));
+            } else {
+                self.files.push(FilelistPath {
+                    path: resolve(&arg),
+                    span,
+                });
+            }
+        }
+    }
+
+    /// Find the file defining `module` in the library directories
+    pub fn find_library_file(&self, module: &str) -> Option<PathBuf> {
+        let mut exts: Vec<&str> = self.libext.iter().map(String::as_str).collect();
+        if exts.is_empty() {
+            exts.push("");
+        }
+        self.library_dirs
+            .iter()
+            .flat_map(|dir| {
+                exts.iter()
+                    .map(move |ext| dir.join(format!("{module}{ext}")))
+            })
+            .find(|path| path.is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments() {
+        let args = super::arguments(
+            "a.v // comment\n  +incdir+inc # comment\n/* block\n */ \"with space.v\"",
+            FileId(1),
+        );
+        let texts: Vec<&str> = args.iter().map(|(text, _)| text.as_str()).collect();
+        assert_eq!(texts, vec!["a.v", "+incdir+inc", "with space.v"]);
+        assert_eq!(
+            args[1].1.from,
+            Location {
+                row: 1,
+                col: 2,
+                offset: 17
+            }
+        );
+        assert_eq!(
+            args[1].1.to,
+            Location {
+                row: 1,
+                col: 12,
+                offset: 27
+            }
+        );
+        assert_eq!(args[2].1.file, FileId(1));
+    }
+
+    #[test]
+    fn env() {
+        std::env::set_var("FILELIST_TEST_HOME", "/home");
+        assert_eq!(
+            expand_env("$FILELIST_TEST_HOME/a ${FILELIST_TEST_HOME}/b $(FILELIST_TEST_HOME)"),
+            ("/home/a /home/b /home".to_owned(), vec![])
+        );
+        assert_eq!(
+            expand_env("$FILELIST_TEST_UNDEFINED/a"),
+            ("/a".to_owned(), vec!["FILELIST_TEST_UNDEFINED".to_owned()])
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let mut sources = SourceManager::new();
+        sources.add(
+            "top.f",
+            "+incdir+inc+inc2 +define+A+B=1\n-F sub/sub.f\n-f sub/other.f\ntop.v\n-v lib.v -y libdir +libext+.v+.sv\n",
+        );
+        sources.add("sub/sub.f", "a.v +incdir+sub_inc -F sub.f");
+        sources.add("sub/other.f", "b.v -bogus -f");
+        let filelist = Filelist::load("top.f", &mut sources);
+        let files: Vec<&Path> = filelist.files.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(
+            files,
+            vec![Path::new("sub/a.v"), Path::new("b.v"), Path::new("top.v")]
+        );
+        assert_eq!(
+            filelist.incdirs,
+            vec![
+                PathBuf::from("inc"),
+                PathBuf::from("inc2"),
+                PathBuf::from("sub/sub_inc")
+            ]
+        );
+        assert_eq!(filelist.defines, vec!["A", "B=1"]);
+        assert_eq!(filelist.library_files[0].path, PathBuf::from("lib.v"));
+        assert_eq!(filelist.library_dirs, vec![PathBuf::from("libdir")]);
+        assert_eq!(filelist.libext, vec![".v", ".sv"]);
+
+        let msgs: Vec<Message> = filelist.diag.iter().map(|d| d.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                Message::RecursiveFilelist("sub.f".to_owned()),
+                Message::UnknownFilelistOption("-bogus".to_owned()),
+                Message::MissingOptionArgument("-f".to_owned()),
+            ]
+        );
+        assert_eq!(
+            sources.render(&filelist.diag[0]),
+            "sub/sub.f:0:23: Error: Filelist includes itself: sub.f\n    included from top.f:1:3"
+        );
+    }
+
+    #[test]
+    fn nested_cycle() {
+        let mut sources = SourceManager::new();
+        sources.add("top.f", "-F sub/a.f");
+        sources.add("sub/a.f", "a.v -F ../sub/a.f -F ./a.f -f ../top.f");
+        let filelist = Filelist::load("top.f", &mut sources);
+        let msgs: Vec<Message> = filelist.diag.iter().map(|d| d.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                Message::RecursiveFilelist("../sub/a.f".to_owned()),
+                Message::RecursiveFilelist("./a.f".to_owned()),
+                Message::RecursiveFilelist("../top.f".to_owned()),
+            ]
+        );
+        assert_eq!(filelist.files.len(), 1);
+    }
+}
@@ -1,16 +1,23 @@
 //! Lexer
 
 use crate::diagnostic::{Diagnostic, Message, Severity};
+use crate::line_index::LineIndex;
+use crate::source::FileId;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use strcursor::StrCursor;
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
 pub struct Location {
     pub row: usize,
+    /// Column counted in grapheme clusters
     pub col: usize,
+    /// Byte offset in the file
+    #[serde(default)]
+    pub offset: usize,
 }
 
 impl std::fmt::Display for Location {
@@ -21,6 +28,8 @@ impl std::fmt::Display for Location {
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Span {
+    #[serde(default)]
+    pub file: FileId,
     pub from: Location,
     pub to: Location,
 }
@@ -29,9 +38,15 @@ pub struct Span {
 pub enum Token {
     // Types
     Number,
+    TimeLiteral,
     Identifier,
+    SystemIdentifier,
     StringLiteral,
     Directive,
+    MacroText,
+    MacroQuote,        // `"
+    MacroEscapedQuote, // `\`"
+    MacroPaste,        // ``
     Comment,
 
     // Keywords, Annex B
@@ -44,6 +59,7 @@ pub enum Token {
     And,
     Assert,
     Assign,
+    Assume,
     Automatic,
     Before,
     Begin,
@@ -113,6 +129,7 @@ pub enum Token {
     FirstMatch,
     For,
     Force,
+    Foreach,
     Forever,
     Fork,
     ForkJoin,
@@ -137,6 +154,7 @@ pub enum Token {
     Input,
     Inside,
     Instance,
+    Int,
     Integer,
     Interconnect,
     Interface,
@@ -148,6 +166,7 @@ pub enum Token {
     Let,
     LibList,
     Library,
+    Local,
     Localparam,
     Logic,
     LongInt,
@@ -167,6 +186,7 @@ pub enum Token {
     Not,
     NotIf0,
     NotIf1,
+    Null,
     Or,
     Output,
     Package,
@@ -267,6 +287,7 @@ pub enum Token {
     Wait,
     WaitOrder,
     Wand,
+    Weak,
     Weak0,
     Weak1,
     While,
@@ -286,52 +307,240 @@ pub enum Token {
     RBracket,
     LBraces, // {}
     RBraces,
-    Colon,      // :
-    Comma,      // ,
-    Semicolon,  // ;
-    Dot,        // .
-    Equal,      // =
-    At,         // @
-    Question,   // ?
-    Apostrophe, // '
+    Colon,             // :
+    Comma,             // ,
+    Semicolon,         // ;
+    Dot,               // .
+    Equal,             // =
+    At,                // @
+    Question,          // ?
+    Apostrophe,        // '
+    Dollar,            // $
+    DoubleColon,       // ::
+    DoubleSharp,       // ##
+    SharpMinusSharp,   // #-#
+    SharpEqualSharp,   // #=#
+    ApostropheLBraces, // '{
+    DotStar,           // .*
+    PlusColon,         // +:
+    MinusColon,        // -:
 
     // Operators, Table 9
-    OpPlus,            // +
-    OpMinus,           // -
-    OpMultiply,        // *
-    OpDivide,          // /
-    OpPow,             // **
-    OpMod,             // %
-    OpGreaterThan,     // >
-    OpGreaterEqual,    // >=
-    OpLessThan,        // <
-    OpLessEqual,       // <=
-    OpNot,             // !
-    OpAnd,             // &&
-    OpOr,              // ||
-    OpEqual,           // ==
-    OpInequal,         // !=
-    OpCaseEqual,       // ===
-    OpCaseInequal,     // !==
-    OpBitNeg,          // ~
-    OpBitAnd,          // &
-    OpBitOr,           // |
-    OpBitXor,          // ^
-    OpBitEquiv1,       // ^~
-    OpBitEquiv2,       // ~^
-    OpNand,            // ~&
-    OpNor,             // ~|
-    OpLeftShift,       // <<
-    OpRightShift,      // >>
-    OpArithLeftShift,  // <<<
-    OpArithRightShift, // >>>
+    OpPlus,                     // +
+    OpMinus,                    // -
+    OpMultiply,                 // *
+    OpDivide,                   // /
+    OpPow,                      // **
+    OpMod,                      // %
+    OpGreaterThan,              // >
+    OpGreaterEqual,             // >=
+    OpLessThan,                 // <
+    OpLessEqual,                // <=
+    OpNot,                      // !
+    OpAnd,                      // &&
+    OpOr,                       // ||
+    OpEqual,                    // ==
+    OpInequal,                  // !=
+    OpCaseEqual,                // ===
+    OpCaseInequal,              // !==
+    OpBitNeg,                   // ~
+    OpBitAnd,                   // &
+    OpBitOr,                    // |
+    OpBitXor,                   // ^
+    OpBitEquiv1,                // ^~
+    OpBitEquiv2,                // ~^
+    OpNand,                     // ~&
+    OpNor,                      // ~|
+    OpLeftShift,                // <<
+    OpRightShift,               // >>
+    OpArithLeftShift,           // <<<
+    OpArithRightShift,          // >>>
+    OpWildcardEqual,            // ==?
+    OpWildcardInequal,          // !=?
+    OpIncrement,                // ++
+    OpDecrement,                // --
+    OpImplication,              // ->
+    OpNonBlockingTrigger,       // ->>
+    OpEquivalence,              // <->
+    OpOverlappedImplication,    // |->
+    OpNonOverlappedImplication, // |=>
+
+    // Assignment operators, A.6.2
+    OpAddAssign,             // +=
+    OpSubAssign,             // -=
+    OpMulAssign,             // *=
+    OpDivAssign,             // /=
+    OpModAssign,             // %=
+    OpAndAssign,             // &=
+    OpOrAssign,              // |=
+    OpXorAssign,             // ^=
+    OpLeftShiftAssign,       // <<=
+    OpRightShiftAssign,      // >>=
+    OpArithLeftShiftAssign,  // <<<=
+    OpArithRightShiftAssign, // >>>=
 }
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub struct ParsedToken<'a> {
     pub span: Span,
     pub token: Token,
-    pub text: &'a str,
+    pub text: Cow<'a, str>,
+    /// Index of the macro expansion that produced this token
+    pub expansion: Option<usize>,
+}
+
+impl<'a> ParsedToken<'a> {
+    pub fn into_owned(self) -> ParsedToken<'static> {
+        ParsedToken {
+            span: self.span,
+            token: self.token,
+            text: Cow::Owned(self.text.into_owned()),
+            expansion: self.expansion,
+        }
+    }
+}
+
+/// Operators and multi-character delimiters, longest first
+const OPERATORS: &[(&str, Token)] = &[
+    ("<<<=", Token::OpArithLeftShiftAssign),
+    (">>>=", Token::OpArithRightShiftAssign),
+    ("===", Token::OpCaseEqual),
+    ("!==", Token::OpCaseInequal),
+    ("==?", Token::OpWildcardEqual),
+    ("!=?", Token::OpWildcardInequal),
+    (">>>", Token::OpArithRightShift),
+    ("<<<", Token::OpArithLeftShift),
+    ("<<=", Token::OpLeftShiftAssign),
+    (">>=", Token::OpRightShiftAssign),
+    ("<->", Token::OpEquivalence),
+    ("->>", Token::OpNonBlockingTrigger),
+    ("|->", Token::OpOverlappedImplication),
+    ("|=>", Token::OpNonOverlappedImplication),
+    ("#-#", Token::SharpMinusSharp),
+    ("#=#", Token::SharpEqualSharp),
+    ("~&", Token::OpNand),
+    ("~|", Token::OpNor),
+    ("~^", Token::OpBitEquiv2),
+    ("^~", Token::OpBitEquiv1),
+    ("==", Token::OpEqual),
+    ("!=", Token::OpInequal),
+    ("&&", Token::OpAnd),
+    ("||", Token::OpOr),
+    ("**", Token::OpPow),
+    ("<=", Token::OpLessEqual),
+    (">=", Token::OpGreaterEqual),
+    (">>", Token::OpRightShift),
+    ("<<", Token::OpLeftShift),
+    ("++", Token::OpIncrement),
+    ("--", Token::OpDecrement),
+    ("+=", Token::OpAddAssign),
+    ("-=", Token::OpSubAssign),
+    ("*=", Token::OpMulAssign),
+    ("/=", Token::OpDivAssign),
+    ("%=", Token::OpModAssign),
+    ("&=", Token::OpAndAssign),
+    ("|=", Token::OpOrAssign),
+    ("^=", Token::OpXorAssign),
+    ("->", Token::OpImplication),
+    ("::", Token::DoubleColon),
+    ("##", Token::DoubleSharp),
+    ("'{", Token::ApostropheLBraces),
+    (".*", Token::DotStar),
+    ("+:", Token::PlusColon),
+    ("-:", Token::MinusColon),
+    ("+", Token::OpPlus),
+    ("-", Token::OpMinus),
+    ("!", Token::OpNot),
+    ("&", Token::OpBitAnd),
+    ("|", Token::OpBitOr),
+    ("^", Token::OpBitXor),
+    ("*", Token::OpMultiply),
+    ("/", Token::OpDivide),
+    ("%", Token::OpMod),
+    ("<", Token::OpLessThan),
+    (">", Token::OpGreaterThan),
+    ("~", Token::OpBitNeg),
+];
+
+/// Reserved keyword sets, 22.14 `begin_keywords, `end_keywords
+///
+/// Variants are ordered so that every keyword set contains all keywords of
+/// the sets before it.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum KeywordVersion {
+    Verilog1995,
+    Verilog2001NoConfig,
+    Verilog2001,
+    Verilog2005,
+    SystemVerilog2005,
+    SystemVerilog2009,
+    SystemVerilog2012,
+    SystemVerilog2017,
+    #[default]
+    SystemVerilog2023,
+}
+
+impl KeywordVersion {
+    /// Parse a version_specifier, e.g. "1364-2001"
+    pub fn from_specifier(specifier: &str) -> Option<Self> {
+        use KeywordVersion::*;
+        match specifier {
+            "1364-1995" => Some(Verilog1995),
+            "1364-2001-noconfig" => Some(Verilog2001NoConfig),
+            "1364-2001" => Some(Verilog2001),
+            "1364-2005" => Some(Verilog2005),
+            "1800-2005" => Some(SystemVerilog2005),
+            "1800-2009" => Some(SystemVerilog2009),
+            "1800-2012" => Some(SystemVerilog2012),
+            "1800-2017" => Some(SystemVerilog2017),
+            "1800-2023" => Some(SystemVerilog2023),
+            _ => None,
+        }
+    }
+
+    /// The version_specifier of this keyword set
+    pub fn specifier(&self) -> &'static str {
+        use KeywordVersion::*;
+        match self {
+            Verilog1995 => "1364-1995",
+            Verilog2001NoConfig => "1364-2001-noconfig",
+            Verilog2001 => "1364-2001",
+            Verilog2005 => "1364-2005",
+            SystemVerilog2005 => "1800-2005",
+            SystemVerilog2009 => "1800-2009",
+            SystemVerilog2012 => "1800-2012",
+            SystemVerilog2017 => "1800-2017",
+            SystemVerilog2023 => "1800-2023",
+        }
+    }
+
+    /// Whether `keyword` is reserved in this keyword set
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(KEYWORD.get(keyword), Some((_, since)) if since <= self)
+    }
+
+    /// Token of a word that is a keyword of some set, it is an identifier in
+    /// the sets before the one that reserved it
+    pub(crate) fn keyword_token(&self, word: &str) -> Option<Token> {
+        KEYWORD.get(word).map(|(token, since)| {
+            if since <= self {
+                *token
+            } else {
+                Token::Identifier
+            }
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct LexerOptions {
+    /// Keyword set in effect before any `begin_keywords
+    pub keywords: KeywordVersion,
+    /// File that the input comes from
+    pub file: FileId,
+    /// The input is the text of a `define, where backslash-newline continues
+    /// the line
+    pub macro_text: bool,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -339,30 +548,305 @@ pub struct Lexer<'a> {
     cursor: StrCursor<'a>,
     pub(crate) loc: Location,
     pub(crate) input: &'a str,
+    pub(crate) file: FileId,
+    /// Stack of keyword sets, the bottom one comes from `LexerOptions`
+    keywords: Vec<KeywordVersion>,
+    macro_text: bool,
     pub tokens: Vec<ParsedToken<'a>>,
     pub diag: Vec<Diagnostic>,
 }
 
-fn keyword_map() -> HashMap<String, Token> {
+/// Keywords in IEEE 1800-2017 Annex B and the keyword set that introduced them
+const KEYWORDS: &[(&str, Token, KeywordVersion)] = {
+    use KeywordVersion::*;
     use Token::*;
-    let mut map = HashMap::new();
-    // TODO: all keywords
-    map.insert(format!("always"), Always);
-    map.insert(format!("always_comb"), AlwaysComb);
-    map.insert(format!("always_ff"), AlwaysFf);
-    map.insert(format!("and"), And);
-    map.insert(format!("assign"), Assign);
-    map.insert(format!("automatic"), Automatic);
-    map.insert(format!("endmodule"), EndModule);
-    map.insert(format!("logic"), Logic);
-    map.insert(format!("inout"), InOut);
-    map.insert(format!("input"), Input);
-    map.insert(format!("output"), Output);
-    map.insert(format!("module"), Module);
-    map.insert(format!("reg"), Reg);
-    map.insert(format!("signed"), Signed);
-    map.insert(format!("wire"), Wire);
-    map
+    &[
+        ("accept_on", AcceptOn, SystemVerilog2009),
+        ("alias", Alias, SystemVerilog2005),
+        ("always", Always, Verilog1995),
+        ("always_comb", AlwaysComb, SystemVerilog2005),
+        ("always_ff", AlwaysFf, SystemVerilog2005),
+        ("always_latch", AlwaysLatch, SystemVerilog2005),
+        ("and", And, Verilog1995),
+        ("assert", Assert, SystemVerilog2005),
+        ("assign", Assign, Verilog1995),
+        ("assume", Assume, SystemVerilog2005),
+        ("automatic", Automatic, Verilog2001NoConfig),
+        ("before", Before, SystemVerilog2005),
+        ("begin", Begin, Verilog1995),
+        ("bind", Bind, SystemVerilog2005),
+        ("bins", Bins, SystemVerilog2005),
+        ("binsof", BinsOf, SystemVerilog2005),
+        ("bit", Bit, SystemVerilog2005),
+        ("break", Break, SystemVerilog2005),
+        ("buf", Buf, Verilog1995),
+        ("bufif0", BufIf0, Verilog1995),
+        ("bufif1", BufIf1, Verilog1995),
+        ("byte", Byte, SystemVerilog2005),
+        ("case", Case, Verilog1995),
+        ("casex", Casex, Verilog1995),
+        ("casez", Casez, Verilog1995),
+        ("cell", Cell, Verilog2001),
+        ("chandle", CHandle, SystemVerilog2005),
+        ("checker", Checker, SystemVerilog2009),
+        ("class", Class, SystemVerilog2005),
+        ("clocking", Clocking, SystemVerilog2005),
+        ("cmos", Cmos, Verilog1995),
+        ("config", Config, Verilog2001),
+        ("const", Const, SystemVerilog2005),
+        ("constraint", Constraint, SystemVerilog2005),
+        ("context", Context, SystemVerilog2005),
+        ("continue", Continue, SystemVerilog2005),
+        ("cover", Cover, SystemVerilog2005),
+        ("covergroup", CoverGroup, SystemVerilog2005),
+        ("coverpoint", CoverPoint, SystemVerilog2005),
+        ("cross", Cross, SystemVerilog2005),
+        ("deassign", Deassign, Verilog1995),
+        ("default", Default, Verilog1995),
+        ("defparam", DefParam, Verilog1995),
+        ("design", Design, Verilog2001),
+        ("disable", Disable, Verilog1995),
+        ("dist", Dist, SystemVerilog2005),
+        ("do", Do, SystemVerilog2005),
+        ("edge", Edge, Verilog1995),
+        ("else", Else, Verilog1995),
+        ("end", End, Verilog1995),
+        ("endcase", EndCase, Verilog1995),
+        ("endchecker", EndChecker, SystemVerilog2009),
+        ("endclass", EndClass, SystemVerilog2005),
+        ("endclocking", EndClocking, SystemVerilog2005),
+        ("endconfig", EndConfig, Verilog2001),
+        ("endfunction", EndFunction, Verilog1995),
+        ("endgenerate", EndGenerate, Verilog2001NoConfig),
+        ("endgroup", EndGroup, SystemVerilog2005),
+        ("endinterface", EndInterface, SystemVerilog2005),
+        ("endmodule", EndModule, Verilog1995),
+        ("endpackage", EndPackage, SystemVerilog2005),
+        ("endprimitive", EndPrimitive, Verilog1995),
+        ("endprogram", EndProgram, SystemVerilog2005),
+        ("endproperty", EndProperty, SystemVerilog2005),
+        ("endspecify", EndSpecify, Verilog1995),
+        ("endsequence", EndSequence, SystemVerilog2005),
+        ("endtable", EndTable, Verilog1995),
+        ("endtask", EndTask, Verilog1995),
+        ("enum", Enum, SystemVerilog2005),
+        ("event", Event, Verilog1995),
+        ("eventually", Eventually, SystemVerilog2009),
+        ("expect", Expect, SystemVerilog2005),
+        ("export", Export, SystemVerilog2005),
+        ("extends", Extends, SystemVerilog2005),
+        ("extern", Extern, SystemVerilog2005),
+        ("final", Final, SystemVerilog2005),
+        ("first_match", FirstMatch, SystemVerilog2005),
+        ("for", For, Verilog1995),
+        ("force", Force, Verilog1995),
+        ("foreach", Foreach, SystemVerilog2005),
+        ("forever", Forever, Verilog1995),
+        ("fork", Fork, Verilog1995),
+        ("forkjoin", ForkJoin, SystemVerilog2005),
+        ("function", Function, Verilog1995),
+        ("generate", Generate, Verilog2001NoConfig),
+        ("genvar", GenVar, Verilog2001NoConfig),
+        ("global", Global, SystemVerilog2009),
+        ("highz0", HighZ0, Verilog1995),
+        ("highz1", HighZ1, Verilog1995),
+        ("if", If, Verilog1995),
+        ("iff", Iff, SystemVerilog2005),
+        ("ifnone", IfNone, Verilog1995),
+        ("ignore_bins", IgnoreBins, SystemVerilog2005),
+        ("illegal_bins", IllegalBins, SystemVerilog2005),
+        ("implements", Implements, SystemVerilog2012),
+        ("implies", Implies, SystemVerilog2009),
+        ("import", Import, SystemVerilog2005),
+        ("incdir", IncDir, Verilog2001),
+        ("include", Include, Verilog2001),
+        ("initial", Initial, Verilog1995),
+        ("inout", InOut, Verilog1995),
+        ("input", Input, Verilog1995),
+        ("inside", Inside, SystemVerilog2005),
+        ("instance", Instance, Verilog2001),
+        ("int", Int, SystemVerilog2005),
+        ("integer", Integer, Verilog1995),
+        ("interconnect", Interconnect, SystemVerilog2012),
+        ("interface", Interface, SystemVerilog2005),
+        ("intersect", Intersect, SystemVerilog2005),
+        ("join", Join, Verilog1995),
+        ("join_any", JoinAny, SystemVerilog2005),
+        ("join_none", JoinNone, SystemVerilog2005),
+        ("large", Large, Verilog1995),
+        ("let", Let, SystemVerilog2009),
+        ("liblist", LibList, Verilog2001),
+        ("library", Library, Verilog2001),
+        ("local", Local, SystemVerilog2005),
+        ("localparam", Localparam, Verilog2001NoConfig),
+        ("logic", Logic, SystemVerilog2005),
+        ("longint", LongInt, SystemVerilog2005),
+        ("macromodule", MacroModule, Verilog1995),
+        ("matches", Matches, SystemVerilog2005),
+        ("medium", Medium, Verilog1995),
+        ("modport", ModPort, SystemVerilog2005),
+        ("module", Module, Verilog1995),
+        ("nand", Nand, Verilog1995),
+        ("negedge", NegEdge, Verilog1995),
+        ("nettype", NetType, SystemVerilog2012),
+        ("new", New, SystemVerilog2005),
+        ("nexttime", NextTime, SystemVerilog2009),
+        ("nmos", Nmos, Verilog1995),
+        ("nor", Nor, Verilog1995),
+        ("noshowcancelled", NoShowCancelled, Verilog2001NoConfig),
+        ("not", Not, Verilog1995),
+        ("notif0", NotIf0, Verilog1995),
+        ("notif1", NotIf1, Verilog1995),
+        ("null", Null, SystemVerilog2005),
+        ("or", Or, Verilog1995),
+        ("output", Output, Verilog1995),
+        ("package", Package, SystemVerilog2005),
+        ("packed", Packed, SystemVerilog2005),
+        ("parameter", Parameter, Verilog1995),
+        ("pmos", Pmos, Verilog1995),
+        ("posedge", PosEdge, Verilog1995),
+        ("primitive", Primitive, Verilog1995),
+        ("priority", Priority, SystemVerilog2005),
+        ("program", Program, SystemVerilog2005),
+        ("property", Property, SystemVerilog2005),
+        ("protected", Protected, SystemVerilog2005),
+        ("pull0", Pull0, Verilog1995),
+        ("pull1", Pull1, Verilog1995),
+        ("pulldown", PullDown, Verilog1995),
+        ("pullup", PullUp, Verilog1995),
+        (
+            "pulsestyle_ondetect",
+            PulseStyleOnDetect,
+            Verilog2001NoConfig,
+        ),
+        ("pulsestyle_onevent", PulseStyleOnEvent, Verilog2001NoConfig),
+        ("pure", Pure, SystemVerilog2005),
+        ("rand", Rand, SystemVerilog2005),
+        ("randc", RandC, SystemVerilog2005),
+        ("randcase", RandCase, SystemVerilog2005),
+        ("randsequence", RandSequence, SystemVerilog2005),
+        ("rcmos", Rcmos, Verilog1995),
+        ("real", Real, Verilog1995),
+        ("realtime", Realtime, Verilog1995),
+        ("ref", Ref, SystemVerilog2005),
+        ("reg", Reg, Verilog1995),
+        ("reject_on", RejectOn, SystemVerilog2009),
+        ("release", Release, Verilog1995),
+        ("repeat", Repeat, Verilog1995),
+        ("restrict", Restrict, SystemVerilog2009),
+        ("return", Return, SystemVerilog2005),
+        ("rnmos", Rnmos, Verilog1995),
+        ("rpmos", Rpmos, Verilog1995),
+        ("rtran", Rtran, Verilog1995),
+        ("rtranif0", RtranIf0, Verilog1995),
+        ("rtranif1", RtranIf1, Verilog1995),
+        ("s_always", SAlways, SystemVerilog2009),
+        ("s_eventually", SEventually, SystemVerilog2009),
+        ("s_nexttime", SNextTime, SystemVerilog2009),
+        ("s_until", SUntil, SystemVerilog2009),
+        ("s_until_with", SUntilWith, SystemVerilog2009),
+        ("scalared", Scalared, Verilog1995),
+        ("sequence", Sequence, SystemVerilog2005),
+        ("shortint", ShortInt, SystemVerilog2005),
+        ("shortreal", ShortReal, SystemVerilog2005),
+        ("showcancelled", ShowCancelled, Verilog2001NoConfig),
+        ("signed", Signed, Verilog2001NoConfig),
+        ("small", Small, Verilog1995),
+        ("soft", Soft, SystemVerilog2012),
+        ("solve", Solve, SystemVerilog2005),
+        ("specify", Specify, Verilog1995),
+        ("specparam", Specparam, Verilog1995),
+        ("static", Static, SystemVerilog2005),
+        ("string", String, SystemVerilog2005),
+        ("strong", Strong, SystemVerilog2009),
+        ("strong0", Strong0, Verilog1995),
+        ("strong1", Strong1, Verilog1995),
+        ("struct", Struct, SystemVerilog2005),
+        ("super", Super, SystemVerilog2005),
+        ("supply0", Supply0, Verilog1995),
+        ("supply1", Supply1, Verilog1995),
+        ("sync_accept_on", SyncAcceptOn, SystemVerilog2009),
+        ("sync_reject_on", SyncRejectOn, SystemVerilog2009),
+        ("table", Table, Verilog1995),
+        ("tagged", Tagged, SystemVerilog2005),
+        ("task", Task, Verilog1995),
+        ("this", This, SystemVerilog2005),
+        ("throughout", Throughout, SystemVerilog2005),
+        ("time", Time, Verilog1995),
+        ("timeprecision", TimePrecision, SystemVerilog2005),
+        ("timeunit", TimeUnit, SystemVerilog2005),
+        ("tran", Tran, Verilog1995),
+        ("tranif0", TranIf0, Verilog1995),
+        ("tranif1", TranIf1, Verilog1995),
+        ("tri", Tri, Verilog1995),
+        ("tri0", Tri0, Verilog1995),
+        ("tri1", Tri1, Verilog1995),
+        ("triand", TriAnd, Verilog1995),
+        ("trior", TriOr, Verilog1995),
+        ("trireg", TriReg, Verilog1995),
+        ("type", Type, SystemVerilog2005),
+        ("typedef", TypeDef, SystemVerilog2005),
+        ("union", Union, SystemVerilog2005),
+        ("unique", Unique, SystemVerilog2005),
+        ("unique0", Unique0, SystemVerilog2009),
+        ("unsigned", Unsigned, Verilog2001NoConfig),
+        ("until", Until, SystemVerilog2009),
+        ("until_with", UntilWith, SystemVerilog2009),
+        ("untyped", Untyped, SystemVerilog2009),
+        ("use", Use, Verilog2001),
+        ("uwire", Uwire, Verilog2005),
+        ("var", Var, SystemVerilog2005),
+        ("vectored", Vectored, Verilog1995),
+        ("virtual", Virtual, SystemVerilog2005),
+        ("void", Void, SystemVerilog2005),
+        ("wait", Wait, Verilog1995),
+        ("wait_order", WaitOrder, SystemVerilog2005),
+        ("wand", Wand, Verilog1995),
+        ("weak", Weak, SystemVerilog2009),
+        ("weak0", Weak0, Verilog1995),
+        ("weak1", Weak1, Verilog1995),
+        ("while", While, Verilog1995),
+        ("wildcard", Wildcard, SystemVerilog2005),
+        ("wire", Wire, Verilog1995),
+        ("with", With, SystemVerilog2005),
+        ("within", Within, SystemVerilog2005),
+        ("wor", Wor, Verilog1995),
+        ("xnor", Xnor, Verilog1995),
+        ("xor", Xor, Verilog1995),
+    ]
+};
+
+fn keyword_map() -> HashMap<&'static str, (Token, KeywordVersion)> {
+    KEYWORDS
+        .iter()
+        .map(|(text, token, since)| (*text, (*token, *since)))
+        .collect()
+}
+
+lazy_static! {
+    static ref KEYWORD: HashMap<&'static str, (Token, KeywordVersion)> = keyword_map();
+}
+
+impl Token {
+    /// Source text of a keyword token, `None` for other tokens
+    pub fn keyword(&self) -> Option<&'static str> {
+        KEYWORDS
+            .iter()
+            .find(|(_, token, _)| token == self)
+            .map(|(text, _, _)| *text)
+    }
+}
+
+/// Cursor at a grapheme boundary
+///
+/// `StrCursor::new_at_left_of_byte_pos` reads the byte at the position, which
+/// is out of bounds at the end of the input.
+fn cursor_at(input: &str, byte_pos: usize) -> StrCursor<'_> {
+    if byte_pos >= input.len() {
+        StrCursor::new_at_end(input)
+    } else {
+        StrCursor::new_at_left_of_byte_pos(input, byte_pos)
+    }
 }
 
 fn is_newline(ch: char) -> bool {
@@ -371,30 +855,63 @@ fn is_newline(ch: char) -> bool {
 
 impl<'a> Lexer<'a> {
     pub fn lex(input: &'a str) -> Lexer<'a> {
+        Self::lex_with_options(input, LexerOptions::default())
+    }
+
+    pub fn lex_with_options(input: &'a str, options: LexerOptions) -> Lexer<'a> {
         let mut lexer = Lexer {
             input,
             cursor: StrCursor::new_at_start(input),
-            loc: Location { row: 0, col: 0 },
+            loc: Location::default(),
+            file: options.file,
+            keywords: vec![options.keywords],
+            macro_text: options.macro_text,
             tokens: vec![],
             diag: vec![],
         };
         lexer.work();
+        lexer.fill_offsets();
         lexer
     }
 
+    /// Fill in byte offsets of locations from their rows and columns
+    fn fill_offsets(&mut self) {
+        let index = LineIndex::new(self.input);
+        let fill = |loc: &mut Location| loc.offset = index.location_offset(loc.row, loc.col);
+        for token in &mut self.tokens {
+            fill(&mut token.span.from);
+            fill(&mut token.span.to);
+        }
+        for diag in &mut self.diag {
+            fill(&mut diag.pos.from);
+            fill(&mut diag.pos.to);
+        }
+        fill(&mut self.loc);
+    }
+
     fn err(&mut self, from: Location, to: Location, msg: Message) {
         self.diag.push(Diagnostic {
-            pos: Span { from, to },
+            pos: Span {
+                file: self.file,
+                from,
+                to,
+            },
             msg,
             severity: Severity::Error,
+            notes: vec![],
         });
     }
 
     fn warn(&mut self, from: Location, to: Location, msg: Message) {
         self.diag.push(Diagnostic {
-            pos: Span { from, to },
+            pos: Span {
+                file: self.file,
+                from,
+                to,
+            },
             msg,
             severity: Severity::Warning,
+            notes: vec![],
         });
     }
 
@@ -422,8 +939,13 @@ impl<'a> Lexer<'a> {
                         // end of line
                         self.tokens.push(ParsedToken {
                             token: Token::Comment,
-                            span: Span { from, to },
-                            text: orig_cursor.slice_between(cursor).unwrap(),
+                            span: Span {
+                                file: self.file,
+                                from,
+                                to,
+                            },
+                            text: orig_cursor.slice_between(cursor).unwrap().into(),
+                            expansion: None,
                         });
                         self.cursor = cursor;
                         return true;
@@ -437,10 +959,16 @@ impl<'a> Lexer<'a> {
                                 // end of comment
                                 self.tokens.push(ParsedToken {
                                     token: Token::Comment,
-                                    span: Span { from, to: self.loc },
-                                    text: orig_cursor.slice_between(next).unwrap(),
+                                    span: Span {
+                                        file: self.file,
+                                        from,
+                                        to: self.loc,
+                                    },
+                                    text: orig_cursor.slice_between(next).unwrap().into(),
+                                    expansion: None,
                                 });
                                 self.cursor = next;
+                                self.loc.col += 1;
                                 return true;
                             } else if is_newline(gc.base_char()) {
                                 self.loc.row += 1;
@@ -454,13 +982,18 @@ impl<'a> Lexer<'a> {
 
                         // not closed until end of input
                         let to = Location {
-                            row: self.loc.row,
                             col: self.loc.col - 1,
+                            ..self.loc
                         };
                         self.tokens.push(ParsedToken {
                             token: Token::Comment,
-                            span: Span { from, to },
-                            text: orig_cursor.slice_between(cursor).unwrap(),
+                            span: Span {
+                                file: self.file,
+                                from,
+                                to,
+                            },
+                            text: orig_cursor.slice_between(cursor).unwrap().into(),
+                            expansion: None,
                         });
                         self.err(from, self.loc, Message::MultilineCommentUnclosed);
                         self.cursor = cursor;
@@ -475,112 +1008,101 @@ impl<'a> Lexer<'a> {
     // A.8.7 Numbers
     fn number(&mut self) -> bool {
         lazy_static! {
+            // white space is allowed between size, base and value, 5.7.1
             static ref RE: Regex = Regex::new(&format!(
-                "^({}|{}|{}|{}|{}|{}|{}|{}|{})",
+                "^({}|{}|{}|{}|{}|{}|{}|{}|{}|{})",
                 // octal_number
-                "([1-9][0-9_]*)?'[sS]?[oO][0-7xXzZ][0-7xXzZ_]*", // [ size ] octal_base octal_value
+                "([1-9][0-9_]*\\s*)?'[sS]?[oO]\\s*[0-7xXzZ][0-7xXzZ_]*", // [ size ] octal_base octal_value
                 // binary_number
-                "([1-9][0-9_]*)?'[sS]?[bB][01xXzZ][01xXzZ_]*", // [ size ] binary_base binary_value
+                "([1-9][0-9_]*\\s*)?'[sS]?[bB]\\s*[01xXzZ][01xXzZ_]*", // [ size ] binary_base binary_value
                 // hex_number
-                "([1-9][0-9_]*)?'[sS]?[hH][0-9a-fA-FxXzZ][0-9a-fA-FxXzZ_]*", // [ size ] hex_base hex_value
+                "([1-9][0-9_]*\\s*)?'[sS]?[hH]\\s*[0-9a-fA-FxXzZ][0-9a-fA-FxXzZ_]*", // [ size ] hex_base hex_value
                 // real_number
                 "[0-9][0-9_]*(\\.[0-9][0-9_]*)?[eE][+-]?[0-9][0-9_]*", // unsigned_number [ . unsigned_number ] exp [ sign ] unsigned_number
                 "[0-9][0-9_]*\\.[0-9][0-9_]*", // unsigned_number . unsigned_number
                 // decimal_number
-                "([1-9][0-9_]*)?'[sS]?[dD][0-9][0-9_]*", // [ size ] decimal_base unsigned_number
-                "([1-9][0-9_]*)?'[sS]?[dD][xX]_*",       // [ size ] decimal_base x_digit { _ }
-                "([1-9][0-9_]*)?'[sS]?[dD][zZ?]_*",      // [ size ] decimal_base z_digit { _ }
-                "[0-9][0-9_]*",                         // unsigned_number
+                // a sign before the value is accepted like other tools do, as in `'sd-5`
+                "([1-9][0-9_]*\\s*)?'[sS]?[dD]\\s*[+-]?[0-9][0-9_]*", // [ size ] decimal_base unsigned_number
+                "([1-9][0-9_]*\\s*)?'[sS]?[dD]\\s*[xX]_*",       // [ size ] decimal_base x_digit { _ }
+                "([1-9][0-9_]*\\s*)?'[sS]?[dD]\\s*[zZ?]_*",      // [ size ] decimal_base z_digit { _ }
+                "[0-9][0-9_]*",                                     // unsigned_number
+                // unbased_unsized_literal
+                "'[01xXzZ]",
             ))
             .unwrap();
+            // A.8.4 time_literal
+            static ref TIME_RE: Regex =
+                Regex::new("^([0-9][0-9_]*(\\.[0-9][0-9_]*)?(s|ms|us|ns|ps|fs)|1step)\\b")
+                    .unwrap();
         }
         let s = self.cursor.slice_after();
+        if let Some(m) = TIME_RE.find(s) {
+            self.push_match(Token::TimeLiteral, m.end());
+            return true;
+        }
         if let Some(m) = RE.find(s) {
-            assert_eq!(m.start(), 0);
-            let from = self.loc;
-            let orig_pos = self.cursor.byte_pos();
-            let orig_cursor = self.cursor;
-            let new_pos = orig_pos + m.end();
-            let new_cursor = StrCursor::new_at_left_of_byte_pos(self.input, new_pos);
-            let mut cursor = self.cursor;
-            while let Some((_, next)) = cursor.next() {
-                if next == new_cursor {
-                    self.tokens.push(ParsedToken {
-                        token: Token::Number,
-                        span: Span { from, to: self.loc },
-                        text: orig_cursor.slice_between(next).unwrap(),
-                    });
-                    self.loc.col += 1;
-                    self.cursor = next;
-                    return true;
-                }
-                self.loc.col += 1;
-                cursor = next;
-            }
+            self.push_match(Token::Number, m.end());
+            return true;
         }
         false
     }
 
-    // A.8.6 Operators
-    fn operator(&mut self) -> bool {
-        let mut first = ' ';
-        let mut second = ' ';
-        let mut third = ' ';
-        if let Some((gc, next)) = self.cursor.next() {
-            first = gc.base_char();
-            if let Some((gc, next)) = next.next() {
-                second = gc.base_char();
-                if let Some((gc, _next)) = next.next() {
-                    third = gc.base_char();
-                }
+    /// Push a token of the next `len` bytes, which may span lines
+    fn push_match(&mut self, token: Token, len: usize) {
+        let from = self.loc;
+        let orig_cursor = self.cursor;
+        let new_cursor = cursor_at(self.input, orig_cursor.byte_pos() + len);
+        let mut to = self.loc;
+        let mut cursor = self.cursor;
+        while let Some((gc, next)) = cursor.next() {
+            to = self.loc;
+            if is_newline(gc.base_char()) {
+                self.loc.row += 1;
+                self.loc.col = 0;
+            } else {
+                self.loc.col += 1;
             }
-        }
-        let (token, len) = match (first, second, third) {
-            ('=', '=', '=') => (Token::OpCaseEqual, 3),
-            ('!', '=', '=') => (Token::OpCaseInequal, 3),
-            ('>', '>', '>') => (Token::OpArithRightShift, 3),
-            ('<', '<', '<') => (Token::OpArithLeftShift, 3),
-            ('~', '&', _) => (Token::OpNand, 2),
-            ('~', '|', _) => (Token::OpNor, 2),
-            ('~', '^', _) => (Token::OpBitEquiv2, 2),
-            ('^', '~', _) => (Token::OpBitEquiv1, 2),
-            ('=', '=', _) => (Token::OpEqual, 2),
-            ('!', '=', _) => (Token::OpInequal, 2),
-            ('&', '&', _) => (Token::OpAnd, 2),
-            ('|', '|', _) => (Token::OpOr, 2),
-            ('*', '*', _) => (Token::OpPow, 2),
-            ('<', '=', _) => (Token::OpLessEqual, 2),
-            ('>', '=', _) => (Token::OpGreaterEqual, 2),
-            ('>', '>', _) => (Token::OpRightShift, 2),
-            ('<', '<', _) => (Token::OpLeftShift, 2),
-            ('+', _, _) => (Token::OpPlus, 1),
-            ('-', _, _) => (Token::OpMinus, 1),
-            ('!', _, _) => (Token::OpNot, 1),
-            ('&', _, _) => (Token::OpBitAnd, 1),
-            ('|', _, _) => (Token::OpBitOr, 1),
-            ('^', _, _) => (Token::OpBitXor, 1),
-            ('*', _, _) => (Token::OpMultiply, 1),
-            ('/', _, _) => (Token::OpDivide, 1),
-            ('%', _, _) => (Token::OpMod, 1),
-            ('<', _, _) => (Token::OpLessThan, 1),
-            ('>', _, _) => (Token::OpGreaterThan, 1),
-            ('~', _, _) => (Token::OpBitNeg, 1),
-            _ => {
-                return false;
+            cursor = next;
+            if next == new_cursor {
+                break;
             }
+        }
+        self.tokens.push(ParsedToken {
+            token,
+            span: Span {
+                file: self.file,
+                from,
+                to,
+            },
+            text: orig_cursor.slice_between(cursor).unwrap().into(),
+            expansion: None,
+        });
+        self.cursor = cursor;
+    }
+
+    // A.8.6 Operators, and delimiters longer than one character
+    fn operator(&mut self) -> bool {
+        let rest = self.cursor.slice_after();
+        // maximal munch, OPERATORS is sorted by length
+        let (text, token) = match OPERATORS.iter().find(|(text, _)| rest.starts_with(text)) {
+            Some(&(text, token)) => (text, token),
+            None => return false,
         };
+        let len = text.len();
+        let cursor = cursor_at(self.input, self.cursor.byte_pos() + len);
         let to = Location {
-            row: self.loc.row,
             col: self.loc.col + len - 1,
+            ..self.loc
         };
-        let mut cursor = self.cursor;
-        for _ in 0..len {
-            cursor = cursor.next().unwrap().1;
-        }
         self.tokens.push(ParsedToken {
-            span: Span { from: self.loc, to },
+            span: Span {
+                file: self.file,
+                from: self.loc,
+                to,
+            },
             token,
-            text: self.cursor.slice_between(cursor).unwrap(),
+            text: self.cursor.slice_between(cursor).unwrap().into(),
+            expansion: None,
         });
         self.cursor = cursor;
         self.loc.col += len;
@@ -589,58 +1111,96 @@ impl<'a> Lexer<'a> {
 
     // 2.6 Strings
     fn string(&mut self) -> bool {
-        if let Some((gc, next)) = self.cursor.next() {
-            if gc.base_char() == '"' {
-                let mut cursor = next;
-                let mut escaping = false;
-                let from = self.loc;
-                let mut loc = self.loc;
-                let mut escape_loc = Location { row: 0, col: 0 };
-                loc.col += 1;
-                while let Some((gc, next)) = cursor.next() {
-                    match (gc.base_char(), escaping) {
-                        ('\\', false) => {
-                            escaping = true;
-                            escape_loc = loc;
-                        }
-                        ('n', true) | ('t', true) | ('\\', true) | ('"', true) => {
-                            escaping = false;
-                        }
-                        (ch, true) if ch >= '0' && ch <= '7' => {
-                            escaping = false;
-                        }
-                        (ch, true) => {
-                            // bad escape character
-                            self.warn(escape_loc, loc, Message::UnrecognizedEscapeCharacter(ch));
-                            escaping = false;
-                        }
-                        ('"', false) => {
-                            // end
-                            self.tokens.push(ParsedToken {
-                                span: Span { from, to: loc },
-                                token: Token::StringLiteral,
-                                text: self.cursor.slice_between(next).unwrap(),
-                            });
-                            self.cursor = next;
-                            self.loc = loc;
-                            self.loc.col += 1;
-                            return true;
-                        }
-                        _ => {}
-                    }
-                    loc.col += 1;
-                    cursor = next;
+        let rest = self.cursor.slice_after();
+        if !rest.starts_with('"') {
+            return false;
+        }
+        // 1800-2023 triple-quoted strings may contain newlines and quotes
+        let triple = rest.starts_with("\"\"\"");
+        let from = self.loc;
+        let mut to = from;
+        let mut loc = from;
+        let mut cursor = self.cursor;
+        for _ in 0..if triple { 3 } else { 1 } {
+            cursor = cursor.next().unwrap().1;
+            to = loc;
+            loc.col += 1;
+        }
+
+        let mut escape_loc = None;
+        while let Some((gc, next)) = cursor.next() {
+            let ch = gc.base_char();
+            if let Some(escape_loc) = escape_loc.take() {
+                match ch {
+                    'n' | 't' | '\\' | '"' | 'v' | 'f' | 'a' | '0'..='7' => {}
+                    'x' if next
+                        .slice_after()
+                        .starts_with(|ch: char| ch.is_ascii_hexdigit()) => {}
+                    // line continuation
+                    ch if is_newline(ch) => {}
+                    ch => self.warn(escape_loc, loc, Message::UnrecognizedEscapeCharacter(ch)),
+                }
+            } else if ch == '\\' {
+                escape_loc = Some(loc);
+            } else if ch == '"' && (!triple || next.slice_after().starts_with("\"\"")) {
+                // end
+                let mut end = next;
+                to = loc;
+                if triple {
+                    end = end.next().unwrap().1.next().unwrap().1;
+                    to.col += 2;
                 }
+                self.tokens.push(ParsedToken {
+                    span: Span {
+                        file: self.file,
+                        from,
+                        to,
+                    },
+                    token: Token::StringLiteral,
+                    text: self.cursor.slice_between(end).unwrap().into(),
+                    expansion: None,
+                });
+                self.cursor = end;
+                self.loc = to;
+                self.loc.col += 1;
+                return true;
+            } else if is_newline(ch) && !triple {
+                break;
             }
+            to = loc;
+            if is_newline(ch) {
+                loc.row += 1;
+                loc.col = 0;
+            } else {
+                loc.col += 1;
+            }
+            cursor = next;
         }
-        false
+
+        // not closed until the end of the line or input
+        self.err(from, to, Message::UnterminatedString);
+        self.tokens.push(ParsedToken {
+            span: Span {
+                file: self.file,
+                from,
+                to,
+            },
+            token: Token::StringLiteral,
+            text: self.cursor.slice_between(cursor).unwrap().into(),
+            expansion: None,
+        });
+        self.cursor = cursor;
+        self.loc = loc;
+        true
     }
 
     // 2.7 Identifiers, keywords, and system names
+    /// Keyword set the input started with
+    pub(crate) fn initial_keywords(&self) -> KeywordVersion {
+        self.keywords[0]
+    }
+
     fn identifier_keyword(&mut self) -> bool {
-        lazy_static! {
-            static ref KEYWORD: HashMap<String, Token> = keyword_map();
-        }
         let mut cursor = self.cursor;
         let from = self.loc;
         let mut loc = self.loc;
@@ -663,19 +1223,63 @@ impl<'a> Lexer<'a> {
         self.loc = loc;
         loc.col -= 1;
         self.cursor = cursor;
-        let token = *KEYWORD.get(slice).unwrap_or(&Token::Identifier);
+        let token = match KEYWORD.get(slice) {
+            Some((token, since)) if since <= self.keywords.last().unwrap() => *token,
+            _ => Token::Identifier,
+        };
 
         self.tokens.push(ParsedToken {
-            span: Span { from, to: loc },
+            span: Span {
+                file: self.file,
+                from,
+                to: loc,
+            },
             token,
-            text: slice,
+            text: slice.into(),
+            expansion: None,
         });
         true
     }
 
+    // 5.6.3 System tasks and system functions
+    fn system_identifier(&mut self) -> bool {
+        let rest = self.cursor.slice_after();
+        let len = rest[1..]
+            .find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_' && ch != '$')
+            .unwrap_or(rest.len() - 1);
+        if len == 0 {
+            // a single `$` is a delimiter
+            return false;
+        }
+        let len = len + 1;
+        let cursor = cursor_at(self.input, self.cursor.byte_pos() + len);
+        let to = Location {
+            col: self.loc.col + len - 1,
+            ..self.loc
+        };
+        self.tokens.push(ParsedToken {
+            span: Span {
+                file: self.file,
+                from: self.loc,
+                to,
+            },
+            token: Token::SystemIdentifier,
+            text: self.cursor.slice_between(cursor).unwrap().into(),
+            expansion: None,
+        });
+        self.cursor = cursor;
+        self.loc.col += len;
+        true
+    }
+
     // 5.6.1 Escaped identifiers
     fn escaped_identifier(&mut self) -> bool {
         let mut cursor = self.cursor;
+        // a backslash alone is not an identifier
+        match cursor.next().and_then(|(_, next)| next.next()) {
+            Some((gc, _)) if !gc.base_char().is_whitespace() => {}
+            _ => return false,
+        }
         let from = self.loc;
         let mut loc = self.loc;
         while let Some((gc, next)) = cursor.next() {
@@ -699,9 +1303,14 @@ impl<'a> Lexer<'a> {
         self.cursor = cursor;
 
         self.tokens.push(ParsedToken {
-            span: Span { from, to: loc },
+            span: Span {
+                file: self.file,
+                from,
+                to: loc,
+            },
             token: Token::Identifier,
-            text: slice,
+            text: slice.into(),
+            expansion: None,
         });
         true
     }
@@ -724,15 +1333,18 @@ impl<'a> Lexer<'a> {
                 '@' => Token::At,
                 '?' => Token::Question,
                 '\'' => Token::Apostrophe,
+                '$' => Token::Dollar,
                 _ => return false,
             };
             self.tokens.push(ParsedToken {
                 span: Span {
+                    file: self.file,
                     from: self.loc,
                     to: self.loc,
                 },
                 token,
-                text: self.cursor.slice_between(next).unwrap(),
+                text: self.cursor.slice_between(next).unwrap().into(),
+                expansion: None,
             });
             self.cursor = next;
             self.loc.col += 1;
@@ -741,13 +1353,45 @@ impl<'a> Lexer<'a> {
         false
     }
 
+    // 22.5.1 `" `\`" `` in macro text
+    fn macro_operator(&mut self) -> bool {
+        let rest = self.cursor.slice_after();
+        let (token, len) = if rest.starts_with("`\"") {
+            (Token::MacroQuote, 2)
+        } else if rest.starts_with("`\\`\"") {
+            (Token::MacroEscapedQuote, 4)
+        } else if rest.starts_with("``") {
+            (Token::MacroPaste, 2)
+        } else {
+            return false;
+        };
+        let cursor = cursor_at(self.input, self.cursor.byte_pos() + len);
+        let to = Location {
+            col: self.loc.col + len - 1,
+            ..self.loc
+        };
+        self.tokens.push(ParsedToken {
+            span: Span {
+                file: self.file,
+                from: self.loc,
+                to,
+            },
+            token,
+            text: self.cursor.slice_between(cursor).unwrap().into(),
+            expansion: None,
+        });
+        self.cursor = cursor;
+        self.loc.col += len;
+        true
+    }
+
     // 2.7.5 Compiler directives
     fn directive(&mut self) -> bool {
         if let Some((gc, next)) = self.cursor.next() {
             if gc.base_char() == '`' {
+                let from = self.loc;
                 self.loc.col += 1;
                 let mut cursor = next;
-                let from = self.loc;
                 let mut loc = self.loc;
                 while let Some((gc, next)) = cursor.next() {
                     match gc.base_char() {
@@ -770,20 +1414,152 @@ impl<'a> Lexer<'a> {
                 self.cursor = cursor;
 
                 self.tokens.push(ParsedToken {
-                    span: Span { from, to: loc },
+                    span: Span {
+                        file: self.file,
+                        from,
+                        to: loc,
+                    },
                     token: Token::Directive,
-                    text: slice,
+                    text: slice.into(),
+                    expansion: None,
                 });
+                match slice {
+                    "`begin_keywords" => self.begin_keywords(from),
+                    "`end_keywords" => self.end_keywords(from, loc),
+                    "`define" => self.macro_text(),
+                    _ => {}
+                }
                 return true;
             }
         }
         false
     }
 
+    // 22.5.1 `define text_macro_name macro_text
+    // The rest of the line, including backslash-newline continuations, is
+    // kept as a single MacroText token for the preprocessor.
+    fn macro_text(&mut self) {
+        self.skip_blank();
+        let from = self.loc;
+        let mut to = self.loc;
+        let mut loc = self.loc;
+        let mut cursor = self.cursor;
+        let mut end = self.cursor;
+        let mut in_string = false;
+        let mut prev_ch = ' ';
+        while let Some((gc, next)) = cursor.next() {
+            let ch = gc.base_char();
+            if is_newline(ch) {
+                if prev_ch != '\\' {
+                    break;
+                }
+                loc.row += 1;
+                loc.col = 0;
+            } else {
+                if ch == '"' && prev_ch != '\\' {
+                    in_string = !in_string;
+                } else if ch == '/' && !in_string {
+                    if let Some((gc, _)) = next.next() {
+                        if gc.base_char() == '/' {
+                            // one line comment ends macro text
+                            break;
+                        }
+                    }
+                }
+                if !ch.is_whitespace() {
+                    end = next;
+                    to = loc;
+                }
+                loc.col += 1;
+            }
+            prev_ch = ch;
+            cursor = next;
+        }
+
+        if end != self.cursor {
+            self.tokens.push(ParsedToken {
+                span: Span {
+                    file: self.file,
+                    from,
+                    to,
+                },
+                token: Token::MacroText,
+                text: self.cursor.slice_between(end).unwrap().into(),
+                expansion: None,
+            });
+            self.cursor = end;
+            self.loc = Location {
+                col: to.col + 1,
+                ..to
+            };
+        }
+    }
+
+    // 22.5.1 backslash-newline continues macro text on the next line
+    fn line_continuation(&mut self) -> bool {
+        if !self.macro_text {
+            return false;
+        }
+        if let Some((_, next)) = self.cursor.next() {
+            if let Some((gc, next)) = next.next() {
+                if is_newline(gc.base_char()) {
+                    self.loc.row += 1;
+                    self.loc.col = 0;
+                    self.cursor = next;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn skip_blank(&mut self) {
+        while let Some((gc, next)) = self.cursor.next() {
+            if gc.base_char() != ' ' && gc.base_char() != '\t' {
+                break;
+            }
+            self.loc.col += 1;
+            self.cursor = next;
+        }
+    }
+
+    // 22.14 `begin_keywords "version_specifier"
+    fn begin_keywords(&mut self, from: Location) {
+        self.skip_blank();
+        let current = *self.keywords.last().unwrap();
+        if self.string() {
+            let token = self.tokens.last().unwrap().clone();
+            let specifier = token.text.trim_matches('"');
+            if let Some(version) = KeywordVersion::from_specifier(specifier) {
+                self.keywords.push(version);
+            } else {
+                self.err(
+                    token.span.from,
+                    token.span.to,
+                    Message::UnknownKeywordVersion(specifier.to_owned()),
+                );
+                // keep `end_keywords balanced
+                self.keywords.push(current);
+            }
+        } else {
+            self.err(from, self.loc, Message::MissingKeywordVersion);
+            self.keywords.push(current);
+        }
+    }
+
+    // 22.14 `end_keywords
+    fn end_keywords(&mut self, from: Location, to: Location) {
+        if self.keywords.len() > 1 {
+            self.keywords.pop();
+        } else {
+            self.err(from, to, Message::UnmatchedEndKeywords);
+        }
+    }
+
     fn work(&mut self) {
         while let Some((gc, next)) = self.cursor.next() {
             match gc.base_char() {
-                ch @ _ if ch.is_whitespace() => {
+                ch if ch.is_whitespace() => {
                     if is_newline(ch) {
                         self.loc.row += 1;
                         self.loc.col = 0;
@@ -798,6 +1574,7 @@ impl<'a> Lexer<'a> {
                     continue;
                 }
                 '+' | '-' | '!' | '~' | '&' | '|' | '^' | '*' | '/' | '%' | '=' | '<' | '>'
+                | '#' | ':' | '.' | '\''
                     if self.operator() =>
                 {
                     continue;
@@ -808,15 +1585,24 @@ impl<'a> Lexer<'a> {
                 'a'..='z' | 'A'..='Z' | '_' if self.identifier_keyword() => {
                     continue;
                 }
+                '$' if self.system_identifier() => {
+                    continue;
+                }
+                '\\' if self.line_continuation() => {
+                    continue;
+                }
                 '\\' if self.escaped_identifier() => {
                     continue;
                 }
                 '#' | '(' | ')' | '[' | ']' | '{' | '}' | ':' | ',' | ';' | '.' | '=' | '@'
-                | '?' | '\''
+                | '?' | '\'' | '$'
                     if self.delimiter() =>
                 {
                     continue;
                 }
+                '`' if self.macro_operator() => {
+                    continue;
+                }
                 '`' if self.directive() => {
                     continue;
                 }
@@ -837,35 +1623,122 @@ mod tests {
     #[test]
     fn unexpected_char() {
         let lexer = Lexer::lex("é和é是不一样的");
-        assert!(lexer.diag.len() > 0);
+        assert!(!lexer.diag.is_empty());
     }
 
     #[test]
     fn comment() {
         let lexer = Lexer::lex("// woc woc\nsomething // abcde");
-        assert!(lexer.tokens.len() > 0);
+        assert!(!lexer.tokens.is_empty());
         assert_eq!(lexer.tokens[0].text, "// woc woc");
 
         let lexer = Lexer::lex("/* woc woc\nsomething */");
         assert_eq!(lexer.tokens.len(), 1);
         assert_eq!(lexer.tokens[0].text, "/* woc woc\nsomething */");
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 1, col: 11 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 1,
+                col: 11,
+                offset: 22
+            }
+        );
 
         let lexer = Lexer::lex("/* not closed\ncomment ");
         assert_eq!(lexer.tokens[0].text, "/* not closed\ncomment ");
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 1, col: 7 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 1,
+                col: 7,
+                offset: 21
+            }
+        );
         assert_eq!(lexer.diag.len(), 1);
     }
 
+    #[test]
+    fn byte_offset() {
+        let lexer = Lexer::lex("// é和\r\n  `define");
+        assert_eq!(lexer.tokens[1].text, "`define");
+        assert_eq!(
+            lexer.tokens[1].span.from,
+            Location {
+                row: 1,
+                col: 2,
+                offset: 12
+            }
+        );
+        assert_eq!(lexer.tokens[1].span.to.offset, 18);
+        assert_eq!(lexer.tokens[0].span.to.offset, 5);
+    }
+
+    #[test]
+    fn block_comment_offset() {
+        for input in [
+            "/* c */ module top(input a);",
+            "/* é\r\n c */module top(input a);",
+            "/* a\n */ /* b\n\n c */ module top(input a);",
+        ] {
+            let lexer = Lexer::lex(input);
+            for token in &lexer.tokens {
+                assert_eq!(
+                    &input[token.span.from.offset..token.span.from.offset + token.text.len()],
+                    token.text,
+                    "{:?}",
+                    token
+                );
+            }
+            let module = lexer
+                .tokens
+                .iter()
+                .find(|t| t.token == Token::Module)
+                .unwrap();
+            let last = lexer.tokens.last().unwrap();
+            assert_eq!(
+                &input[module.span.from.offset..=last.span.to.offset],
+                "module top(input a);"
+            );
+        }
+    }
+
     #[test]
     fn crlf_newline() {
         let lexer = Lexer::lex("/* woc woc\r\nsomething */");
         assert_eq!(lexer.tokens.len(), 1);
         assert_eq!(lexer.tokens[0].text, "/* woc woc\r\nsomething */");
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 1, col: 11 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 1,
+                col: 11,
+                offset: 23
+            }
+        );
     }
 
     #[test]
@@ -873,20 +1746,62 @@ mod tests {
         let lexer = Lexer::lex("1234");
         assert_eq!(lexer.tokens.len(), 1);
         assert_eq!(lexer.tokens[0].text, "1234");
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 3 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 3,
+                offset: 3
+            }
+        );
 
         let lexer = Lexer::lex("1234_5678 ");
         assert_eq!(lexer.tokens.len(), 1);
         assert_eq!(lexer.tokens[0].text, "1234_5678");
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 8 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 8,
+                offset: 8
+            }
+        );
 
         let lexer = Lexer::lex("  123'sh111bbb ");
         assert_eq!(lexer.tokens.len(), 1);
         assert_eq!(lexer.tokens[0].text, "123'sh111bbb");
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 2 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 13 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 2,
+                offset: 2
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 13,
+                offset: 13
+            }
+        );
 
         let lexer = Lexer::lex("1.0 1.0e+30");
         assert_eq!(lexer.tokens.len(), 2);
@@ -894,61 +1809,645 @@ mod tests {
         assert_eq!(lexer.tokens[1].text, "1.0e+30");
     }
 
+    #[test]
+    fn number_forms() {
+        let tokens = |text| -> Vec<(Token, std::string::String)> {
+            let lexer = Lexer::lex(text);
+            assert!(lexer.diag.is_empty(), "{:?}", lexer.diag);
+            lexer
+                .tokens
+                .iter()
+                .map(|token| (token.token, token.text.to_string()))
+                .collect()
+        };
+        use Token::*;
+        assert_eq!(
+            tokens("'0 '1 'x 'Z"),
+            vec![
+                (Number, "'0".to_owned()),
+                (Number, "'1".to_owned()),
+                (Number, "'x".to_owned()),
+                (Number, "'Z".to_owned()),
+            ]
+        );
+        assert_eq!(
+            tokens("{'1, a}"),
+            vec![
+                (LBraces, "{".to_owned()),
+                (Number, "'1".to_owned()),
+                (Comma, ",".to_owned()),
+                (Identifier, "a".to_owned()),
+                (RBraces, "}".to_owned()),
+            ]
+        );
+        assert_eq!(
+            tokens("10ns 1.5ps 1step 2s 100fs"),
+            vec![
+                (TimeLiteral, "10ns".to_owned()),
+                (TimeLiteral, "1.5ps".to_owned()),
+                (TimeLiteral, "1step".to_owned()),
+                (TimeLiteral, "2s".to_owned()),
+                (TimeLiteral, "100fs".to_owned()),
+            ]
+        );
+        // not time literals
+        assert_eq!(
+            tokens("10nsx 2step"),
+            vec![
+                (Number, "10".to_owned()),
+                (Identifier, "nsx".to_owned()),
+                (Number, "2".to_owned()),
+                (Identifier, "step".to_owned()),
+            ]
+        );
+        assert_eq!(
+            tokens("8 'h FF 4'sb 1010 'd 3"),
+            vec![
+                (Number, "8 'h FF".to_owned()),
+                (Number, "4'sb 1010".to_owned()),
+                (Number, "'d 3".to_owned()),
+            ]
+        );
+        assert_eq!(
+            tokens("'sd-5 - 4'd+3"),
+            vec![
+                (Number, "'sd-5".to_owned()),
+                (OpMinus, "-".to_owned()),
+                (Number, "4'd+3".to_owned()),
+            ]
+        );
+        assert_eq!(
+            tokens("a = 8'(b);"),
+            vec![
+                (Identifier, "a".to_owned()),
+                (Equal, "=".to_owned()),
+                (Number, "8".to_owned()),
+                (Apostrophe, "'".to_owned()),
+                (LParen, "(".to_owned()),
+                (Identifier, "b".to_owned()),
+                (RParen, ")".to_owned()),
+                (Semicolon, ";".to_owned()),
+            ]
+        );
+
+        let lexer = Lexer::lex("16\n  'h\n ab x");
+        assert_eq!(lexer.tokens[0].text, "16\n  'h\n ab");
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 2,
+                col: 2,
+                offset: 10
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.from,
+            Location {
+                row: 2,
+                col: 4,
+                offset: 12
+            }
+        );
+    }
+
     #[test]
     fn operator() {
         let lexer = Lexer::lex("+~|<<<^~-");
         assert_eq!(lexer.tokens.len(), 5);
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[1].span.from, Location { row: 0, col: 1 });
-        assert_eq!(lexer.tokens[1].span.to, Location { row: 0, col: 2 });
-        assert_eq!(lexer.tokens[2].span.from, Location { row: 0, col: 3 });
-        assert_eq!(lexer.tokens[2].span.to, Location { row: 0, col: 5 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.from,
+            Location {
+                row: 0,
+                col: 1,
+                offset: 1
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.to,
+            Location {
+                row: 0,
+                col: 2,
+                offset: 2
+            }
+        );
+        assert_eq!(
+            lexer.tokens[2].span.from,
+            Location {
+                row: 0,
+                col: 3,
+                offset: 3
+            }
+        );
+        assert_eq!(
+            lexer.tokens[2].span.to,
+            Location {
+                row: 0,
+                col: 5,
+                offset: 5
+            }
+        );
+    }
+
+    #[test]
+    fn operator_maximal_munch() {
+        let tokens = |text| -> Vec<Token> {
+            let lexer = Lexer::lex(text);
+            assert!(lexer.diag.is_empty(), "{:?}", lexer.diag);
+            lexer.tokens.iter().map(|token| token.token).collect()
+        };
+        use Token::*;
+        assert_eq!(tokens("a<=b"), vec![Identifier, OpLessEqual, Identifier]);
+        assert_eq!(
+            tokens("a<<<=b"),
+            vec![Identifier, OpArithLeftShiftAssign, Identifier]
+        );
+        assert_eq!(
+            tokens("a<<=b"),
+            vec![Identifier, OpLeftShiftAssign, Identifier]
+        );
+        assert_eq!(
+            tokens("a>>>=b>>=c"),
+            vec![
+                Identifier,
+                OpArithRightShiftAssign,
+                Identifier,
+                OpRightShiftAssign,
+                Identifier
+            ]
+        );
+        assert_eq!(tokens("#1##2"), vec![Sharp, Number, DoubleSharp, Number]);
+        assert_eq!(
+            tokens("a#-#b#=#c"),
+            vec![
+                Identifier,
+                SharpMinusSharp,
+                Identifier,
+                SharpEqualSharp,
+                Identifier
+            ]
+        );
+        assert_eq!(
+            tokens("a++ --b"),
+            vec![Identifier, OpIncrement, OpDecrement, Identifier]
+        );
+        assert_eq!(
+            tokens("+=-=*=/=%=&=|=^="),
+            vec![
+                OpAddAssign,
+                OpSubAssign,
+                OpMulAssign,
+                OpDivAssign,
+                OpModAssign,
+                OpAndAssign,
+                OpOrAssign,
+                OpXorAssign
+            ]
+        );
+        assert_eq!(
+            tokens("a->b->>c<->d"),
+            vec![
+                Identifier,
+                OpImplication,
+                Identifier,
+                OpNonBlockingTrigger,
+                Identifier,
+                OpEquivalence,
+                Identifier
+            ]
+        );
+        assert_eq!(
+            tokens("a|->b|=>c|d"),
+            vec![
+                Identifier,
+                OpOverlappedImplication,
+                Identifier,
+                OpNonOverlappedImplication,
+                Identifier,
+                OpBitOr,
+                Identifier
+            ]
+        );
+        assert_eq!(
+            tokens("a==?b!=?c===d"),
+            vec![
+                Identifier,
+                OpWildcardEqual,
+                Identifier,
+                OpWildcardInequal,
+                Identifier,
+                OpCaseEqual,
+                Identifier
+            ]
+        );
+        assert_eq!(tokens("pkg::a"), vec![Identifier, DoubleColon, Identifier]);
+        assert_eq!(
+            tokens("'{0} '0"),
+            vec![ApostropheLBraces, Number, RBraces, Number]
+        );
+        assert_eq!(
+            tokens("m u(.*);"),
+            vec![Identifier, Identifier, LParen, DotStar, RParen, Semicolon]
+        );
+        assert_eq!(
+            tokens("a[i+:4] b[j-:2] c[1:0]"),
+            vec![
+                Identifier, LBracket, Identifier, PlusColon, Number, RBracket, Identifier,
+                LBracket, Identifier, MinusColon, Number, RBracket, Identifier, LBracket, Number,
+                Colon, Number, RBracket,
+            ]
+        );
+        assert_eq!(tokens("a = b"), vec![Identifier, Equal, Identifier]);
+
+        let lexer = Lexer::lex("x<<<=y");
+        assert_eq!(lexer.tokens[1].text, "<<<=");
+        assert_eq!(lexer.tokens[1].span.to.col, 4);
+        assert_eq!(lexer.tokens[2].span.from.col, 5);
     }
 
     #[test]
     fn string() {
         let lexer = Lexer::lex(r#""abcde\t\n\r\\\"\"""#);
         assert_eq!(lexer.tokens.len(), 1);
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 18 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 18,
+                offset: 18
+            }
+        );
         assert_eq!(lexer.diag.len(), 1); // \r
         assert_eq!(lexer.diag[0].msg, Message::UnrecognizedEscapeCharacter('r'));
         // \r
     }
 
+    #[test]
+    fn string_forms() {
+        let lexer = Lexer::lex(r#""\v\f\a\x41\101" "\xg""#);
+        assert_eq!(lexer.tokens.len(), 2);
+        assert_eq!(lexer.diag.len(), 1);
+        assert_eq!(lexer.diag[0].msg, Message::UnrecognizedEscapeCharacter('x'));
+
+        // line continuation
+        let lexer = Lexer::lex("\"a\\\nb\" x");
+        assert!(lexer.diag.is_empty(), "{:?}", lexer.diag);
+        assert_eq!(lexer.tokens[0].text, "\"a\\\nb\"");
+        assert_eq!(lexer.tokens[1].span.from.row, 1);
+
+        let lexer = Lexer::lex("\"\"\"a \"quote\"\nline\"\"\" \"\" x");
+        assert!(lexer.diag.is_empty(), "{:?}", lexer.diag);
+        assert_eq!(lexer.tokens.len(), 3);
+        assert_eq!(lexer.tokens[0].text, "\"\"\"a \"quote\"\nline\"\"\"");
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 1,
+                col: 6,
+                offset: 19
+            }
+        );
+        assert_eq!(lexer.tokens[1].text, "\"\"");
+
+        let lexer = Lexer::lex("x = \"abc\ny;");
+        assert_eq!(lexer.diag.len(), 1);
+        assert_eq!(lexer.diag[0].msg, Message::UnterminatedString);
+        let texts: Vec<&str> = lexer.tokens.iter().map(|t| t.text.as_ref()).collect();
+        assert_eq!(texts, vec!["x", "=", "\"abc", "y", ";"]);
+
+        let lexer = Lexer::lex("\"\"\"abc\n");
+        assert_eq!(lexer.diag[0].msg, Message::UnterminatedString);
+        assert_eq!(lexer.tokens[0].text, "\"\"\"abc\n");
+    }
+
     #[test]
     fn identifier() {
         let lexer = Lexer::lex(r#""abc"abc"#);
         assert_eq!(lexer.tokens.len(), 2);
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 4 });
-        assert_eq!(lexer.tokens[1].span.from, Location { row: 0, col: 5 });
-        assert_eq!(lexer.tokens[1].span.to, Location { row: 0, col: 7 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 4,
+                offset: 4
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.from,
+            Location {
+                row: 0,
+                col: 5,
+                offset: 5
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.to,
+            Location {
+                row: 0,
+                col: 7,
+                offset: 7
+            }
+        );
 
         let lexer = Lexer::lex(r#"abc "abc""#);
         assert_eq!(lexer.tokens.len(), 2);
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 2 });
-        assert_eq!(lexer.tokens[1].span.from, Location { row: 0, col: 4 });
-        assert_eq!(lexer.tokens[1].span.to, Location { row: 0, col: 8 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 2,
+                offset: 2
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.from,
+            Location {
+                row: 0,
+                col: 4,
+                offset: 4
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.to,
+            Location {
+                row: 0,
+                col: 8,
+                offset: 8
+            }
+        );
+    }
+
+    #[test]
+    fn system_identifier() {
+        let lexer = Lexer::lex("$display(\"%d\", $clog2(W)); $test$plusargs q[$]");
+        assert!(lexer.diag.is_empty(), "{:?}", lexer.diag);
+        let tokens: Vec<(Token, &str)> = lexer
+            .tokens
+            .iter()
+            .map(|token| (token.token, token.text.as_ref()))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::SystemIdentifier, "$display"),
+                (Token::LParen, "("),
+                (Token::StringLiteral, "\"%d\""),
+                (Token::Comma, ","),
+                (Token::SystemIdentifier, "$clog2"),
+                (Token::LParen, "("),
+                (Token::Identifier, "W"),
+                (Token::RParen, ")"),
+                (Token::RParen, ")"),
+                (Token::Semicolon, ";"),
+                (Token::SystemIdentifier, "$test$plusargs"),
+                (Token::Identifier, "q"),
+                (Token::LBracket, "["),
+                (Token::Dollar, "$"),
+                (Token::RBracket, "]"),
+            ]
+        );
+        assert_eq!(lexer.tokens[4].span.from.col, 15);
+        assert_eq!(lexer.tokens[4].span.to.col, 20);
     }
 
     #[test]
     fn keyword() {
         let lexer = Lexer::lex(r#"and andd an always"#);
         assert_eq!(lexer.tokens.len(), 4);
-        assert_eq!(lexer.tokens[0].span.from, Location { row: 0, col: 0 });
-        assert_eq!(lexer.tokens[0].span.to, Location { row: 0, col: 2 });
+        assert_eq!(
+            lexer.tokens[0].span.from,
+            Location {
+                row: 0,
+                col: 0,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            lexer.tokens[0].span.to,
+            Location {
+                row: 0,
+                col: 2,
+                offset: 2
+            }
+        );
         assert_eq!(lexer.tokens[0].token, Token::And);
-        assert_eq!(lexer.tokens[1].span.from, Location { row: 0, col: 4 });
-        assert_eq!(lexer.tokens[1].span.to, Location { row: 0, col: 7 });
+        assert_eq!(
+            lexer.tokens[1].span.from,
+            Location {
+                row: 0,
+                col: 4,
+                offset: 4
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.to,
+            Location {
+                row: 0,
+                col: 7,
+                offset: 7
+            }
+        );
         assert_eq!(lexer.tokens[1].token, Token::Identifier);
-        assert_eq!(lexer.tokens[2].span.from, Location { row: 0, col: 9 });
-        assert_eq!(lexer.tokens[2].span.to, Location { row: 0, col: 10 });
+        assert_eq!(
+            lexer.tokens[2].span.from,
+            Location {
+                row: 0,
+                col: 9,
+                offset: 9
+            }
+        );
+        assert_eq!(
+            lexer.tokens[2].span.to,
+            Location {
+                row: 0,
+                col: 10,
+                offset: 10
+            }
+        );
         assert_eq!(lexer.tokens[2].token, Token::Identifier);
-        assert_eq!(lexer.tokens[3].span.from, Location { row: 0, col: 12 });
-        assert_eq!(lexer.tokens[3].span.to, Location { row: 0, col: 17 });
+        assert_eq!(
+            lexer.tokens[3].span.from,
+            Location {
+                row: 0,
+                col: 12,
+                offset: 12
+            }
+        );
+        assert_eq!(
+            lexer.tokens[3].span.to,
+            Location {
+                row: 0,
+                col: 17,
+                offset: 17
+            }
+        );
         assert_eq!(lexer.tokens[3].token, Token::Always);
     }
+
+    #[test]
+    fn all_keywords() {
+        for (text, token, _) in KEYWORDS {
+            let lexer = Lexer::lex(text);
+            assert_eq!(lexer.tokens.len(), 1, "lexing {}", text);
+            assert_eq!(lexer.tokens[0].token, *token, "lexing {}", text);
+            assert_eq!(lexer.tokens[0].text, *text);
+            assert_eq!(lexer.tokens[0].token.keyword(), Some(*text));
+        }
+        assert_eq!(keyword_map().len(), KEYWORDS.len());
+        assert_eq!(Token::Identifier.keyword(), None);
+    }
+
+    #[test]
+    fn keyword_version() {
+        let options = LexerOptions {
+            keywords: KeywordVersion::Verilog1995,
+            ..LexerOptions::default()
+        };
+        let lexer = Lexer::lex_with_options("logic bit generate wire", options);
+        assert_eq!(lexer.tokens[0].token, Token::Identifier);
+        assert_eq!(lexer.tokens[1].token, Token::Identifier);
+        assert_eq!(lexer.tokens[2].token, Token::Identifier);
+        assert_eq!(lexer.tokens[3].token, Token::Wire);
+
+        assert!(KeywordVersion::Verilog2001.is_keyword("config"));
+        assert!(!KeywordVersion::Verilog2001NoConfig.is_keyword("config"));
+        assert!(KeywordVersion::Verilog2001NoConfig.is_keyword("generate"));
+        assert!(!KeywordVersion::Verilog2005.is_keyword("logic"));
+        assert!(KeywordVersion::SystemVerilog2012.is_keyword("nettype"));
+        assert!(!KeywordVersion::SystemVerilog2009.is_keyword("nettype"));
+        assert!(!KeywordVersion::SystemVerilog2023.is_keyword("abc"));
+    }
+
+    #[test]
+    fn begin_end_keywords() {
+        let lexer = Lexer::lex(
+            r#"logic
+`begin_keywords "1364-2001"
+logic generate
+`begin_keywords "1364-1995"
+generate
+`end_keywords
+generate
+`end_keywords
+logic"#,
+        );
+        assert_eq!(lexer.diag.len(), 0);
+        let tokens: Vec<Token> = lexer
+            .tokens
+            .iter()
+            .map(|token| token.token)
+            .filter(|token| *token != Token::Directive && *token != Token::StringLiteral)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Logic,
+                Token::Identifier,
+                Token::Generate,
+                Token::Identifier,
+                Token::Generate,
+                Token::Logic
+            ]
+        );
+
+        let lexer = Lexer::lex("`begin_keywords \"1364-2077\" `end_keywords `end_keywords");
+        assert_eq!(lexer.diag.len(), 2);
+        assert_eq!(
+            lexer.diag[0].msg,
+            Message::UnknownKeywordVersion("1364-2077".to_owned())
+        );
+        assert_eq!(lexer.diag[1].msg, Message::UnmatchedEndKeywords);
+
+        let lexer = Lexer::lex("`begin_keywords logic");
+        assert_eq!(lexer.diag[0].msg, Message::MissingKeywordVersion);
+        assert_eq!(lexer.tokens[1].token, Token::Logic);
+    }
+
+    #[test]
+    fn line_continuation() {
+        let lexer = Lexer::lex("wire a = b \\\n;");
+        assert_eq!(lexer.diag.len(), 1);
+        assert_eq!(lexer.diag[0].msg, Message::UnexpectedChar('\\'));
+        assert_eq!(lexer.tokens.last().unwrap().token, Token::Semicolon);
+
+        let options = LexerOptions {
+            macro_text: true,
+            ..LexerOptions::default()
+        };
+        let lexer = Lexer::lex_with_options("a \\\n b", options);
+        assert!(lexer.diag.is_empty());
+        assert_eq!(lexer.tokens.len(), 2);
+    }
+
+    #[test]
+    fn macro_text() {
+        let lexer = Lexer::lex("`define A(x) x \\\n + 1 // c\nB");
+        assert_eq!(lexer.tokens.len(), 4);
+        assert_eq!(lexer.tokens[1].token, Token::MacroText);
+        assert_eq!(lexer.tokens[1].text, "A(x) x \\\n + 1");
+        assert_eq!(
+            lexer.tokens[1].span.from,
+            Location {
+                row: 0,
+                col: 8,
+                offset: 8
+            }
+        );
+        assert_eq!(
+            lexer.tokens[1].span.to,
+            Location {
+                row: 1,
+                col: 3,
+                offset: 20
+            }
+        );
+        assert_eq!(lexer.tokens[2].token, Token::Comment);
+        assert_eq!(
+            lexer.tokens[3].span.from,
+            Location {
+                row: 2,
+                col: 0,
+                offset: 27
+            }
+        );
+    }
 }
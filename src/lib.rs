@@ -1,4 +1,13 @@
 pub mod ast;
+pub mod compilation;
+pub mod cst;
 pub mod diagnostic;
+pub mod filelist;
 pub mod lexer;
+pub mod line_index;
+pub mod logic_value;
 pub mod parser;
+pub mod preprocessor;
+pub mod source;
+pub mod syntax_tree;
+pub mod system_tasks;
@@ -0,0 +1,232 @@
+//! Conversion between byte offsets and line/column positions
+
+use crate::lexer::Location;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use strcursor::StrCursor;
+
+/// A zero-based line and column, the unit of the column depends on context
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// Start of a character in a line that is not pure ASCII
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+struct WideChar {
+    utf8: usize,
+    utf16: usize,
+    grapheme: usize,
+}
+
+/// Line starts of a text
+///
+/// Lines are terminated by `\n`, `\r\n` or `\r`, the same as in the lexer.
+/// Columns of ASCII lines are converted by arithmetic, other lines keep a
+/// table of their characters.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct LineIndex {
+    /// Byte offset where each line starts
+    starts: Vec<usize>,
+    /// Characters of lines with non-ASCII text, ending with the line length
+    wide: HashMap<usize, Vec<WideChar>>,
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> LineIndex {
+        let mut res = LineIndex {
+            starts: vec![0],
+            wide: HashMap::new(),
+            len: text.len(),
+        };
+        let bytes = text.as_bytes();
+        let mut index = 0;
+        let mut line_start = 0;
+        while index < bytes.len() {
+            let terminator = match bytes[index] {
+                b'\r' if bytes.get(index + 1) == Some(&b'\n') => 2,
+                b'\r' | b'\n' => 1,
+                _ => {
+                    index += 1;
+                    continue;
+                }
+            };
+            res.add_line(&text[line_start..index]);
+            index += terminator;
+            line_start = index;
+            res.starts.push(index);
+        }
+        res.add_line(&text[line_start..]);
+        res
+    }
+
+    fn add_line(&mut self, content: &str) {
+        if !content.is_ascii() {
+            self.wide
+                .insert(self.starts.len() - 1, Self::wide_chars(content));
+        }
+    }
+
+    fn wide_chars(content: &str) -> Vec<WideChar> {
+        let mut chars = vec![];
+        let mut utf16 = 0;
+        let mut cursor = StrCursor::new_at_start(content);
+        let mut grapheme = 0;
+        while let Some((gc, next)) = cursor.next() {
+            let mut utf8 = cursor.byte_pos();
+            for ch in gc.as_str().chars() {
+                chars.push(WideChar {
+                    utf8,
+                    utf16,
+                    grapheme,
+                });
+                utf8 += ch.len_utf8();
+                utf16 += ch.len_utf16();
+            }
+            grapheme += 1;
+            cursor = next;
+        }
+        chars.push(WideChar {
+            utf8: content.len(),
+            utf16,
+            grapheme,
+        });
+        chars
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Line and UTF-8 column of a byte offset
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let offset = offset.min(self.len);
+        let line = self.starts.partition_point(|&start| start <= offset) - 1;
+        LineCol {
+            line,
+            col: offset - self.starts[line],
+        }
+    }
+
+    /// Byte offset of a line and UTF-8 column
+    pub fn offset(&self, pos: LineCol) -> usize {
+        match self.starts.get(pos.line) {
+            Some(start) => (start + pos.col).min(self.len),
+            None => self.len,
+        }
+    }
+
+    fn wide_char(&self, line: usize, f: impl Fn(&WideChar) -> bool) -> Option<&WideChar> {
+        let chars = self.wide.get(&line)?;
+        let index = chars.partition_point(f);
+        Some(&chars[index.min(chars.len() - 1)])
+    }
+
+    /// Convert a UTF-8 column to a UTF-16 column
+    pub fn to_utf16(&self, pos: LineCol) -> LineCol {
+        match self.wide_char(pos.line, |ch| ch.utf8 < pos.col) {
+            Some(ch) => LineCol {
+                line: pos.line,
+                col: ch.utf16 + pos.col.saturating_sub(ch.utf8),
+            },
+            None => pos,
+        }
+    }
+
+    /// Convert a UTF-16 column to a UTF-8 column
+    pub fn from_utf16(&self, pos: LineCol) -> LineCol {
+        match self.wide_char(pos.line, |ch| ch.utf16 < pos.col) {
+            Some(ch) => LineCol {
+                line: pos.line,
+                col: ch.utf8 + pos.col.saturating_sub(ch.utf16),
+            },
+            None => pos,
+        }
+    }
+
+    /// Convert a UTF-8 column to a grapheme column
+    pub fn to_grapheme(&self, pos: LineCol) -> LineCol {
+        match self.wide_char(pos.line, |ch| ch.utf8 < pos.col) {
+            Some(ch) => LineCol {
+                line: pos.line,
+                col: ch.grapheme + pos.col.saturating_sub(ch.utf8),
+            },
+            None => pos,
+        }
+    }
+
+    /// Convert a grapheme column to a UTF-8 column
+    pub fn from_grapheme(&self, pos: LineCol) -> LineCol {
+        match self.wide_char(pos.line, |ch| ch.grapheme < pos.col) {
+            Some(ch) => LineCol {
+                line: pos.line,
+                col: ch.utf8 + pos.col.saturating_sub(ch.grapheme),
+            },
+            None => pos,
+        }
+    }
+
+    /// Location of a byte offset, with a grapheme column
+    pub fn location(&self, offset: usize) -> Location {
+        let pos = self.to_grapheme(self.line_col(offset));
+        Location {
+            row: pos.line,
+            col: pos.col,
+            offset: offset.min(self.len),
+        }
+    }
+
+    /// Byte offset of a row and grapheme column
+    pub fn location_offset(&self, row: usize, col: usize) -> usize {
+        self.offset(self.from_grapheme(LineCol { line: row, col }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii() {
+        let index = LineIndex::new("ab\ncd\r\nef\rg");
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(0), LineCol { line: 0, col: 0 });
+        assert_eq!(index.line_col(4), LineCol { line: 1, col: 1 });
+        assert_eq!(index.line_col(7), LineCol { line: 2, col: 0 });
+        assert_eq!(index.line_col(10), LineCol { line: 3, col: 0 });
+        assert_eq!(index.offset(LineCol { line: 2, col: 1 }), 8);
+        let pos = LineCol { line: 1, col: 1 };
+        assert_eq!(index.to_utf16(pos), pos);
+        assert_eq!(index.to_grapheme(pos), pos);
+    }
+
+    #[test]
+    fn unicode() {
+        // é is two chars, e and a combining acute accent, 𝄞 needs two UTF-16
+        // units
+        let text = "x\na\u{65}\u{301}𝄞b";
+        let index = LineIndex::new(text);
+        let b = text.find('b').unwrap();
+        assert_eq!(b, 10);
+        let pos = index.line_col(b);
+        assert_eq!(pos, LineCol { line: 1, col: 8 });
+        assert_eq!(index.to_utf16(pos), LineCol { line: 1, col: 5 });
+        assert_eq!(index.to_grapheme(pos), LineCol { line: 1, col: 3 });
+        assert_eq!(index.from_utf16(LineCol { line: 1, col: 5 }), pos);
+        assert_eq!(index.from_grapheme(LineCol { line: 1, col: 3 }), pos);
+        assert_eq!(index.from_grapheme(LineCol { line: 1, col: 2 }).col, 4);
+        assert_eq!(
+            index.location(b),
+            Location {
+                row: 1,
+                col: 3,
+                offset: 10
+            }
+        );
+        assert_eq!(index.location_offset(1, 3), 10);
+        // past the end of a line
+        assert_eq!(index.from_grapheme(LineCol { line: 1, col: 5 }).col, 10);
+    }
+}
@@ -0,0 +1,510 @@
+//! 4-state values of integral numbers, 5.7.1 and 6.3.1
+
+use crate::diagnostic::{Diagnostic, Message, Severity};
+use crate::lexer::Span;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::num::IntErrorKind;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, Default)]
+pub enum LogicValue {
+    #[default]
+    Zero,
+    One,
+    X,
+    Z,
+}
+
+impl LogicValue {
+    pub fn is_known(self) -> bool {
+        matches!(self, LogicValue::Zero | LogicValue::One)
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            LogicValue::Zero => '0',
+            LogicValue::One => '1',
+            LogicValue::X => 'x',
+            LogicValue::Z => 'z',
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Base {
+    Binary,
+    Octal,
+    Decimal,
+    Hex,
+}
+
+impl Base {
+    fn from_char(ch: char) -> Option<Base> {
+        match ch {
+            'b' | 'B' => Some(Base::Binary),
+            'o' | 'O' => Some(Base::Octal),
+            'd' | 'D' => Some(Base::Decimal),
+            'h' | 'H' => Some(Base::Hex),
+            _ => None,
+        }
+    }
+
+    /// Bits per digit, `None` for decimal
+    fn digit_bits(self) -> Option<usize> {
+        match self {
+            Base::Binary => Some(1),
+            Base::Octal => Some(3),
+            Base::Decimal => None,
+            Base::Hex => Some(4),
+        }
+    }
+}
+
+/// The value of an integral number
+///
+/// Bits are stored least significant first.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, Default)]
+pub struct BitVector {
+    pub signed: bool,
+    /// Whether the number has an explicit size, unsized numbers are 32 bits
+    /// or wider
+    pub sized: bool,
+    /// `None` for unbased unsized literals like `'1`, which take the width
+    /// of their context
+    pub base: Option<Base>,
+    bits: Vec<LogicValue>,
+}
+
+/// Width of unsized numbers
+const UNSIZED_WIDTH: usize = 32;
+
+/// Largest width of a number, the minimum limit an implementation may set
+/// on vector widths, 6.9.1
+pub const MAX_WIDTH: usize = 1 << 16;
+
+impl BitVector {
+    /// A known value of `width` bits
+    pub fn from_u64(value: u64, width: usize, signed: bool) -> BitVector {
+        let bits = (0..width)
+            .map(|index| {
+                if index < 64 && value >> index & 1 == 1 {
+                    LogicValue::One
+                } else {
+                    LogicValue::Zero
+                }
+            })
+            .collect();
+        BitVector {
+            signed,
+            sized: true,
+            base: Some(Base::Decimal),
+            bits,
+        }
+    }
+
+    /// Decode the text of a number token, like `16'hDEAD`, `4'b10xz` or `'1`
+    ///
+    /// Returns `None` for real numbers and numbers that cannot be decoded.
+    /// Truncated values are reported as warnings, illegal digits and sizes
+    /// as errors. Sizes above `MAX_WIDTH` are reported and capped. A sign
+    /// before a decimal value like `'sd-5` negates it.
+    pub fn parse(text: &str, span: Span, diag: &mut Vec<Diagnostic>) -> Option<BitVector> {
+        let mut report = |severity, msg| {
+            diag.push(Diagnostic {
+                pos: span,
+                msg,
+                severity,
+                notes: vec![],
+            })
+        };
+
+        let (size, signed, base, digits) = match text.find('\'') {
+            Some(index) => {
+                let size = text[..index].trim();
+                let rest = text[index + 1..].trim_start();
+                let (signed, rest) = match rest.strip_prefix(['s', 'S']) {
+                    Some(rest) => (true, rest),
+                    None => (false, rest),
+                };
+                let mut chars = rest.chars();
+                let base_char = chars.next()?;
+                match Base::from_char(base_char) {
+                    Some(base) => (size, signed, Some(base), chars.as_str()),
+                    None if size.is_empty() && !signed && chars.as_str().is_empty() => {
+                        // unbased_unsized_literal
+                        let bit = match base_char {
+                            '0' => LogicValue::Zero,
+                            '1' => LogicValue::One,
+                            'x' | 'X' => LogicValue::X,
+                            'z' | 'Z' | '?' => LogicValue::Z,
+                            _ => {
+                                report(
+                                    Severity::Error,
+                                    Message::IllegalDigit(base_char, text.to_owned()),
+                                );
+                                return None;
+                            }
+                        };
+                        return Some(BitVector {
+                            signed: false,
+                            sized: false,
+                            base: None,
+                            bits: vec![bit],
+                        });
+                    }
+                    None => {
+                        report(
+                            Severity::Error,
+                            Message::IllegalDigit(base_char, text.to_owned()),
+                        );
+                        return None;
+                    }
+                }
+            }
+            None if text.contains(['.', 'e', 'E']) => return None,
+            // an unsized decimal number is signed
+            None => ("", true, Some(Base::Decimal), text),
+        };
+
+        let width = if size.is_empty() {
+            None
+        } else {
+            let size: String = size.chars().filter(|&ch| ch != '_').collect();
+            match size.parse::<usize>() {
+                Ok(width) if width > 0 => Some(width),
+                Err(err) if *err.kind() == IntErrorKind::PosOverflow => Some(usize::MAX),
+                _ => {
+                    report(Severity::Error, Message::InvalidNumberSize(text.to_owned()));
+                    return None;
+                }
+            }
+        };
+
+        let mut digits: Vec<char> = digits
+            .chars()
+            .filter(|&ch| ch != '_' && !ch.is_whitespace())
+            .collect();
+        let negative = digits.first() == Some(&'-');
+        if matches!(digits.first(), Some('-' | '+')) {
+            digits.remove(0);
+        }
+        if digits.is_empty() {
+            // the lexer does not produce based numbers without a value
+            return None;
+        }
+        let base = base.unwrap();
+        let value = match base.digit_bits() {
+            Some(digit_bits) => Self::decode_digits(&digits, digit_bits),
+            None if negative && !digits.iter().all(char::is_ascii_digit) => Err('-'),
+            None => Self::decode_decimal(&digits),
+        };
+        let mut value = match value {
+            Ok(value) => value,
+            Err(ch) => {
+                report(Severity::Error, Message::IllegalDigit(ch, text.to_owned()));
+                return None;
+            }
+        };
+
+        let width = width.unwrap_or_else(|| {
+            let significant = value
+                .iter()
+                .rposition(|&bit| bit != LogicValue::Zero)
+                .map_or(0, |index| index + 1);
+            significant.max(UNSIZED_WIDTH)
+        });
+        let width = if width > MAX_WIDTH {
+            report(
+                Severity::Error,
+                Message::NumberTooWide(text.to_owned(), MAX_WIDTH),
+            );
+            MAX_WIDTH
+        } else {
+            width
+        };
+        if value.len() > width {
+            if value[width..].iter().any(|&bit| bit != LogicValue::Zero) {
+                report(
+                    Severity::Warning,
+                    Message::NumberTruncated(text.to_owned(), width),
+                );
+            }
+            value.truncate(width);
+        } else {
+            // pad with zeros, or with x or z if the leftmost bit is x or z
+            let pad = match value.last() {
+                Some(&bit) if !bit.is_known() => bit,
+                _ => LogicValue::Zero,
+            };
+            value.resize(width, pad);
+        }
+        if negative {
+            Self::negate(&mut value);
+        }
+
+        Some(BitVector {
+            signed,
+            sized: !size.is_empty(),
+            base: Some(base),
+            bits: value,
+        })
+    }
+
+    /// Two's complement of known bits
+    fn negate(bits: &mut [LogicValue]) {
+        let mut carry = true;
+        for bit in bits.iter_mut() {
+            let inverted = *bit == LogicValue::Zero;
+            *bit = if inverted != carry {
+                LogicValue::One
+            } else {
+                LogicValue::Zero
+            };
+            carry = inverted && carry;
+        }
+    }
+
+    /// Decode binary, octal or hex digits, returns an illegal digit
+    fn decode_digits(digits: &[char], digit_bits: usize) -> Result<Vec<LogicValue>, char> {
+        let mut bits = Vec::with_capacity(digits.len() * digit_bits);
+        for &ch in digits.iter().rev() {
+            let digit = match ch {
+                'x' | 'X' => Err(LogicValue::X),
+                'z' | 'Z' | '?' => Err(LogicValue::Z),
+                _ => match ch.to_digit(1 << digit_bits) {
+                    Some(digit) => Ok(digit),
+                    None => return Err(ch),
+                },
+            };
+            for index in 0..digit_bits {
+                bits.push(match digit {
+                    Ok(digit) if digit >> index & 1 == 1 => LogicValue::One,
+                    Ok(_) => LogicValue::Zero,
+                    Err(bit) => bit,
+                });
+            }
+        }
+        Ok(bits)
+    }
+
+    /// Decode decimal digits or a single x or z digit
+    fn decode_decimal(digits: &[char]) -> Result<Vec<LogicValue>, char> {
+        match digits {
+            ['x' | 'X'] => return Ok(vec![LogicValue::X]),
+            ['z' | 'Z' | '?'] => return Ok(vec![LogicValue::Z]),
+            _ => {}
+        }
+        // little endian 32-bit limbs
+        let mut limbs: Vec<u32> = vec![];
+        for &ch in digits {
+            let mut carry = ch.to_digit(10).ok_or(ch)? as u64;
+            for limb in limbs.iter_mut() {
+                let product = *limb as u64 * 10 + carry;
+                *limb = product as u32;
+                carry = product >> 32;
+            }
+            if carry != 0 {
+                limbs.push(carry as u32);
+            }
+        }
+        let mut bits: Vec<LogicValue> = limbs
+            .iter()
+            .flat_map(|&limb| {
+                (0..32).map(move |index| {
+                    if limb >> index & 1 == 1 {
+                        LogicValue::One
+                    } else {
+                        LogicValue::Zero
+                    }
+                })
+            })
+            .collect();
+        let len = bits
+            .iter()
+            .rposition(|&bit| bit == LogicValue::One)
+            .map_or(1, |index| index + 1);
+        bits.resize(len, LogicValue::Zero);
+        Ok(bits)
+    }
+
+    pub fn width(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Bits, least significant first
+    pub fn bits(&self) -> &[LogicValue] {
+        &self.bits
+    }
+
+    pub fn bit(&self, index: usize) -> Option<LogicValue> {
+        self.bits.get(index).copied()
+    }
+
+    /// Whether no bit is x or z
+    pub fn is_known(&self) -> bool {
+        self.bits.iter().all(|bit| bit.is_known())
+    }
+
+    /// Value as an unsigned number, `None` if a bit is x or z or the value
+    /// needs more than 64 bits
+    pub fn to_u64(&self) -> Option<u64> {
+        let mut res = 0u64;
+        for (index, &bit) in self.bits.iter().enumerate() {
+            match bit {
+                LogicValue::Zero => {}
+                LogicValue::One if index < 64 => res |= 1 << index,
+                _ => return None,
+            }
+        }
+        Some(res)
+    }
+
+    /// Value as a number, sign extended if the vector is signed
+    pub fn to_i64(&self) -> Option<i64> {
+        let width = self.width();
+        if self.signed && width <= 64 {
+            let value = self.to_u64()?;
+            let shift = 64 - width;
+            Some(((value << shift) as i64) >> shift)
+        } else {
+            i64::try_from(self.to_u64()?).ok()
+        }
+    }
+}
+
+/// Binary form like `4'sb10xz`
+impl fmt::Display for BitVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.base.is_none() {
+            return write!(f, "'{}", self.bits[0].to_char());
+        }
+        write!(f, "{}'", self.width())?;
+        if self.signed {
+            write!(f, "s")?;
+        }
+        write!(f, "b")?;
+        for bit in self.bits.iter().rev() {
+            write!(f, "{}", bit.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Location;
+    use crate::source::FileId;
+
+    fn parse(text: &str) -> (Option<BitVector>, Vec<Message>) {
+        let mut diag = vec![];
+        let value = BitVector::parse(
+            text,
+            Span {
+                file: FileId::default(),
+                from: Location::default(),
+                to: Location::default(),
+            },
+            &mut diag,
+        );
+        (value, diag.into_iter().map(|diag| diag.msg).collect())
+    }
+
+    fn binary(text: &str) -> String {
+        let (value, diag) = parse(text);
+        assert!(diag.is_empty(), "{:?}", diag);
+        value.unwrap().to_string()
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(binary("4'b10xz"), "4'b10xz");
+        assert_eq!(binary("6'o7"), "6'b000111");
+        assert_eq!(binary("8'sh_f0"), "8'sb11110000");
+        assert_eq!(binary("16 'h DEAD"), "16'b1101111010101101");
+        assert_eq!(binary("8'hx"), "8'bxxxxxxxx");
+        assert_eq!(binary("8'bz1"), "8'bzzzzzzz1");
+        assert_eq!(binary("5'dz"), "5'bzzzzz");
+        assert_eq!(binary("'1"), "'1");
+        assert_eq!(binary("'x"), "'x");
+
+        let (value, _) = parse("'hff");
+        let value = value.unwrap();
+        assert!(!value.sized && !value.signed);
+        assert_eq!(value.width(), 32);
+        assert_eq!(value.to_u64(), Some(255));
+
+        let (value, _) = parse("42");
+        let value = value.unwrap();
+        assert!(value.signed);
+        assert_eq!(value.width(), 32);
+        assert_eq!(value.to_i64(), Some(42));
+        assert_eq!(value.base, Some(Base::Decimal));
+
+        // wider than 64 bits
+        let (value, _) = parse("100'd36893488147419103232");
+        let value = value.unwrap();
+        assert_eq!(value.bit(65), Some(LogicValue::One));
+        assert_eq!(
+            value
+                .bits()
+                .iter()
+                .filter(|&&bit| bit == LogicValue::One)
+                .count(),
+            1
+        );
+        assert_eq!(value.to_u64(), None);
+        let (value, _) = parse("36893488147419103232");
+        assert_eq!(value.unwrap().width(), 66);
+
+        assert_eq!(parse("4'sb1100").0.unwrap().to_i64(), Some(-4));
+        assert_eq!(parse("4'b1100").0.unwrap().to_i64(), Some(12));
+        assert_eq!(parse("4'b1x00").0.unwrap().to_i64(), None);
+        assert_eq!(parse("1.5e3"), (None, vec![]));
+
+        // a sign before a decimal value
+        let (value, _) = parse("'sd-5");
+        let value = value.unwrap();
+        assert_eq!(value.width(), 32);
+        assert_eq!(value.to_i64(), Some(-5));
+        assert_eq!(binary("4'sd-8"), "4'sb1000");
+        assert_eq!(binary("4'd+3"), "4'b0011");
+    }
+
+    #[test]
+    fn diagnostics() {
+        let (value, diag) = parse("4'hFF");
+        assert_eq!(value.unwrap().to_string(), "4'b1111");
+        assert_eq!(diag, vec![Message::NumberTruncated("4'hFF".to_owned(), 4)]);
+        // leading zeros are not truncated values
+        assert_eq!(parse("4'h0F").1, vec![]);
+        assert_eq!(
+            parse("4'b102"),
+            (None, vec![Message::IllegalDigit('2', "4'b102".to_owned())])
+        );
+        assert_eq!(
+            parse("8'd1x"),
+            (None, vec![Message::IllegalDigit('x', "8'd1x".to_owned())])
+        );
+        assert_eq!(
+            parse("0'b1").1,
+            vec![Message::InvalidNumberSize("0'b1".to_owned())]
+        );
+        assert_eq!(
+            parse("8'sd-x").1,
+            vec![Message::IllegalDigit('-', "8'sd-x".to_owned())]
+        );
+        for text in ["4294967295'b1", "99999999999999999999999'h1"] {
+            let (value, diag) = parse(text);
+            assert_eq!(value.unwrap().width(), MAX_WIDTH);
+            assert_eq!(
+                diag,
+                vec![Message::NumberTooWide(text.to_owned(), MAX_WIDTH)]
+            );
+        }
+        assert_eq!(
+            Message::NumberTruncated("4'hFF".to_owned(), 4).to_string(),
+            "Value of 4'hFF does not fit in 4 bits and is truncated"
+        );
+    }
+}
@@ -1,41 +1,67 @@
 //! Parser
 
-use crate::diagnostic::{Diagnostic, Message, Severity};
+use crate::ast::NodeSpan;
+use crate::diagnostic::{Diagnostic, Message, Note, Severity};
 use crate::lexer::{Lexer, Location, ParsedToken, Span, Token};
+use crate::preprocessor::{expansion_notes, MacroExpansion, Preprocessor, PreprocessorOptions};
+use crate::source::FileId;
 
 #[derive(Debug)]
 pub struct Parser<'a> {
-    input: &'a str,
     index: usize,
+    /// Last token consumed by `advance`
+    last: Option<usize>,
+    /// File of the end location
+    file: FileId,
     end_loc: Location,
     tokens: Vec<ParsedToken<'a>>,
+    expansions: Vec<MacroExpansion>,
     diag: Vec<Diagnostic>,
+    /// Names declared by `typedef` or as type parameters in the current module
+    type_names: Vec<String>,
+    /// Names declared by `nettype` in the current module
+    net_type_names: Vec<String>,
 }
 
 impl<'a> Parser<'a> {
     pub fn from(input: &'a str) -> Parser<'a> {
         let lexer = Lexer::lex(input);
-        Self::from_lexer(lexer)
+        let pp = Preprocessor::preprocess(lexer, &PreprocessorOptions::default());
+        Self::from_preprocessor(pp)
     }
 
     pub fn from_lexer(lexer: Lexer<'a>) -> Parser<'a> {
         Parser {
-            input: lexer.input,
             index: 0,
+            last: None,
+            file: lexer.file,
             end_loc: lexer.loc,
             tokens: lexer.tokens,
+            expansions: vec![],
             diag: lexer.diag,
+            type_names: vec![],
+            net_type_names: vec![],
         }
     }
 
-    pub(crate) fn peek(&self) -> Option<ParsedToken<'a>> {
-        if self.index < self.tokens.len() {
-            Some(self.tokens[self.index])
-        } else {
-            None
+    pub fn from_preprocessor(pp: Preprocessor<'a>) -> Parser<'a> {
+        Parser {
+            index: 0,
+            last: None,
+            file: pp.file,
+            end_loc: pp.loc,
+            tokens: pp.tokens,
+            expansions: pp.expansions,
+            diag: pp.diag,
+            type_names: vec![],
+            net_type_names: vec![],
         }
     }
 
+    pub(crate) fn peek(&self) -> Option<&ParsedToken<'a>> {
+        self.tokens.get(self.index)
+    }
+
     fn skip_comment(&mut self) {
         while self.index < self.tokens.len() && self.tokens[self.index].token == Token::Comment {
             self.index += 1;
@@ -51,6 +77,23 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Whether the token after the current one is in `arr`
+    pub(crate) fn probe_next(&mut self, arr: &[Token]) -> bool {
+        self.skip_comment();
+        self.tokens
+            .get(self.index + 1..)
+            .and_then(|tokens| tokens.iter().find(|token| token.token != Token::Comment))
+            .is_some_and(|token| arr.contains(&token.token))
+    }
+
+    /// Tokens from the current one on, without comments
+    pub(crate) fn lookahead(&self) -> impl Iterator<Item = Token> + '_ {
+        self.tokens[self.index.min(self.tokens.len())..]
+            .iter()
+            .map(|token| token.token)
+            .filter(|token| *token != Token::Comment)
+    }
+
     pub(crate) fn probe_err(&mut self, arr: &[Token]) -> bool {
         self.skip_comment();
         let res = if self.index < self.tokens.len() {
@@ -69,9 +112,32 @@ impl<'a> Parser<'a> {
     }
 
     pub(crate) fn advance(&mut self) {
+        self.last = Some(self.index);
         self.index += 1;
     }
 
+    /// Index of the first token of a node parsed next
+    pub(crate) fn start(&mut self) -> usize {
+        self.skip_comment();
+        self.index
+    }
+
+    /// Span of a node from `first` to the last consumed token
+    pub(crate) fn span_from(&self, first: usize) -> NodeSpan {
+        NodeSpan {
+            first,
+            last: self.last.unwrap_or(first).max(first),
+        }
+    }
+
+    /// Span of a node that may not have consumed any token
+    pub(crate) fn optional_span_from(&self, first: usize) -> Option<NodeSpan> {
+        match self.last {
+            Some(last) if last >= first => Some(NodeSpan { first, last }),
+            _ => None,
+        }
+    }
+
     pub(crate) fn index(&self) -> usize {
         self.index
     }
@@ -96,27 +162,77 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// File of the current token
+    pub(crate) fn file(&self) -> FileId {
+        match self.tokens.get(self.index) {
+            Some(token) => token.span.file,
+            None => self.file,
+        }
+    }
+
+    /// Remember the name of a type declared by `typedef` or a type parameter
+    pub(crate) fn declare_type(&mut self, index: usize) {
+        let name = self.tokens[index].text.to_string();
+        self.type_names.push(name);
+    }
+
+    /// Remember the name of a net type declared by `nettype`
+    pub(crate) fn declare_net_type(&mut self, index: usize) {
+        let name = self.tokens[index].text.to_string();
+        self.net_type_names.push(name);
+    }
+
+    /// Forget the type names at the start of a module
+    pub(crate) fn clear_types(&mut self) {
+        self.type_names.clear();
+        self.net_type_names.clear();
+    }
+
+    /// Whether the current token is a declared type name
+    pub(crate) fn is_type_name(&self) -> bool {
+        self.is_declared(&self.type_names)
+    }
+
+    /// Whether the current token is a net type name declared by `nettype`
+    pub(crate) fn is_net_type_name(&self) -> bool {
+        self.is_declared(&self.net_type_names)
+    }
+
+    fn is_declared(&self, names: &[String]) -> bool {
+        match self.peek() {
+            Some(token) if token.token == Token::Identifier => {
+                names.iter().any(|name| *name == token.text)
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn current_text(&self) -> String {
         if self.index < self.tokens.len() {
-            self.tokens[self.index].text.to_owned()
+            self.tokens[self.index].text.to_string()
         } else {
             "end of file".to_owned()
         }
     }
 
-    pub(crate) fn err(&mut self, from: Location, to: Location, msg: Message) {
-        self.diag.push(Diagnostic {
-            pos: Span { from, to },
-            msg,
-            severity: Severity::Error,
-        });
+    /// Notes on the macro expansions that produced the current token
+    fn notes(&self) -> Vec<Note> {
+        match self.peek() {
+            Some(token) => expansion_notes(&self.expansions, token.expansion),
+            None => vec![],
+        }
     }
 
-    pub(crate) fn warn(&mut self, from: Location, to: Location, msg: Message) {
+    pub(crate) fn err(&mut self, from: Location, to: Location, msg: Message) {
         self.diag.push(Diagnostic {
-            pos: Span { from, to },
+            pos: Span {
+                file: self.file(),
+                from,
+                to,
+            },
             msg,
-            severity: Severity::Warning,
+            severity: Severity::Error,
+            notes: self.notes(),
         });
     }
 
@@ -124,7 +240,17 @@ impl<'a> Parser<'a> {
         &self.diag
     }
 
-    pub fn get_token(&self, index: usize) -> &ParsedToken {
+    pub fn get_token(&self, index: usize) -> &ParsedToken<'a> {
         &self.tokens[index]
     }
+
+    /// Location of a node
+    pub fn span(&self, span: NodeSpan) -> Span {
+        span.resolve(&self.tokens)
+    }
+
+    /// Tokens and diagnostics after parsing
+    pub fn finish(self) -> (Vec<ParsedToken<'a>>, Vec<Diagnostic>) {
+        (self.tokens, self.diag)
+    }
 }
@@ -0,0 +1,1379 @@
+//! Preprocessor
+
+use crate::diagnostic::{Diagnostic, Message, Note, Severity};
+use crate::lexer::{KeywordVersion, Lexer, LexerOptions, Location, ParsedToken, Span, Token};
+use crate::source::{FileId, SourceManager};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct PreprocessorOptions {
+    /// Predefined macros, like `+define+FOO=1`
+    pub defines: HashMap<String, String>,
+    /// Directories searched by `include, like `+incdir+dir`
+    pub incdirs: Vec<PathBuf>,
+    /// Macros defined by earlier files of the compilation unit
+    pub macros: HashMap<String, MacroDefinition<'static>>,
+}
+
+impl PreprocessorOptions {
+    /// Add a predefined macro from a `NAME` or `NAME=VALUE` argument
+    pub fn define(&mut self, arg: &str) {
+        match arg.split_once('=') {
+            Some((name, value)) => self.defines.insert(name.to_owned(), value.to_owned()),
+            None => self.defines.insert(arg.to_owned(), String::new()),
+        };
+    }
+}
+
+/// formal_argument ::= simple_identifier [ = default_text ]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MacroParameter<'a> {
+    pub name: String,
+    pub default: Option<Vec<ParsedToken<'a>>>,
+}
+
+/// A text macro defined by `define or by the caller
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MacroDefinition<'a> {
+    /// Location of the macro text, `None` for predefined macros
+    pub span: Option<Span>,
+    /// Formal arguments, `None` if the macro takes no parentheses
+    pub parameters: Option<Vec<MacroParameter<'a>>>,
+    pub body: Vec<ParsedToken<'a>>,
+}
+
+impl MacroParameter<'_> {
+    pub fn into_owned(self) -> MacroParameter<'static> {
+        MacroParameter {
+            name: self.name,
+            default: self
+                .default
+                .map(|tokens| tokens.into_iter().map(ParsedToken::into_owned).collect()),
+        }
+    }
+}
+
+impl MacroDefinition<'_> {
+    pub fn into_owned(self) -> MacroDefinition<'static> {
+        MacroDefinition {
+            span: self.span,
+            parameters: self
+                .parameters
+                .map(|params| params.into_iter().map(MacroParameter::into_owned).collect()),
+            body: self.body.into_iter().map(ParsedToken::into_owned).collect(),
+        }
+    }
+}
+
+/// A macro usage that produced tokens
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct MacroExpansion {
+    pub name: String,
+    /// Location of the macro usage including its arguments
+    pub site: Span,
+    /// Location of the macro text, `None` for predefined macros
+    pub definition: Option<Span>,
+    /// The expansion containing the macro usage
+    pub parent: Option<usize>,
+}
+
+/// Notes describing the chain of macro expansions of a token
+pub fn expansion_notes(expansions: &[MacroExpansion], mut expansion: Option<usize>) -> Vec<Note> {
+    let mut notes = vec![];
+    while let Some(index) = expansion {
+        let exp = &expansions[index];
+        notes.push(match exp.definition {
+            Some(span) => Note {
+                pos: Some(span),
+                msg: Message::InMacroExpansion(exp.name.clone()),
+            },
+            None => Note {
+                pos: None,
+                msg: Message::InPredefinedMacroExpansion(exp.name.clone()),
+            },
+        });
+        expansion = exp.parent;
+    }
+    notes
+}
+
+/// State of an `ifdef/`ifndef block
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+struct Conditional {
+    span: Span,
+    /// Whether tokens in the current branch are kept
+    active: bool,
+    /// Whether any branch has been taken
+    taken: bool,
+    /// Whether `else has been seen
+    has_else: bool,
+    /// Whether the enclosing region is active
+    parent_active: bool,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Preprocessor<'a> {
+    /// Tokens to be processed in reverse order, macro expansions are pushed
+    /// back here to be rescanned
+    input: Vec<ParsedToken<'a>>,
+    pub(crate) loc: Location,
+    pub(crate) file: FileId,
+    incdirs: Vec<PathBuf>,
+    sources: SourceManager,
+    conditionals: Vec<Conditional>,
+    /// Keyword sets of the `begin_keywords regions, the lexer of a file only
+    /// sees its own regions
+    keywords: Vec<KeywordVersion>,
+    pub macros: HashMap<String, MacroDefinition<'a>>,
+    pub expansions: Vec<MacroExpansion>,
+    pub tokens: Vec<ParsedToken<'a>>,
+    pub diag: Vec<Diagnostic>,
+}
+
+/// Diagnostics of a lexer without those on `begin_keywords regions, which may
+/// span files and are checked by the preprocessor
+fn without_keyword_regions(diag: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    diag.into_iter()
+        .filter(|diag| diag.msg != Message::UnmatchedEndKeywords)
+        .collect()
+}
+
+/// Lex macro text found at `origin`
+fn lex_at<'a>(text: &Cow<'a, str>, origin: Span) -> (Vec<ParsedToken<'a>>, Vec<Diagnostic>) {
+    let options = LexerOptions {
+        macro_text: true,
+        ..LexerOptions::default()
+    };
+    let (mut tokens, mut diag) = match text {
+        Cow::Borrowed(text) => {
+            let lexer = Lexer::lex_with_options(text, options);
+            (lexer.tokens, lexer.diag)
+        }
+        Cow::Owned(text) => {
+            let lexer = Lexer::lex_with_options(text, options);
+            let tokens = lexer
+                .tokens
+                .into_iter()
+                .map(ParsedToken::into_owned)
+                .collect();
+            (tokens, lexer.diag)
+        }
+    };
+    let shift = |span: &mut Span| {
+        for loc in [&mut span.from, &mut span.to] {
+            if loc.row == 0 {
+                loc.col += origin.from.col;
+            }
+            loc.row += origin.from.row;
+            loc.offset += origin.from.offset;
+        }
+        span.file = origin.file;
+    };
+    for token in &mut tokens {
+        shift(&mut token.span);
+    }
+    for diag in &mut diag {
+        shift(&mut diag.pos);
+    }
+    (tokens, diag)
+}
+
+/// Whether there is whitespace between two tokens
+fn has_gap(prev: &Span, next: &Span) -> bool {
+    prev.to.row != next.from.row || prev.to.col + 1 < next.from.col
+}
+
+/// Source text of tokens with whitespace collapsed
+fn spelling(tokens: &[ParsedToken<'_>]) -> String {
+    let mut res = String::new();
+    let mut prev: Option<Span> = None;
+    for token in tokens {
+        if let Some(prev) = prev {
+            if has_gap(&prev, &token.span) {
+                res.push(' ');
+            }
+        }
+        res.push_str(&token.text);
+        prev = Some(token.span);
+    }
+    res
+}
+
+fn is_name(token: &ParsedToken<'_>) -> bool {
+    token.token == Token::Identifier || token.token.keyword().is_some()
+}
+
+impl<'a> Preprocessor<'a> {
+    /// Preprocess an unnamed file, included files are resolved against the
+    /// include directories only
+    pub fn preprocess(lexer: Lexer<'a>, options: &PreprocessorOptions) -> Preprocessor<'a> {
+        let mut sources = SourceManager::new();
+        while sources.len() <= lexer.file.0 {
+            sources.add("", lexer.input);
+        }
+        Self::preprocess_with_sources(lexer, options, &mut sources)
+    }
+
+    /// Preprocess a file of `sources`, included files are added to `sources`
+    pub fn preprocess_with_sources(
+        lexer: Lexer<'a>,
+        options: &PreprocessorOptions,
+        sources: &mut SourceManager,
+    ) -> Preprocessor<'a> {
+        let keywords = vec![lexer.initial_keywords()];
+        let mut input = lexer.tokens;
+        input.reverse();
+        let origin = Span {
+            file: lexer.file,
+            from: Location::default(),
+            to: Location::default(),
+        };
+        let mut pp = Preprocessor {
+            input,
+            loc: lexer.loc,
+            file: lexer.file,
+            incdirs: options.incdirs.clone(),
+            sources: std::mem::take(sources),
+            conditionals: vec![],
+            keywords,
+            macros: options.macros.clone(),
+            expansions: vec![],
+            tokens: vec![],
+            diag: without_keyword_regions(lexer.diag),
+        };
+        for (name, text) in &options.defines {
+            let text: Cow<'a, str> = Cow::Owned(text.clone());
+            let (body, diag) = lex_at(&text, origin);
+            pp.diag.extend(diag);
+            pp.macros.insert(
+                name.clone(),
+                MacroDefinition {
+                    span: None,
+                    parameters: None,
+                    body,
+                },
+            );
+        }
+        pp.work();
+        *sources = std::mem::take(&mut pp.sources);
+        pp
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name)
+    }
+
+    fn report(&mut self, token: &ParsedToken<'a>, msg: Message, severity: Severity) {
+        self.diag.push(Diagnostic {
+            pos: token.span,
+            msg,
+            severity,
+            notes: expansion_notes(&self.expansions, token.expansion),
+        });
+    }
+
+    fn err(&mut self, token: &ParsedToken<'a>, msg: Message) {
+        self.report(token, msg, Severity::Error);
+    }
+
+    fn warn(&mut self, token: &ParsedToken<'a>, msg: Message) {
+        self.report(token, msg, Severity::Warning);
+    }
+
+    fn active(&self) -> bool {
+        self.conditionals.last().is_none_or(|cond| cond.active)
+    }
+
+    fn peek_on_line(&self, directive: &ParsedToken<'a>, token: Token) -> bool {
+        match self.input.last() {
+            Some(t) => t.token == token && t.span.from.row == directive.span.to.row,
+            None => false,
+        }
+    }
+
+    /// Next token on the same line as `directive`, skipping comments
+    fn next_on_line(&mut self, directive: &ParsedToken<'a>) -> Option<ParsedToken<'a>> {
+        while let Some(token) = self.input.last() {
+            if token.span.from.row != directive.span.to.row {
+                break;
+            }
+            let token = self.input.pop().unwrap();
+            if token.token != Token::Comment {
+                return Some(token);
+            }
+        }
+        None
+    }
+
+    /// Skip the remaining tokens on the line of `directive`
+    fn skip_line(&mut self, directive: &ParsedToken<'a>) {
+        while self.next_on_line(directive).is_some() {}
+    }
+
+    /// Macro name following `directive`
+    fn macro_name(&mut self, directive: &ParsedToken<'a>) -> Option<String> {
+        match self.next_on_line(directive) {
+            Some(token) if is_name(&token) => Some(token.text.into_owned()),
+            Some(token) => {
+                self.err(
+                    &token,
+                    Message::UnexpectedToken(Token::Identifier, token.text.to_string()),
+                );
+                None
+            }
+            None => {
+                self.err(
+                    directive,
+                    Message::UnexpectedToken(Token::Identifier, "end of line".to_owned()),
+                );
+                None
+            }
+        }
+    }
+
+    fn expect_on_line(&mut self, directive: &ParsedToken<'a>, expected: Token) {
+        match self.next_on_line(directive) {
+            Some(token) if token.token == expected => {}
+            Some(token) => self.err(
+                &token,
+                Message::UnexpectedToken(expected, token.text.to_string()),
+            ),
+            None => self.err(
+                directive,
+                Message::UnexpectedToken(expected, "end of line".to_owned()),
+            ),
+        }
+    }
+
+    // 22.6 `ifdef condition
+    // ifdef_condition ::= text_macro_identifier | ( ifdef_macro_expression )
+    // ifdef_macro_expression ::= text_macro_identifier | ( ifdef_macro_expression )
+    //   | ! ifdef_macro_expression | ifdef_macro_expression && ifdef_macro_expression
+    //   | ifdef_macro_expression || ifdef_macro_expression
+    fn condition(&mut self, directive: &ParsedToken<'a>) -> bool {
+        if self.peek_on_line(directive, Token::LParen) {
+            self.condition_not(directive)
+        } else {
+            match self.macro_name(directive) {
+                Some(name) => self.is_defined(&name),
+                None => false,
+            }
+        }
+    }
+
+    fn condition_or(&mut self, directive: &ParsedToken<'a>) -> bool {
+        let mut res = self.condition_and(directive);
+        while self.peek_on_line(directive, Token::OpOr) {
+            self.input.pop();
+            res |= self.condition_and(directive);
+        }
+        res
+    }
+
+    fn condition_and(&mut self, directive: &ParsedToken<'a>) -> bool {
+        let mut res = self.condition_not(directive);
+        while self.peek_on_line(directive, Token::OpAnd) {
+            self.input.pop();
+            res &= self.condition_not(directive);
+        }
+        res
+    }
+
+    fn condition_not(&mut self, directive: &ParsedToken<'a>) -> bool {
+        if self.peek_on_line(directive, Token::OpNot) {
+            self.input.pop();
+            !self.condition_not(directive)
+        } else if self.peek_on_line(directive, Token::LParen) {
+            self.input.pop();
+            let res = self.condition_or(directive);
+            self.expect_on_line(directive, Token::RParen);
+            res
+        } else {
+            match self.macro_name(directive) {
+                Some(name) => self.is_defined(&name),
+                None => false,
+            }
+        }
+    }
+
+    // 22.6 `ifdef, `else, `elsif, `endif, `ifndef
+    fn conditional(&mut self, directive: &ParsedToken<'a>) {
+        let unmatched = Message::UnmatchedConditional(directive.text.to_string());
+        match directive.text.as_ref() {
+            "`ifdef" | "`ifndef" => {
+                let parent_active = self.active();
+                let mut cond = self.condition(directive);
+                if directive.text == "`ifndef" {
+                    cond = !cond;
+                }
+                self.conditionals.push(Conditional {
+                    span: directive.span,
+                    active: parent_active && cond,
+                    taken: cond,
+                    has_else: false,
+                    parent_active,
+                });
+            }
+            "`elsif" => {
+                let cond = self.condition(directive);
+                match self.conditionals.pop() {
+                    Some(mut state) => {
+                        if state.has_else {
+                            self.err(
+                                directive,
+                                Message::ConditionalAfterElse(directive.text.to_string()),
+                            );
+                        }
+                        state.active = state.parent_active && !state.taken && cond;
+                        state.taken |= cond;
+                        self.conditionals.push(state);
+                    }
+                    None => self.err(directive, unmatched),
+                }
+            }
+            "`else" => match self.conditionals.pop() {
+                Some(mut state) => {
+                    if state.has_else {
+                        self.err(
+                            directive,
+                            Message::ConditionalAfterElse(directive.text.to_string()),
+                        );
+                    }
+                    state.active = state.parent_active && !state.taken;
+                    state.taken = true;
+                    state.has_else = true;
+                    self.conditionals.push(state);
+                }
+                None => self.err(directive, unmatched),
+            },
+            _ => {
+                // `endif
+                if self.conditionals.pop().is_none() {
+                    self.err(directive, unmatched);
+                }
+            }
+        }
+    }
+
+    /// Tokens up to `,` or `)` at the outermost level
+    fn macro_text_until_separator(
+        tokens: &[ParsedToken<'a>],
+        mut index: usize,
+    ) -> (Vec<ParsedToken<'a>>, usize) {
+        let mut res = vec![];
+        let mut depth = 0;
+        while index < tokens.len() {
+            match tokens[index].token {
+                Token::LParen | Token::LBracket | Token::LBraces | Token::ApostropheLBraces => {
+                    depth += 1
+                }
+                Token::RParen | Token::Comma if depth == 0 => break,
+                Token::RParen | Token::RBracket | Token::RBraces => depth -= 1,
+                _ => {}
+            }
+            res.push(tokens[index].clone());
+            index += 1;
+        }
+        (res, index)
+    }
+
+    // 22.5.1 `define
+    // text_macro_definition ::= `define text_macro_name macro_text
+    // text_macro_name ::= text_macro_identifier [ ( list_of_formal_arguments ) ]
+    fn define(&mut self, directive: &ParsedToken<'a>) {
+        let text = match self.input.last() {
+            Some(token) if token.token == Token::MacroText => self.input.pop().unwrap(),
+            _ => {
+                self.err(
+                    directive,
+                    Message::UnexpectedToken(Token::Identifier, "end of line".to_owned()),
+                );
+                return;
+            }
+        };
+        let (tokens, diag) = lex_at(&text.text, text.span);
+        self.diag.extend(diag);
+        if !is_name(&tokens[0]) {
+            self.err(
+                &tokens[0],
+                Message::UnexpectedToken(Token::Identifier, tokens[0].text.to_string()),
+            );
+            return;
+        }
+
+        let name = tokens[0].text.to_string();
+        let mut index = 1;
+        let mut parameters = None;
+        // no whitespace is allowed between the name and the parenthesis
+        if tokens.len() > 1
+            && tokens[1].token == Token::LParen
+            && !has_gap(&tokens[0].span, &tokens[1].span)
+        {
+            let mut params = vec![];
+            index = 2;
+            loop {
+                match tokens.get(index) {
+                    Some(token) if token.token == Token::RParen && params.is_empty() => {
+                        index += 1;
+                        break;
+                    }
+                    Some(token) if is_name(token) => {
+                        let mut param = MacroParameter {
+                            name: token.text.to_string(),
+                            default: None,
+                        };
+                        index += 1;
+                        if tokens.get(index).map(|token| token.token) == Some(Token::Equal) {
+                            let (default, next) =
+                                Self::macro_text_until_separator(&tokens, index + 1);
+                            param.default = Some(default);
+                            index = next;
+                        }
+                        params.push(param);
+                    }
+                    Some(token) => {
+                        let token = token.clone();
+                        self.err(
+                            &token,
+                            Message::UnexpectedToken(Token::Identifier, token.text.to_string()),
+                        );
+                        return;
+                    }
+                    None => {
+                        self.err(
+                            &text,
+                            Message::UnexpectedToken(Token::RParen, "end of line".to_owned()),
+                        );
+                        return;
+                    }
+                }
+                match tokens.get(index) {
+                    Some(token) if token.token == Token::Comma => index += 1,
+                    Some(token) if token.token == Token::RParen => {
+                        index += 1;
+                        break;
+                    }
+                    Some(token) => {
+                        let token = token.clone();
+                        self.err(
+                            &token,
+                            Message::UnexpectedTokens(
+                                vec![Token::Comma, Token::RParen],
+                                token.text.to_string(),
+                            ),
+                        );
+                        return;
+                    }
+                    None => {
+                        self.err(
+                            &text,
+                            Message::UnexpectedToken(Token::RParen, "end of line".to_owned()),
+                        );
+                        return;
+                    }
+                }
+            }
+            parameters = Some(params);
+        }
+
+        self.macros.insert(
+            name,
+            MacroDefinition {
+                span: Some(text.span),
+                parameters,
+                body: tokens[index..].to_vec(),
+            },
+        );
+    }
+
+    /// Actual arguments of a macro usage, and the closing parenthesis
+    fn macro_arguments(
+        &mut self,
+        usage: &ParsedToken<'a>,
+        name: &str,
+    ) -> Option<(Vec<Vec<ParsedToken<'a>>>, ParsedToken<'a>)> {
+        match self.input.last() {
+            Some(token) if token.token == Token::LParen => {}
+            _ => {
+                self.err(usage, Message::MissingMacroArguments(name.to_owned()));
+                return None;
+            }
+        }
+        self.input.pop();
+        let mut args = vec![vec![]];
+        let mut depth = 0;
+        while let Some(token) = self.input.pop() {
+            match token.token {
+                Token::Comment => continue,
+                Token::RParen if depth == 0 => {
+                    return Some((args, token));
+                }
+                Token::Comma if depth == 0 => {
+                    args.push(vec![]);
+                    continue;
+                }
+                Token::LParen | Token::LBracket | Token::LBraces | Token::ApostropheLBraces => {
+                    depth += 1
+                }
+                Token::RParen | Token::RBracket | Token::RBraces => depth -= 1,
+                _ => {}
+            }
+            args.last_mut().unwrap().push(token);
+        }
+        self.err(usage, Message::UnterminatedMacroArguments(name.to_owned()));
+        None
+    }
+
+    /// Append a token to the expansion, pasting it to the previous one after ``
+    fn append(&mut self, out: &mut Vec<ParsedToken<'a>>, token: ParsedToken<'a>, paste: &mut bool) {
+        if std::mem::take(paste) {
+            if let Some(prev) = out.pop() {
+                let text: Cow<'a, str> = Cow::Owned(format!("{}{}", prev.text, token.text));
+                let (mut tokens, diag) = lex_at(&text, prev.span);
+                self.diag.extend(diag);
+                for token in &mut tokens {
+                    token.span = prev.span;
+                    token.expansion = prev.expansion;
+                }
+                out.extend(tokens);
+                return;
+            }
+        }
+        out.push(token);
+    }
+
+    /// Text of a `" ... `" stringification
+    fn stringify(
+        tokens: &[ParsedToken<'a>],
+        args: &HashMap<String, Vec<ParsedToken<'a>>>,
+    ) -> String {
+        let mut res = String::from("\"");
+        let mut prev: Option<Span> = None;
+        let mut paste = false;
+        for token in tokens {
+            if token.token == Token::MacroPaste {
+                paste = true;
+                continue;
+            }
+            if let Some(prev) = prev {
+                if !paste && has_gap(&prev, &token.span) {
+                    res.push(' ');
+                }
+            }
+            match args.get(token.text.as_ref()) {
+                Some(arg) if is_name(token) => res.push_str(&spelling(arg)),
+                _ if token.token == Token::MacroEscapedQuote => res.push_str("\\\""),
+                _ => res.push_str(&token.text),
+            }
+            prev = Some(token.span);
+            paste = false;
+        }
+        res.push('"');
+        res
+    }
+
+    /// Substitute actual arguments into the macro body
+    fn substitute(
+        &mut self,
+        def: &MacroDefinition<'a>,
+        args: &HashMap<String, Vec<ParsedToken<'a>>>,
+        site: Span,
+        expansion: usize,
+    ) -> Vec<ParsedToken<'a>> {
+        let body = &def.body;
+        let mut out = vec![];
+        let mut paste = false;
+        let mut index = 0;
+        // tokens from the macro text are located at the usage, tokens from
+        // actual arguments keep their own location
+        let relocate = |token: &ParsedToken<'a>| ParsedToken {
+            span: site,
+            expansion: Some(expansion),
+            ..token.clone()
+        };
+        while index < body.len() {
+            let token = &body[index];
+            index += 1;
+            match token.token {
+                Token::MacroPaste => {
+                    paste = true;
+                }
+                Token::MacroQuote => {
+                    let end = match body[index..]
+                        .iter()
+                        .position(|token| token.token == Token::MacroQuote)
+                    {
+                        Some(len) => index + len,
+                        None => {
+                            self.err(&relocate(token), Message::UnterminatedStringification);
+                            body.len()
+                        }
+                    };
+                    let string = ParsedToken {
+                        token: Token::StringLiteral,
+                        text: Self::stringify(&body[index..end], args).into(),
+                        ..relocate(token)
+                    };
+                    self.append(&mut out, string, &mut paste);
+                    index = end + 1;
+                }
+                _ => match args.get(token.text.as_ref()) {
+                    Some(arg) if is_name(token) => {
+                        for arg_token in arg {
+                            self.append(&mut out, arg_token.clone(), &mut paste);
+                        }
+                    }
+                    _ => self.append(&mut out, relocate(token), &mut paste),
+                },
+            }
+        }
+        out
+    }
+
+    // 22.5.1 `text_macro_identifier [ ( list_of_actual_arguments ) ]
+    fn expand(&mut self, usage: ParsedToken<'a>) {
+        let name = usage.text[1..].to_string();
+        if name == "__FILE__" {
+            let path = self.sources.path(usage.span.file).display().to_string();
+            self.tokens.push(ParsedToken {
+                token: Token::StringLiteral,
+                text: format!("{:?}", path).into(),
+                ..usage
+            });
+            return;
+        }
+        if name == "__LINE__" {
+            self.tokens.push(ParsedToken {
+                token: Token::Number,
+                text: format!("{}", usage.span.from.row + 1).into(),
+                ..usage
+            });
+            return;
+        }
+        let def = match self.macros.get(&name) {
+            Some(def) => def.clone(),
+            None => {
+                self.err(&usage, Message::UndefinedMacro(name));
+                return;
+            }
+        };
+
+        let mut parent = usage.expansion;
+        while let Some(index) = parent {
+            if self.expansions[index].name == name {
+                self.err(&usage, Message::RecursiveMacro(name));
+                return;
+            }
+            parent = self.expansions[index].parent;
+        }
+
+        let mut site = usage.span;
+        let mut args = HashMap::new();
+        if let Some(params) = &def.parameters {
+            let (mut actual, end) = match self.macro_arguments(&usage, &name) {
+                Some(res) => res,
+                None => return,
+            };
+            if usage.expansion.is_none() {
+                site.to = end.span.to;
+            }
+            if params.is_empty() && actual.len() == 1 && actual[0].is_empty() {
+                actual.clear();
+            }
+            if actual.len() > params.len() {
+                self.err(&usage, Message::TooManyMacroArguments(name.clone()));
+            }
+            for (i, param) in params.iter().enumerate() {
+                let arg = match (actual.get(i), &param.default) {
+                    (Some(arg), Some(default)) if arg.is_empty() => default.clone(),
+                    (Some(arg), _) => arg.clone(),
+                    (None, Some(default)) => default.clone(),
+                    (None, None) => {
+                        self.err(
+                            &usage,
+                            Message::MissingMacroArgument(name.clone(), param.name.clone()),
+                        );
+                        vec![]
+                    }
+                };
+                args.insert(param.name.clone(), arg);
+            }
+        }
+
+        let index = self.expansions.len();
+        self.expansions.push(MacroExpansion {
+            name,
+            site,
+            definition: def.span,
+            parent: usage.expansion,
+        });
+        let tokens = self.substitute(&def, &args, site, index);
+        self.input.extend(tokens.into_iter().rev());
+    }
+
+    // 22.4 `include
+    // `include "filename" | `include <filename>
+    fn include(&mut self, directive: &ParsedToken<'a>) {
+        let (name, angle) = match self.next_on_line(directive) {
+            Some(token) if token.token == Token::StringLiteral && token.text.len() >= 2 => {
+                (token.text[1..token.text.len() - 1].to_owned(), false)
+            }
+            Some(token) if token.token == Token::OpLessThan => {
+                let mut name = vec![];
+                loop {
+                    match self.next_on_line(directive) {
+                        Some(token) if token.token == Token::OpGreaterThan => break,
+                        Some(token) => name.push(token),
+                        None => {
+                            self.err(
+                                directive,
+                                Message::UnexpectedToken(
+                                    Token::OpGreaterThan,
+                                    "end of line".to_owned(),
+                                ),
+                            );
+                            return;
+                        }
+                    }
+                }
+                (spelling(&name), true)
+            }
+            Some(token) if token.token == Token::Directive => {
+                // the file name is given by a macro
+                self.expand(token);
+                return self.include(directive);
+            }
+            Some(token) => {
+                self.err(
+                    &token,
+                    Message::UnexpectedToken(Token::StringLiteral, token.text.to_string()),
+                );
+                return;
+            }
+            None => {
+                self.err(
+                    directive,
+                    Message::UnexpectedToken(Token::StringLiteral, "end of line".to_owned()),
+                );
+                return;
+            }
+        };
+
+        let from = directive.span.file;
+        let path = match self
+            .sources
+            .resolve_include(&name, from, angle, &self.incdirs)
+        {
+            Some(path) => path,
+            None => {
+                self.err(directive, Message::IncludeNotFound(name));
+                return;
+            }
+        };
+        if self.sources.is_cyclic(from, &path) {
+            self.err(directive, Message::IncludeCycle(name));
+            return;
+        }
+        let file = match self.sources.include(&path, directive.span) {
+            Ok(file) => file,
+            Err(err) => {
+                self.err(
+                    directive,
+                    Message::CannotReadFile(path.display().to_string(), err.to_string()),
+                );
+                return;
+            }
+        };
+
+        let text = self.sources.text(file);
+        let options = LexerOptions {
+            keywords: *self.keywords.last().unwrap(),
+            file,
+            ..LexerOptions::default()
+        };
+        let lexer = Lexer::lex_with_options(&text, options);
+        self.diag.extend(without_keyword_regions(lexer.diag));
+        self.input.extend(
+            lexer
+                .tokens
+                .into_iter()
+                .rev()
+                .map(|token| token.into_owned()),
+        );
+    }
+
+    fn directive(&mut self, directive: ParsedToken<'a>) {
+        match directive.text.as_ref() {
+            "`ifdef" | "`ifndef" | "`elsif" | "`else" | "`endif" => {
+                self.conditional(&directive);
+            }
+            _ if !self.active() => {
+                // ignored in inactive regions, including the macro text
+                // of `define
+            }
+            "`define" => self.define(&directive),
+            "`undef" => {
+                if let Some(name) = self.macro_name(&directive) {
+                    if self.macros.remove(&name).is_none() {
+                        self.warn(&directive, Message::UndefinedMacro(name));
+                    }
+                }
+            }
+            "`undefineall" => {
+                self.macros.clear();
+            }
+            "`begin_keywords" => {
+                // the lexer reports invalid versions
+                let current = *self.keywords.last().unwrap();
+                let version = match self.input.last() {
+                    Some(token) if self.peek_on_line(&directive, Token::StringLiteral) => {
+                        KeywordVersion::from_specifier(token.text.trim_matches('"'))
+                    }
+                    _ => None,
+                };
+                self.keywords.push(version.unwrap_or(current));
+                self.skip_line(&directive);
+            }
+            "`end_keywords" => {
+                if self.keywords.len() > 1 {
+                    self.keywords.pop();
+                } else {
+                    self.err(&directive, Message::UnmatchedEndKeywords);
+                }
+                self.skip_line(&directive);
+            }
+            "`celldefine"
+            | "`endcelldefine"
+            | "`default_nettype"
+            | "`line"
+            | "`nounconnected_drive"
+            | "`pragma"
+            | "`resetall"
+            | "`timescale"
+            | "`unconnected_drive" => {
+                // handled by the lexer or not relevant to parsing
+                self.skip_line(&directive);
+            }
+            "`include" => self.include(&directive),
+            _ => self.expand(directive),
+        }
+    }
+
+    fn work(&mut self) {
+        while let Some(token) = self.input.pop() {
+            if token.token == Token::Directive {
+                self.directive(token);
+            } else if self.active() {
+                let mut token = token;
+                // keywords of a `begin_keywords region that started in
+                // another file
+                if let Some(keyword) = self.keywords.last().unwrap().keyword_token(&token.text) {
+                    token.token = keyword;
+                }
+                self.tokens.push(token);
+            }
+        }
+
+        while let Some(state) = self.conditionals.pop() {
+            self.diag.push(Diagnostic {
+                pos: state.span,
+                msg: Message::UnterminatedConditional,
+                severity: Severity::Error,
+                notes: vec![],
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn preprocess<'a>(input: &'a str, defines: &[&str]) -> Preprocessor<'a> {
+        let mut options = PreprocessorOptions::default();
+        for define in defines {
+            options.define(define);
+        }
+        Preprocessor::preprocess(Lexer::lex(input), &options)
+    }
+
+    fn texts(pp: &Preprocessor<'_>) -> Vec<String> {
+        pp.tokens
+            .iter()
+            .map(|token| token.text.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn ifdef() {
+        let input = "`ifdef FOO a `elsif BAR b `else c `endif d";
+        assert_eq!(texts(&preprocess(input, &[])), vec!["c", "d"]);
+        assert_eq!(texts(&preprocess(input, &["FOO=1"])), vec!["a", "d"]);
+        assert_eq!(texts(&preprocess(input, &["BAR"])), vec!["b", "d"]);
+        assert_eq!(texts(&preprocess(input, &["FOO", "BAR"])), vec!["a", "d"]);
+
+        let input = "`ifndef FOO\na\n`endif\nb";
+        assert_eq!(texts(&preprocess(input, &[])), vec!["a", "b"]);
+        assert_eq!(texts(&preprocess(input, &["FOO"])), vec!["b"]);
+    }
+
+    #[test]
+    fn nested_ifdef() {
+        let input = "`ifdef A `ifdef B ab `else a `endif `else `ifdef B b `else none `endif `endif";
+        assert_eq!(texts(&preprocess(input, &[])), vec!["none"]);
+        assert_eq!(texts(&preprocess(input, &["A"])), vec!["a"]);
+        assert_eq!(texts(&preprocess(input, &["B"])), vec!["b"]);
+        assert_eq!(texts(&preprocess(input, &["A", "B"])), vec!["ab"]);
+    }
+
+    #[test]
+    fn ifdef_expression() {
+        let input = "`ifdef (A && !B) x `elsif (A || (B && C)) y `endif";
+        assert_eq!(texts(&preprocess(input, &["A"])), vec!["x"]);
+        assert_eq!(texts(&preprocess(input, &["A", "B"])), vec!["y"]);
+        assert_eq!(texts(&preprocess(input, &["B", "C"])), vec!["y"]);
+        assert_eq!(texts(&preprocess(input, &["B"])), Vec::<String>::new());
+    }
+
+    #[test]
+    fn define_undef() {
+        let input = r#"`define FOO 1
+`ifdef FOO a `endif
+`undef FOO
+`ifdef FOO b `endif
+`define BAR(x) \
+  x + 1 // comment
+`ifdef BAR c `endif
+`undefineall
+`ifdef BAR d `endif
+`ifdef PREDEFINED e `endif"#;
+        let pp = preprocess(input, &[]);
+        assert_eq!(texts(&pp), vec!["a", "// comment", "c"]);
+        assert_eq!(pp.diag.len(), 0);
+
+        let pp = preprocess("`define FOO(x) \\\n  x + 1 // comment\nfoo", &[]);
+        let def = &pp.macros["FOO"];
+        assert_eq!(def.parameters.as_ref().unwrap()[0].name, "x");
+        assert_eq!(def.body.len(), 3);
+        assert_eq!(
+            def.body[2].span.from,
+            Location {
+                row: 1,
+                col: 6,
+                offset: 23
+            }
+        );
+        assert_eq!(texts(&pp), vec!["// comment", "foo"]);
+
+        let pp = preprocess("`ifdef FOO `define BAR `endif `ifdef BAR a `endif", &[]);
+        assert!(!pp.is_defined("BAR"));
+        assert_eq!(pp.tokens.len(), 0);
+    }
+
+    #[test]
+    fn other_directives() {
+        let pp = preprocess("`timescale 1ns/1ps\nmodule `resetall\nendmodule", &[]);
+        assert_eq!(texts(&pp), vec!["module", "endmodule"]);
+    }
+
+    #[test]
+    fn unbalanced() {
+        let pp = preprocess("`ifdef FOO\na", &[]);
+        assert_eq!(pp.diag.len(), 1);
+        assert_eq!(pp.diag[0].msg, Message::UnterminatedConditional);
+
+        let pp = preprocess("a `endif `else `elsif B", &[]);
+        assert_eq!(pp.diag.len(), 3);
+        assert_eq!(
+            pp.diag[0].msg,
+            Message::UnmatchedConditional("`endif".to_owned())
+        );
+
+        let pp = preprocess("`ifdef A `else `else `endif", &[]);
+        assert_eq!(pp.diag.len(), 1);
+        assert_eq!(
+            pp.diag[0].msg,
+            Message::ConditionalAfterElse("`else".to_owned())
+        );
+        assert_eq!(pp.diag[0].msg.to_string(), "`else after `else");
+
+        let pp = preprocess("`ifdef A `else `elsif B `endif", &[]);
+        assert_eq!(pp.diag.len(), 1);
+        assert_eq!(
+            pp.diag[0].msg,
+            Message::ConditionalAfterElse("`elsif".to_owned())
+        );
+
+        let pp = preprocess("`ifdef\n`endif", &[]);
+        assert_eq!(pp.diag.len(), 1);
+    }
+
+    #[test]
+    fn object_like_macro() {
+        let pp = preprocess("`define W 8\n`define MSB (`W - 1)\nwire [`MSB:0] a;", &[]);
+        assert_eq!(
+            texts(&pp),
+            vec!["wire", "[", "(", "8", "-", "1", ")", ":", "0", "]", "a", ";"]
+        );
+        assert_eq!(pp.diag.len(), 0);
+        // located at the usage
+        assert_eq!(
+            pp.tokens[3].span.from,
+            Location {
+                row: 2,
+                col: 6,
+                offset: 39
+            }
+        );
+        let exp = &pp.expansions[pp.tokens[3].expansion.unwrap()];
+        assert_eq!(exp.name, "W");
+        assert_eq!(
+            exp.definition.unwrap().from,
+            Location {
+                row: 0,
+                col: 8,
+                offset: 8
+            }
+        );
+        let exp = &pp.expansions[exp.parent.unwrap()];
+        assert_eq!(exp.name, "MSB");
+        assert_eq!(
+            exp.site.from,
+            Location {
+                row: 2,
+                col: 6,
+                offset: 39
+            }
+        );
+
+        let pp = preprocess("`X", &["X=x"]);
+        assert_eq!(texts(&pp), vec!["x"]);
+        assert_eq!(pp.expansions[0].definition, None);
+    }
+
+    #[test]
+    fn function_like_macro() {
+        let pp = preprocess(
+            "`define ADD(a, b=1) a + b\n`ADD(x, y) `ADD(x) `ADD(f(1, 2), ) `ADD((p, q), {r, s})",
+            &[],
+        );
+        assert_eq!(
+            texts(&pp),
+            vec![
+                "x", "+", "y", "x", "+", "1", "f", "(", "1", ",", "2", ")", "+", "1", "(", "p",
+                ",", "q", ")", "+", "{", "r", ",", "s", "}"
+            ]
+        );
+        assert_eq!(pp.diag.len(), 0);
+        // tokens from arguments keep their location
+        assert_eq!(
+            pp.tokens[0].span.from,
+            Location {
+                row: 1,
+                col: 5,
+                offset: 31
+            }
+        );
+        assert_eq!(
+            pp.tokens[1].span.from,
+            Location {
+                row: 1,
+                col: 0,
+                offset: 26
+            }
+        );
+        assert_eq!(
+            pp.tokens[1].span.to,
+            Location {
+                row: 1,
+                col: 9,
+                offset: 35
+            }
+        );
+
+        let pp = preprocess("`define N() 1\n`define M(a) a\n`N() `M() `M(`M(2))", &[]);
+        assert_eq!(texts(&pp), vec!["1", "2"]);
+        assert_eq!(pp.diag.len(), 0);
+    }
+
+    #[test]
+    fn macro_argument_errors() {
+        let pp = preprocess("`define M(a, b) a\n`M(1) `M(1, 2, 3) `M `M(1", &[]);
+        let msgs: Vec<Message> = pp.diag.iter().map(|diag| diag.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                Message::MissingMacroArgument("M".to_owned(), "b".to_owned()),
+                Message::TooManyMacroArguments("M".to_owned()),
+                Message::MissingMacroArguments("M".to_owned()),
+                Message::UnterminatedMacroArguments("M".to_owned()),
+            ]
+        );
+
+        let pp = preprocess("`UNDEFINED", &[]);
+        assert_eq!(
+            pp.diag[0].msg,
+            Message::UndefinedMacro("UNDEFINED".to_owned())
+        );
+    }
+
+    #[test]
+    fn stringification() {
+        let pp = preprocess(
+            r#"`define MSG(x, y) `"x: `\`"y`\`"`"
+`define STR(a) `"a`"
+`define CAT(a) `"pre``a`"
+`MSG(value, some  text) `STR(  1 +   2 ) `CAT(fix)"#,
+            &[],
+        );
+        assert_eq!(
+            texts(&pp),
+            vec![r#""value: \"some text\"""#, r#""1 + 2""#, r#""prefix""#]
+        );
+        assert_eq!(pp.tokens[0].token, Token::StringLiteral);
+
+        let pp = preprocess("`define S `\"abc\n`S", &[]);
+        assert_eq!(pp.diag[0].msg, Message::UnterminatedStringification);
+    }
+
+    #[test]
+    fn token_pasting() {
+        let pp = preprocess(
+            "`define REG(name, n) reg name``_q``n;\n`define PASTE(a, b) a``b\n`REG(data, 0) `PASTE(1, 2)",
+            &[],
+        );
+        assert_eq!(texts(&pp), vec!["reg", "data_q0", ";", "12"]);
+        assert_eq!(pp.tokens[1].token, Token::Identifier);
+        assert_eq!(pp.tokens[3].token, Token::Number);
+    }
+
+    #[test]
+    fn recursive_macro() {
+        let pp = preprocess("`define A `B\n`define B x `A\n`A", &[]);
+        assert_eq!(texts(&pp), vec!["x"]);
+        assert_eq!(pp.diag.len(), 1);
+        assert_eq!(pp.diag[0].msg, Message::RecursiveMacro("A".to_owned()));
+        assert_eq!(pp.diag[0].notes.len(), 2);
+        assert_eq!(
+            format!("{}", pp.diag[0]),
+            "Error at (2:0-2:1): Macro expands to itself: A\n    in expansion of macro B defined at 1:8\n    in expansion of macro A defined at 0:8"
+        );
+    }
+
+    #[test]
+    fn include() {
+        let mut sources = SourceManager::new();
+        let top = sources.add("top.sv", "`include \"inc/a.svh\"\n`A `__FILE__");
+        sources.add("inc/a.svh", "`define A a\n`define B <b.svh>\n`include `B");
+        sources.add("lib/b.svh", "b");
+        let options = PreprocessorOptions {
+            incdirs: vec![PathBuf::from("lib")],
+            ..PreprocessorOptions::default()
+        };
+        let text = sources.text(top);
+        let lexer = Lexer::lex_with_options(
+            &text,
+            LexerOptions {
+                file: top,
+                ..LexerOptions::default()
+            },
+        );
+        let pp = Preprocessor::preprocess_with_sources(lexer, &options, &mut sources);
+        assert_eq!(texts(&pp), vec!["b", "a", "\"top.sv\""]);
+        assert_eq!(pp.diag.len(), 0);
+        let file = pp.tokens[0].span.file;
+        assert_eq!(sources.path(file), Path::new("lib/b.svh"));
+        assert_eq!(sources.include_chain(file).len(), 2);
+        assert_eq!(sources.include_chain(file)[1].file, top);
+    }
+
+    #[test]
+    fn include_keywords() {
+        let mut sources = SourceManager::new();
+        let top = sources.add(
+            "top.sv",
+            "`begin_keywords \"1364-1995\"\n`include \"a.svh\"\n\
+             logic\n`end_keywords\nlogic\n`end_keywords\nlogic\n`end_keywords",
+        );
+        sources.add("a.svh", "logic\n`begin_keywords \"1364-2005\"\nuwire logic");
+        let text = sources.text(top);
+        let lexer = Lexer::lex_with_options(
+            &text,
+            LexerOptions {
+                file: top,
+                ..LexerOptions::default()
+            },
+        );
+        let options = PreprocessorOptions::default();
+        let pp = Preprocessor::preprocess_with_sources(lexer, &options, &mut sources);
+        let tokens: Vec<Token> = pp.tokens.iter().map(|token| token.token).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier,
+                Token::Uwire,
+                Token::Identifier,
+                Token::Identifier,
+                Token::Identifier,
+                Token::Logic
+            ]
+        );
+        assert_eq!(pp.diag.len(), 1);
+        assert_eq!(pp.diag[0].msg, Message::UnmatchedEndKeywords);
+    }
+
+    #[test]
+    fn include_errors() {
+        let mut sources = SourceManager::new();
+        let top = sources.add(
+            "top.sv",
+            "`include \"top.sv\"\n`include \"none.svh\"\n`include x",
+        );
+        let text = sources.text(top);
+        let lexer = Lexer::lex_with_options(
+            &text,
+            LexerOptions {
+                file: top,
+                ..LexerOptions::default()
+            },
+        );
+        let options = PreprocessorOptions::default();
+        let pp = Preprocessor::preprocess_with_sources(lexer, &options, &mut sources);
+        let msgs: Vec<Message> = pp.diag.iter().map(|diag| diag.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                Message::IncludeCycle("top.sv".to_owned()),
+                Message::IncludeNotFound("none.svh".to_owned()),
+                Message::UnexpectedToken(Token::StringLiteral, "x".to_owned()),
+            ]
+        );
+
+        // the same header through a different path
+        let mut sources = SourceManager::new();
+        let top = sources.add("top.sv", "`include \"dir/a.svh\"");
+        sources.add("dir/a.svh", "`include \"../dir/a.svh\"");
+        let text = sources.text(top);
+        let lexer = Lexer::lex_with_options(
+            &text,
+            LexerOptions {
+                file: top,
+                ..LexerOptions::default()
+            },
+        );
+        let pp = Preprocessor::preprocess_with_sources(lexer, &options, &mut sources);
+        let msgs: Vec<Message> = pp.diag.iter().map(|diag| diag.msg.clone()).collect();
+        assert_eq!(msgs, vec![Message::IncludeCycle("../dir/a.svh".to_owned())]);
+        assert_eq!(sources.len(), 3);
+    }
+
+    #[test]
+    fn line_macro() {
+        let pp = preprocess("\n\n`__LINE__", &[]);
+        assert_eq!(texts(&pp), vec!["3"]);
+    }
+}
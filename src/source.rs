@@ -0,0 +1,302 @@
+//! Source files
+
+use crate::diagnostic::Diagnostic;
+use crate::lexer::Span;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Identifies one inclusion of a source file in a `SourceManager`
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize, Default,
+)]
+pub struct FileId(pub usize);
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub text: Arc<str>,
+    /// Location of the `include directive, `None` for top-level files
+    pub included_from: Option<Span>,
+}
+
+/// Owns the text of all source files
+///
+/// Every `include creates a new `FileId`, so the same header included twice
+/// has two ids sharing the same text.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct SourceManager {
+    files: Vec<SourceFile>,
+    /// Texts by the `file_key` of their path
+    cache: HashMap<PathBuf, Arc<str>>,
+}
+
+/// Path that is the same for all spellings of the path of a file
+///
+/// Existing files are canonicalized, the paths of files that only exist in
+/// memory lose their `.` and `..` components.
+fn file_key(path: &Path) -> PathBuf {
+    if let Ok(path) = std::fs::canonicalize(path) {
+        return path;
+    }
+    let mut res = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(res.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                res.pop();
+            }
+            component => res.push(component),
+        }
+    }
+    res
+}
+
+impl SourceManager {
+    pub fn new() -> SourceManager {
+        Self::default()
+    }
+
+    fn push(&mut self, path: PathBuf, text: Arc<str>, included_from: Option<Span>) -> FileId {
+        self.files.push(SourceFile {
+            path,
+            text,
+            included_from,
+        });
+        FileId(self.files.len() - 1)
+    }
+
+    /// Add a top-level file whose text is already in memory
+    pub fn add(&mut self, path: impl Into<PathBuf>, text: impl Into<Arc<str>>) -> FileId {
+        let path = path.into();
+        let text = text.into();
+        self.cache.insert(file_key(&path), text.clone());
+        self.push(path, text, None)
+    }
+
+    fn read(&mut self, path: &Path) -> std::io::Result<Arc<str>> {
+        let key = file_key(path);
+        if let Some(text) = self.cache.get(&key) {
+            return Ok(text.clone());
+        }
+        let text: Arc<str> = std::fs::read_to_string(path)?.into();
+        self.cache.insert(key, text.clone());
+        Ok(text)
+    }
+
+    /// Read a top-level file
+    pub fn load(&mut self, path: impl AsRef<Path>) -> std::io::Result<FileId> {
+        let path = path.as_ref();
+        let text = self.read(path)?;
+        Ok(self.push(path.to_owned(), text, None))
+    }
+
+    /// Read a file included at `from`
+    pub fn include(&mut self, path: impl AsRef<Path>, from: Span) -> std::io::Result<FileId> {
+        let path = path.as_ref();
+        let text = self.read(path)?;
+        Ok(self.push(path.to_owned(), text, Some(from)))
+    }
+
+    pub fn file(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0]
+    }
+
+    pub fn path(&self, id: FileId) -> &Path {
+        &self.files[id.0].path
+    }
+
+    pub fn text(&self, id: FileId) -> Arc<str> {
+        self.files[id.0].text.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Locations of the `include directives that led to `id`, innermost first
+    pub fn include_chain(&self, id: FileId) -> Vec<Span> {
+        let mut res = vec![];
+        let mut current = self.file(id).included_from;
+        while let Some(span) = current {
+            res.push(span);
+            current = self.file(span.file).included_from;
+        }
+        res
+    }
+
+    /// Whether including `path` from `id` would include a file in itself
+    pub fn is_cyclic(&self, id: FileId, path: &Path) -> bool {
+        let key = file_key(path);
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if file_key(self.path(id)) == key {
+                return true;
+            }
+            current = self.file(id).included_from.map(|span| span.file);
+        }
+        false
+    }
+
+    /// 22.4 `include
+    ///
+    /// A relative `"name"` is searched in the directory of the including file
+    /// first, then in the include directories. A `<name>` is only searched in
+    /// the include directories.
+    pub fn resolve_include(
+        &self,
+        name: &str,
+        from: FileId,
+        angle: bool,
+        incdirs: &[PathBuf],
+    ) -> Option<PathBuf> {
+        let name = Path::new(name);
+        if name.is_absolute() {
+            return if name.is_file() {
+                Some(name.to_owned())
+            } else {
+                None
+            };
+        }
+        let mut candidates = vec![];
+        if !angle && from.0 < self.files.len() {
+            match self.path(from).parent() {
+                Some(dir) => candidates.push(dir.join(name)),
+                None => candidates.push(name.to_owned()),
+            }
+        }
+        candidates.extend(incdirs.iter().map(|dir| dir.join(name)));
+        candidates
+            .into_iter()
+            .find(|path| self.cache.contains_key(&file_key(path)) || path.is_file())
+    }
+
+    /// Render a diagnostic with file names
+    pub fn render(&self, diag: &Diagnostic) -> String {
+        let mut res = format!(
+            "{}:{}: {:?}: {}",
+            self.path(diag.pos.file).display(),
+            diag.pos.from,
+            diag.severity,
+            diag.msg
+        );
+        for note in &diag.notes {
+            match note.pos {
+                Some(pos) => write!(
+                    res,
+                    "\n    {} at {}:{}",
+                    note.msg,
+                    self.path(pos.file).display(),
+                    pos.from
+                ),
+                None => write!(res, "\n    {}", note.msg),
+            }
+            .unwrap();
+        }
+        for span in self.include_chain(diag.pos.file) {
+            write!(
+                res,
+                "\n    included from {}:{}",
+                self.path(span.file).display(),
+                span.from
+            )
+            .unwrap();
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{Message, Severity};
+    use crate::lexer::Location;
+
+    fn span(file: FileId, row: usize, col: usize) -> Span {
+        Span {
+            file,
+            from: Location {
+                row,
+                col,
+                offset: 0,
+            },
+            to: Location {
+                row,
+                col,
+                offset: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn include_chain() {
+        let mut sources = SourceManager::new();
+        let top = sources.add("top.sv", "`include \"a.svh\"");
+        sources.add("dir/a.svh", "`include \"b.svh\"");
+        sources.add("dir/b.svh", "");
+        let a = sources.include("dir/a.svh", span(top, 0, 1)).unwrap();
+        let b = sources.include("dir/b.svh", span(a, 0, 1)).unwrap();
+        assert_eq!(sources.len(), 5);
+        assert_eq!(
+            sources.include_chain(b),
+            vec![span(a, 0, 1), span(top, 0, 1)]
+        );
+        assert!(sources.is_cyclic(b, Path::new("dir/a.svh")));
+        assert!(sources.is_cyclic(b, Path::new("./dir/../dir/a.svh")));
+        assert!(!sources.is_cyclic(a, Path::new("dir/b.svh")));
+        assert_eq!(
+            sources.resolve_include("../dir/b.svh", a, false, &[]),
+            Some(PathBuf::from("dir/../dir/b.svh"))
+        );
+
+        assert_eq!(
+            sources.resolve_include("b.svh", a, false, &[]),
+            Some(PathBuf::from("dir/b.svh"))
+        );
+        assert_eq!(sources.resolve_include("b.svh", a, true, &[]), None);
+        assert_eq!(
+            sources.resolve_include("b.svh", top, true, &[PathBuf::from("dir")]),
+            Some(PathBuf::from("dir/b.svh"))
+        );
+
+        let diag = Diagnostic {
+            pos: span(b, 2, 3),
+            msg: Message::UnterminatedConditional,
+            severity: Severity::Error,
+            notes: vec![],
+        };
+        assert_eq!(
+            sources.render(&diag),
+            "dir/b.svh:2:3: Error: Conditional is not closed by `endif\n    included from dir/a.svh:0:1\n    included from top.sv:0:1"
+        );
+    }
+
+    #[test]
+    fn file_key() {
+        assert_eq!(
+            super::file_key(Path::new("./a/../b/./c.sv")),
+            PathBuf::from("b/c.sv")
+        );
+        assert_eq!(
+            super::file_key(Path::new("../x/../y.sv")),
+            PathBuf::from("../y.sv")
+        );
+
+        // an existing file is found through any spelling of its path
+        let dir = std::env::temp_dir().join(format!("source-key-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("inc")).unwrap();
+        std::fs::write(dir.join("inc/a.svh"), "").unwrap();
+        let mut sources = SourceManager::new();
+        let a = sources.load(dir.join("inc/a.svh")).unwrap();
+        assert!(sources.is_cyclic(a, &dir.join("inc/../inc/./a.svh")));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
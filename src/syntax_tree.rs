@@ -0,0 +1,159 @@
+//! Owned syntax trees
+
+use crate::ast::{Expression, Identifier, NodeSpan, Number, Parse, SourceText, StringLiteral};
+use crate::diagnostic::Diagnostic;
+use crate::lexer::{Lexer, LexerOptions, ParsedToken, Span};
+use crate::logic_value::BitVector;
+use crate::parser::Parser;
+use crate::preprocessor::{Preprocessor, PreprocessorOptions};
+use crate::source::{FileId, SourceManager};
+use std::sync::Arc;
+
+/// A parsed file that owns its text and tokens
+///
+/// AST nodes refer to tokens by index, a `SyntaxTree` keeps the tokens
+/// alive after the `Parser` is gone so the nodes can still be resolved.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SyntaxTree {
+    source: Arc<str>,
+    tokens: Vec<ParsedToken<'static>>,
+    diag: Vec<Diagnostic>,
+    root: SourceText,
+}
+
+impl SyntaxTree {
+    /// Parse a standalone text
+    pub fn parse(source: impl Into<Arc<str>>) -> SyntaxTree {
+        let mut sources = SourceManager::new();
+        let file = sources.add("", source);
+        Self::parse_file(file, &PreprocessorOptions::default(), &mut sources)
+    }
+
+    /// Parse a file of `sources`, included files are added to `sources`
+    pub fn parse_file(
+        file: FileId,
+        options: &PreprocessorOptions,
+        sources: &mut SourceManager,
+    ) -> SyntaxTree {
+        let source = sources.text(file);
+        let lexer = Lexer::lex_with_options(
+            &source,
+            LexerOptions {
+                file,
+                ..LexerOptions::default()
+            },
+        );
+        let pp = Preprocessor::preprocess_with_sources(lexer, options, sources);
+        let parser = Parser::from_preprocessor(pp);
+        Self::from_parser(source.clone(), parser)
+    }
+
+    /// Parse the remaining tokens of `parser`, `source` is the text it reads
+    pub fn from_parser(source: Arc<str>, mut parser: Parser<'_>) -> SyntaxTree {
+        let root = SourceText::parse(&mut parser).unwrap_or_default();
+        let (tokens, diag) = parser.finish();
+        SyntaxTree {
+            source,
+            tokens: tokens.into_iter().map(ParsedToken::into_owned).collect(),
+            diag,
+            root,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn tokens(&self) -> &[ParsedToken<'static>] {
+        &self.tokens
+    }
+
+    pub fn token(&self, index: usize) -> &ParsedToken<'static> {
+        &self.tokens[index]
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diag
+    }
+
+    pub fn root(&self) -> &SourceText {
+        &self.root
+    }
+
+    /// Location of a node
+    pub fn span_of(&self, span: NodeSpan) -> Span {
+        span.resolve(&self.tokens)
+    }
+
+    /// Name of an identifier as written, escaped identifiers keep the `\`
+    pub fn text_of(&self, identifier: &Identifier) -> &str {
+        &self.tokens[identifier.token].text
+    }
+
+    /// Unsigned value of a number, `None` if it cannot be decoded or has x
+    /// or z bits, see `BitVector::to_u64`
+    pub fn value_of(&self, number: &Number) -> Option<u64> {
+        self.bit_vector(number, &mut vec![])?.to_u64()
+    }
+
+    /// Decoded bytes of a string, see `StringLiteral::decode`
+    pub fn string_value(&self, string: &StringLiteral) -> Vec<u8> {
+        StringLiteral::decode(&self.tokens[string.token].text)
+    }
+
+    /// 4-state value of a number, problems are added to `diag`
+    pub fn bit_vector(&self, number: &Number, diag: &mut Vec<Diagnostic>) -> Option<BitVector> {
+        let token = &self.tokens[number.token];
+        BitVector::parse(&token.text, token.span, diag)
+    }
+
+    /// See `Expression::constant_value`
+    pub fn constant_value(&self, expr: &Expression, diag: &mut Vec<Diagnostic>) -> Option<i64> {
+        expr.constant_value(&self.tokens, diag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{DataTypeOrImplicit, Expression};
+
+    #[test]
+    fn syntax_tree() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<SyntaxTree>();
+
+        let tree = SyntaxTree::parse(String::from(
+            "module top(input [7:0] a, output \\b~ );\nendmodule",
+        ));
+        assert!(tree.diagnostics().is_empty());
+        let header = &tree.root().modules[0].header;
+        assert_eq!(tree.text_of(&header.identifier), "top");
+        assert_eq!(tree.text_of(&header.ports.ports[1].1.identifier), "\\b~");
+        let port = &header.ports.ports[0].1;
+        let data = match &port.net_port_type.as_ref().unwrap().data_type_or_implicit {
+            DataTypeOrImplicit::ImplicitData(data) => data,
+            _ => panic!(),
+        };
+        let dimension = &data.dimensions[0];
+        match (&dimension.from, &dimension.to) {
+            (Some(Expression::Number(from)), Some(Expression::Number(to))) => {
+                assert_eq!(tree.value_of(from), Some(7));
+                assert_eq!(tree.value_of(to), Some(0));
+                let mut diag = vec![];
+                let value = tree.bit_vector(from, &mut diag).unwrap();
+                assert_eq!(value.to_i64(), Some(7));
+                assert!(diag.is_empty());
+            }
+            _ => panic!("{:?}", dimension),
+        }
+
+        assert_eq!(dimension.range(tree.tokens(), &mut vec![]), Some((7, 0)));
+
+        let span = tree.span_of(port.span);
+        assert_eq!(
+            &tree.source()[span.from.offset..=span.to.offset],
+            "input [7:0] a"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Standard system tasks and system functions, IEEE 1800-2017 clause 20 and 21
+
+use crate::diagnostic::Message;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+/// Value returned by a system function
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ReturnKind {
+    /// A 32-bit signed integer
+    Integer,
+    /// A single bit
+    Bit,
+    /// A 64-bit time value
+    Time,
+    Real,
+    String,
+    /// A vector whose width depends on the arguments, like `$signed`
+    Vector,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum SystemTfKind {
+    Task,
+    Function(ReturnKind),
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct SystemTf {
+    pub name: &'static str,
+    pub kind: SystemTfKind,
+    pub min_args: usize,
+    /// `None` for a variable number of arguments
+    pub max_args: Option<usize>,
+    /// Whether it may be called in a constant expression, 11.2.1
+    pub constant: bool,
+}
+
+const ANY: Option<usize> = None;
+
+use ReturnKind::{Bit, Integer, Real, Time, Vector};
+use SystemTfKind::*;
+
+const fn task(name: &'static str, min_args: usize, max_args: Option<usize>) -> SystemTf {
+    SystemTf {
+        name,
+        kind: Task,
+        min_args,
+        max_args,
+        constant: false,
+    }
+}
+
+const fn function(
+    name: &'static str,
+    ret: ReturnKind,
+    min_args: usize,
+    max_args: Option<usize>,
+) -> SystemTf {
+    SystemTf {
+        name,
+        kind: Function(ret),
+        min_args,
+        max_args,
+        constant: false,
+    }
+}
+
+const fn constant(
+    name: &'static str,
+    ret: ReturnKind,
+    min_args: usize,
+    max_args: Option<usize>,
+) -> SystemTf {
+    SystemTf {
+        name,
+        kind: Function(ret),
+        min_args,
+        max_args,
+        constant: true,
+    }
+}
+
+const SYSTEM_TFS: &[SystemTf] = &[
+    // 20.2 Simulation control tasks
+    task("$finish", 0, Some(1)),
+    task("$stop", 0, Some(1)),
+    task("$exit", 0, Some(0)),
+    // 20.3 Simulation time functions
+    function("$time", Time, 0, Some(0)),
+    function("$stime", Integer, 0, Some(0)),
+    function("$realtime", Real, 0, Some(0)),
+    // 20.4 Timescale tasks
+    task("$printtimescale", 0, Some(1)),
+    task("$timeformat", 0, Some(4)),
+    // 20.5 Conversion functions
+    constant("$rtoi", Integer, 1, Some(1)),
+    constant("$itor", Real, 1, Some(1)),
+    constant("$realtobits", Vector, 1, Some(1)),
+    constant("$bitstoreal", Real, 1, Some(1)),
+    constant("$shortrealtobits", Vector, 1, Some(1)),
+    constant("$bitstoshortreal", Real, 1, Some(1)),
+    constant("$signed", Vector, 1, Some(1)),
+    constant("$unsigned", Vector, 1, Some(1)),
+    function("$cast", Integer, 2, Some(2)),
+    // 20.6 Data query functions
+    constant("$bits", Integer, 1, Some(1)),
+    function("$typename", ReturnKind::String, 1, Some(1)),
+    constant("$isunbounded", Bit, 1, Some(1)),
+    // 20.7 Array query functions
+    constant("$dimensions", Integer, 1, Some(1)),
+    constant("$unpacked_dimensions", Integer, 1, Some(1)),
+    constant("$left", Integer, 1, Some(2)),
+    constant("$right", Integer, 1, Some(2)),
+    constant("$low", Integer, 1, Some(2)),
+    constant("$high", Integer, 1, Some(2)),
+    constant("$increment", Integer, 1, Some(2)),
+    constant("$size", Integer, 1, Some(2)),
+    // 20.8 Math functions
+    constant("$clog2", Integer, 1, Some(1)),
+    constant("$ln", Real, 1, Some(1)),
+    constant("$log10", Real, 1, Some(1)),
+    constant("$exp", Real, 1, Some(1)),
+    constant("$sqrt", Real, 1, Some(1)),
+    constant("$pow", Real, 2, Some(2)),
+    constant("$floor", Real, 1, Some(1)),
+    constant("$ceil", Real, 1, Some(1)),
+    constant("$sin", Real, 1, Some(1)),
+    constant("$cos", Real, 1, Some(1)),
+    constant("$tan", Real, 1, Some(1)),
+    constant("$asin", Real, 1, Some(1)),
+    constant("$acos", Real, 1, Some(1)),
+    constant("$atan", Real, 1, Some(1)),
+    constant("$atan2", Real, 2, Some(2)),
+    constant("$hypot", Real, 2, Some(2)),
+    constant("$sinh", Real, 1, Some(1)),
+    constant("$cosh", Real, 1, Some(1)),
+    constant("$tanh", Real, 1, Some(1)),
+    constant("$asinh", Real, 1, Some(1)),
+    constant("$acosh", Real, 1, Some(1)),
+    constant("$atanh", Real, 1, Some(1)),
+    // 20.9 Bit vector system functions
+    constant("$countbits", Integer, 2, ANY),
+    constant("$countones", Integer, 1, Some(1)),
+    constant("$onehot", Bit, 1, Some(1)),
+    constant("$onehot0", Bit, 1, Some(1)),
+    constant("$isunknown", Bit, 1, Some(1)),
+    // 20.10 Severity tasks
+    task("$fatal", 0, ANY),
+    task("$error", 0, ANY),
+    task("$warning", 0, ANY),
+    task("$info", 0, ANY),
+    // 20.12 Sampled value system functions
+    function("$sampled", Vector, 1, Some(1)),
+    function("$rose", Bit, 1, Some(2)),
+    function("$fell", Bit, 1, Some(2)),
+    function("$stable", Bit, 1, Some(2)),
+    function("$changed", Bit, 1, Some(2)),
+    function("$past", Vector, 1, Some(4)),
+    // 20.15 Probabilistic distribution functions
+    function("$random", Integer, 0, Some(1)),
+    function("$urandom", Integer, 0, Some(1)),
+    function("$urandom_range", Integer, 1, Some(2)),
+    function("$dist_uniform", Integer, 3, Some(3)),
+    function("$dist_normal", Integer, 3, Some(3)),
+    function("$dist_exponential", Integer, 2, Some(2)),
+    function("$dist_poisson", Integer, 2, Some(2)),
+    function("$dist_chi_square", Integer, 2, Some(2)),
+    function("$dist_t", Integer, 2, Some(2)),
+    function("$dist_erlang", Integer, 3, Some(3)),
+    // 21.2 Display system tasks
+    task("$display", 0, ANY),
+    task("$displayb", 0, ANY),
+    task("$displayh", 0, ANY),
+    task("$displayo", 0, ANY),
+    task("$write", 0, ANY),
+    task("$writeb", 0, ANY),
+    task("$writeh", 0, ANY),
+    task("$writeo", 0, ANY),
+    task("$strobe", 0, ANY),
+    task("$strobeb", 0, ANY),
+    task("$strobeh", 0, ANY),
+    task("$strobeo", 0, ANY),
+    task("$monitor", 0, ANY),
+    task("$monitorb", 0, ANY),
+    task("$monitorh", 0, ANY),
+    task("$monitoro", 0, ANY),
+    task("$monitoron", 0, Some(0)),
+    task("$monitoroff", 0, Some(0)),
+    // 21.3 File input/output system tasks and system functions
+    function("$fopen", Integer, 1, Some(2)),
+    task("$fclose", 1, Some(1)),
+    task("$fdisplay", 1, ANY),
+    task("$fwrite", 1, ANY),
+    task("$fstrobe", 1, ANY),
+    task("$fmonitor", 1, ANY),
+    task("$swrite", 1, ANY),
+    task("$sformat", 2, ANY),
+    function("$sformatf", ReturnKind::String, 1, ANY),
+    function("$fgetc", Integer, 1, Some(1)),
+    function("$ungetc", Integer, 2, Some(2)),
+    function("$fgets", Integer, 2, Some(2)),
+    function("$fscanf", Integer, 2, ANY),
+    function("$sscanf", Integer, 2, ANY),
+    function("$fread", Integer, 2, Some(4)),
+    function("$ftell", Integer, 1, Some(1)),
+    function("$fseek", Integer, 3, Some(3)),
+    function("$rewind", Integer, 1, Some(1)),
+    task("$fflush", 0, Some(1)),
+    function("$ferror", Integer, 2, Some(2)),
+    function("$feof", Integer, 1, Some(1)),
+    // 21.4 Loading memory array data from a file
+    task("$readmemb", 2, Some(4)),
+    task("$readmemh", 2, Some(4)),
+    // 21.5 Writing memory array data to a file
+    task("$writememb", 2, Some(4)),
+    task("$writememh", 2, Some(4)),
+    // 21.6 Command line input
+    function("$test$plusargs", Integer, 1, Some(1)),
+    function("$value$plusargs", Integer, 2, Some(2)),
+    // 21.7 Value change dump (VCD) files
+    task("$dumpfile", 0, Some(1)),
+    task("$dumpvars", 0, ANY),
+    task("$dumpoff", 0, Some(0)),
+    task("$dumpon", 0, Some(0)),
+    task("$dumpall", 0, Some(0)),
+    task("$dumplimit", 1, Some(1)),
+    task("$dumpflush", 0, Some(0)),
+];
+
+lazy_static! {
+    static ref REGISTRY: HashMap<&'static str, &'static SystemTf> =
+        SYSTEM_TFS.iter().map(|tf| (tf.name, tf)).collect();
+}
+
+/// Look up a standard system task or function by name, including the `$`
+pub fn lookup(name: &str) -> Option<&'static SystemTf> {
+    REGISTRY.get(name).copied()
+}
+
+/// All standard system tasks and functions
+pub fn all() -> &'static [SystemTf] {
+    SYSTEM_TFS
+}
+
+impl SystemTf {
+    pub fn is_task(&self) -> bool {
+        self.kind == Task
+    }
+
+    /// Whether `count` arguments are accepted
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min_args && self.max_args.is_none_or(|max| count <= max)
+    }
+
+    /// Human readable number of arguments
+    pub fn arity(&self) -> String {
+        match self.max_args {
+            Some(max) if max == self.min_args => format!("{}", max),
+            Some(max) => format!("{} to {}", self.min_args, max),
+            None => format!("at least {}", self.min_args),
+        }
+    }
+}
+
+/// Check a call of `name` with `count` arguments
+///
+/// `constant` is set when the call is part of a constant expression.
+/// Unknown names are not an error in every tool, callers decide the
+/// severity.
+pub fn check_call(name: &str, count: usize, constant: bool) -> Option<Message> {
+    let tf = match lookup(name) {
+        Some(tf) => tf,
+        None => return Some(Message::UnknownSystemTf(name.to_owned())),
+    };
+    if !tf.accepts(count) {
+        return Some(Message::SystemTfArguments(name.to_owned(), tf.arity()));
+    }
+    if constant && !tf.constant {
+        return Some(Message::NonConstantSystemTf(name.to_owned()));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        let clog2 = lookup("$clog2").unwrap();
+        assert_eq!(clog2.kind, Function(Integer));
+        assert!(clog2.constant);
+        assert!(lookup("$display").unwrap().is_task());
+        assert_eq!(lookup("$test$plusargs").unwrap().min_args, 1);
+        assert_eq!(lookup("$unknown"), None);
+        assert_eq!(REGISTRY.len(), all().len(), "duplicate names");
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(check_call("$clog2", 1, true), None);
+        assert_eq!(check_call("$display", 5, false), None);
+        assert_eq!(
+            check_call("$clog2", 2, true),
+            Some(Message::SystemTfArguments(
+                "$clog2".to_owned(),
+                "1".to_owned()
+            ))
+        );
+        assert_eq!(
+            check_call("$random", 0, true),
+            Some(Message::NonConstantSystemTf("$random".to_owned()))
+        );
+        assert_eq!(
+            check_call("$foo", 0, false),
+            Some(Message::UnknownSystemTf("$foo".to_owned()))
+        );
+        assert_eq!(
+            Message::SystemTfArguments("$size".to_owned(), lookup("$size").unwrap().arity())
+                .to_string(),
+            "Expected 1 to 2 arguments for $size"
+        );
+    }
+}
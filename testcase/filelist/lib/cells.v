@@ -0,0 +1,4 @@
+module lib_cell (
+    input wire a
+);
+endmodule
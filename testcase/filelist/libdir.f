@@ -0,0 +1,4 @@
+// modules found in a library directory
+-y testcase/filelist/ydir
++libext+.sv+.v
+testcase/filelist/rtl/chip.v
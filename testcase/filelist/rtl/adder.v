@@ -0,0 +1,8 @@
+`include "defs.svh"
+
+module adder (
+    input wire [`ADDER_WIDTH:0] a,
+    input wire [`ADDER_WIDTH:0] b,
+    output wire [`ADDER_WIDTH:0] o
+);
+endmodule
@@ -0,0 +1,6 @@
+module chip (
+    input wire a
+);
+    cell_a u0 (a);
+    missing_cell u1 (a);
+endmodule
@@ -0,0 +1,2 @@
+adder.v
+top.v
@@ -0,0 +1,4 @@
+module top (
+    input wire [`ADDER_WIDTH:0] a
+);
+endmodule
@@ -0,0 +1,7 @@
+// design filelist
++incdir+testcase/filelist/include
++define+WIDTH=4
+-bogus
+-F testcase/filelist/rtl/rtl.f
+testcase/filelist/rtl/missing.v
+-v testcase/filelist/lib/cells.v
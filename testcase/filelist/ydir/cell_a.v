@@ -0,0 +1,5 @@
+module cell_a (
+    input wire a
+);
+    cell_b u0 (a);
+endmodule
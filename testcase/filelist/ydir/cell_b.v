@@ -0,0 +1,4 @@
+module cell_b (
+    input wire a
+);
+endmodule
@@ -0,0 +1,20 @@
+module generate_adder #(parameter N = 4) (
+    input [N-1:0] a,
+    input [N-1:0] b,
+    output [N-1:0] s
+);
+
+    genvar i;
+    generate
+        for (i = 0; i < N; i = i + 1) begin : bits
+            assign s[i] = a[i] ^ b[i];
+        end
+    endgenerate
+
+    if (N > 8) begin
+        wire wide;
+    end else begin
+        wire narrow;
+    end
+
+endmodule
@@ -0,0 +1,5 @@
+module width (
+	input [WIDTH-1:0] a,
+	output [(2 ** 3) - 1 : 0] b [0:DEPTH - 1]
+);
+endmodule